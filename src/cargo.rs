@@ -56,8 +56,7 @@ pub async fn get_latest_version(mock_url: Option<String>) -> Result<String, Erro
             .clone())
     } else {
         let message = format!("Error: {:#?}", response.text().await);
-        let source = "get_latest_version response failure".to_string();
-        Err(Error { message, source })
+        Err(Error::new("get_latest_version response failure", &message))
     }
 }
 #[cfg(test)]
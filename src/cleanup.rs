@@ -0,0 +1,173 @@
+//! Cleanup that has to happen if tod is interrupted with Ctrl-C, such as removing half written temp files
+//! Closures are registered while the temp state exists and run by `until_interrupted` in `main`
+use crate::errors::Error;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex, MutexGuard, PoisonError};
+use tokio::fs;
+
+type Cleanup = Box<dyn FnOnce() + Send>;
+
+static REGISTRY: LazyLock<Registry> = LazyLock::new(Registry::default);
+
+#[derive(Default)]
+struct Registry {
+    cleanups: Mutex<HashMap<u64, Cleanup>>,
+    next_id: AtomicU64,
+}
+
+impl Registry {
+    fn cleanups(&self) -> MutexGuard<'_, HashMap<u64, Cleanup>> {
+        // A panic while holding the lock can't leave the map half updated, so carry on with it
+        self.cleanups.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn register(&self, cleanup: impl FnOnce() + Send + 'static) -> CleanupGuard<'_> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.cleanups().insert(id, Box::new(cleanup));
+        CleanupGuard { registry: self, id }
+    }
+
+    async fn until_interrupted<T>(
+        &self,
+        command: impl Future<Output = T>,
+        interrupt: impl Future<Output = std::io::Result<()>>,
+    ) -> Option<T> {
+        // Pinned out here so that the command, and the guards it holds, are still alive when the cleanups run
+        tokio::pin!(command);
+        tokio::select! {
+            biased;
+            result = &mut command => Some(result),
+            Ok(()) = interrupt => {
+                self.run_all();
+                None
+            }
+        }
+    }
+
+    fn run_all(&self) {
+        let pending = self
+            .cleanups()
+            .drain()
+            .map(|(_, cleanup)| cleanup)
+            .collect::<Vec<Cleanup>>();
+        for cleanup in pending {
+            cleanup();
+        }
+    }
+}
+
+/// Unregisters its cleanup when dropped, without running it
+pub struct CleanupGuard<'a> {
+    registry: &'a Registry,
+    id: u64,
+}
+
+impl Drop for CleanupGuard<'_> {
+    fn drop(&mut self) {
+        self.registry.cleanups().remove(&self.id);
+    }
+}
+
+/// Registers a closure to run on Ctrl-C, for as long as the returned guard is alive
+pub fn register(cleanup: impl FnOnce() + Send + 'static) -> CleanupGuard<'static> {
+    REGISTRY.register(cleanup)
+}
+
+/// Runs `command` until it finishes, or until `interrupt` fires such as on Ctrl-C.
+/// When interrupted the registered cleanups run before `command` is dropped and None is returned.
+/// `command` is polled first, so commands that handle Ctrl-C themselves get to finish
+pub async fn until_interrupted<T>(
+    command: impl Future<Output = T>,
+    interrupt: impl Future<Output = std::io::Result<()>>,
+) -> Option<T> {
+    REGISTRY.until_interrupted(command, interrupt).await
+}
+
+/// Writes a file by way of a temp file next to it, so that an interrupted write never leaves a truncated file behind
+/// The temp file is removed on Ctrl-C
+pub async fn write_file(path: &Path, contents: &str) -> Result<(), Error> {
+    let temp_path = temp_path(path);
+    let removed_path = temp_path.clone();
+    let _guard = register(move || {
+        let _ = std::fs::remove_file(removed_path);
+    });
+
+    fs::write(&temp_path, contents).await?;
+    fs::rename(&temp_path, path).await?;
+    Ok(())
+}
+
+fn temp_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".tmp");
+    path.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::sync::Arc;
+    use std::sync::atomic::AtomicBool;
+
+    #[test]
+    fn run_all_runs_registered_cleanups_only() {
+        let registry = Registry::default();
+        let kept = Arc::new(AtomicBool::new(false));
+        let dropped = Arc::new(AtomicBool::new(false));
+
+        let kept_flag = kept.clone();
+        let _guard = registry.register(move || kept_flag.store(true, Ordering::SeqCst));
+        let dropped_flag = dropped.clone();
+        drop(registry.register(move || dropped_flag.store(true, Ordering::SeqCst)));
+
+        registry.run_all();
+        assert!(kept.load(Ordering::SeqCst));
+        assert!(!dropped.load(Ordering::SeqCst));
+        assert!(registry.cleanups().is_empty());
+    }
+
+    #[tokio::test]
+    async fn until_interrupted_runs_cleanups_of_the_interrupted_command() {
+        let registry = Registry::default();
+        let cleaned = Arc::new(AtomicBool::new(false));
+        let cleaned_flag = cleaned.clone();
+        let command = async {
+            let _guard = registry.register(move || cleaned_flag.store(true, Ordering::SeqCst));
+            std::future::pending::<()>().await;
+        };
+
+        let result = registry.until_interrupted(command, async { Ok(()) }).await;
+        assert_eq!(result, None);
+        assert!(cleaned.load(Ordering::SeqCst));
+        assert!(registry.cleanups().is_empty());
+    }
+
+    #[tokio::test]
+    async fn until_interrupted_returns_the_result_of_a_finished_command() {
+        let registry = Registry::default();
+        let result = registry
+            .until_interrupted(async { "done" }, std::future::pending())
+            .await;
+        assert_eq!(result, Some("done"));
+    }
+
+    #[tokio::test]
+    async fn write_file_replaces_contents_without_leaving_temp_file() {
+        let dir = tempfile::tempdir().expect("temp dir should be created");
+        let path = dir.path().join("state.json");
+
+        write_file(&path, "first")
+            .await
+            .expect("write should succeed");
+        write_file(&path, "second")
+            .await
+            .expect("write should succeed");
+
+        let contents = fs::read_to_string(&path).await.expect("file should exist");
+        assert_eq!(contents, "second");
+        assert!(!temp_path(&path).exists());
+    }
+}
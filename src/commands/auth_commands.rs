@@ -1,11 +1,13 @@
 use crate::{
     config::{self, Config},
     errors::Error,
-    format, oauth,
+    format, input, oauth,
 };
 use clap::{Parser, Subcommand};
 use std::{io::ErrorKind, path::PathBuf};
 
+const LOGOUT: &str = "Log out";
+
 #[derive(Subcommand, Debug, Clone)]
 pub enum AuthCommands {
     #[clap(alias = "l")]
@@ -15,11 +17,22 @@ pub enum AuthCommands {
     #[clap(alias = "t")]
     /// (t) Save a Todoist developer API token directly to the config (non-interactive)
     Token(Token),
+
+    #[clap(alias = "o")]
+    /// (o) Remove stored credentials from the config
+    Logout(Logout),
 }
 
 #[derive(Parser, Debug, Clone)]
 pub struct Login {}
 
+#[derive(Parser, Debug, Clone)]
+pub struct Logout {
+    #[arg(short, long, default_value_t = false)]
+    /// Skip the logout confirmation
+    force: bool,
+}
+
 #[derive(Parser, Debug, Clone)]
 pub struct Token {
     /// Todoist developer API token from <https://todoist.com/prefs/integrations>
@@ -30,6 +43,25 @@ pub async fn login(config: &mut Config, _args: &Login) -> Result<String, Error>
     oauth::login(config, None).await
 }
 
+/// Removes the stored credentials from config, keeping projects and other settings
+pub async fn logout(config: &mut Config, args: &Logout) -> Result<String, Error> {
+    let Logout { force } = args;
+
+    if !force {
+        let options = vec![input::CANCEL, LOGOUT];
+        let desc = "Remove stored credentials from config?";
+        if input::select(desc, options, config.mock_select)? == input::CANCEL {
+            return Ok("Cancelled".into());
+        }
+    }
+
+    config.clear_token().await?;
+    Ok(format::green_string(&format!(
+        "✓ Logged out, credentials removed from {}",
+        config.path.display()
+    )))
+}
+
 /// Loads the config for an explicit auth command, creating a valid empty config if needed.
 ///
 /// Explicit auth commands choose their own authentication method, so config creation here must
@@ -66,10 +98,62 @@ pub async fn token(config_path: Option<PathBuf>, args: &Token) -> Result<String,
 
 #[cfg(test)]
 mod tests {
-    use super::load_or_create_config;
+    use super::{Logout, load_or_create_config, logout};
     use crate::config::Config;
+    use crate::test;
     use tempfile::tempdir;
 
+    async fn saved_config_with_token(path: &std::path::Path) -> Config {
+        let mut config = Config::new(None, path.to_path_buf())
+            .await
+            .expect("config should be created")
+            .with_token("existing-token")
+            .with_timezone("UTC")
+            .with_projects(vec![test::fixtures::project()]);
+        config
+            .touch_file()
+            .await
+            .expect("config file should be created");
+        config.save().await.expect("config should save");
+        config
+    }
+
+    #[tokio::test]
+    async fn logout_force_removes_token_and_keeps_projects() {
+        let dir = tempdir().expect("temp dir should be created");
+        let path = dir.path().join("tod.cfg");
+        let mut config = saved_config_with_token(&path).await;
+
+        let result = logout(&mut config, &Logout { force: true }).await;
+        assert!(
+            result
+                .expect("logout should succeed")
+                .contains("Logged out")
+        );
+
+        let saved = Config::load(&path)
+            .await
+            .expect("config should be readable");
+        assert_eq!(saved.token, None);
+        let projects = saved.projects().await.expect("projects should load");
+        assert_eq!(projects, vec![test::fixtures::project()]);
+    }
+
+    #[tokio::test]
+    async fn logout_can_be_cancelled() {
+        let dir = tempdir().expect("temp dir should be created");
+        let path = dir.path().join("tod.cfg");
+        let mut config = saved_config_with_token(&path).await.mock_select(0);
+
+        let result = logout(&mut config, &Logout { force: false }).await;
+        assert_eq!(result, Ok("Cancelled".to_string()));
+
+        let saved = Config::load(&path)
+            .await
+            .expect("config should be readable");
+        assert_eq!(saved.token, Some("existing-token".to_string()));
+    }
+
     #[tokio::test]
     async fn load_or_create_config_creates_empty_config_without_authentication() {
         let dir = tempdir().expect("temp dir should be created");
@@ -3,17 +3,23 @@ use std::fmt::Write;
 
 use crate::{
     cargo::{self, Version},
-    config::{self, Config},
-    errors::Error,
-    update,
+    config::{self, Config, diff},
+    debug,
+    errors::{Error, ErrorKind},
+    format, input,
+    lists::Flag,
+    projects, time, todoist, update,
 };
 use serde_json::Value;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tokio::io::AsyncWriteExt;
 
 // Values pulled from Cargo.toml
 const NAME: &str = env!("CARGO_PKG_NAME");
 const VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Environment variable that `config export-env` sets, as used by other Todoist tools
+const TOKEN_ENV_VAR: &str = "TODOIST_API_TOKEN";
+
 // Verbose values set at build time
 const BUILD_TARGET: &str = env!("BUILD_TARGET");
 const BUILD_PROFILE: &str = env!("BUILD_PROFILE");
@@ -32,6 +38,14 @@ pub enum ConfigCommands {
     /// Validate the configuration file and optionally remove invalid values.
     Check(ConfigCheck),
 
+    /// Check that the config loads, the token works and the projects in it still exist in Todoist.
+    /// Exits with an error if the config or the token is unusable.
+    Validate(ConfigValidate),
+
+    #[clap(alias = "m")]
+    /// (m) Upgrade the configuration file to the current schema, filling in missing values with defaults
+    Migrate(ConfigMigrate),
+
     /// (r) Deletes the configuration file (if present). Errors if the file does not exist.
     #[clap(alias = "r")]
     Reset(ConfigReset),
@@ -47,6 +61,26 @@ pub enum ConfigCommands {
     #[clap(alias = "e")]
     /// (e) Interactively edit the configuration file
     Edit(Edit),
+
+    #[clap(alias = "dp")]
+    /// (dp) Set the project that quick add puts tasks into when no project is given
+    SetDefaultProject(SetDefaultProject),
+
+    #[clap(alias = "tk")]
+    /// (tk) Check a Todoist API token with Todoist and save it to the config, creating the config if needed
+    SetToken(SetToken),
+
+    #[clap(alias = "env")]
+    /// (env) Print a shell command that sets TODOIST_API_TOKEN to the token in config, for use with eval or source
+    ExportEnv(ExportEnv),
+
+    #[clap(alias = "dr")]
+    /// (dr) List projects that are in config but not in Todoist, and in Todoist but not in config
+    DiffRemote(DiffRemote),
+
+    #[clap(alias = "fq")]
+    /// (fq) Send the task creations and completions that were queued while Todoist could not be reached
+    FlushQueue(FlushQueue),
 }
 #[derive(Parser, Debug, Clone)]
 pub struct CheckVersion {
@@ -71,6 +105,22 @@ pub struct ConfigOpen {}
 #[derive(Parser, Debug, Clone)]
 pub struct ConfigCheck {}
 
+#[derive(Parser, Debug, Clone)]
+pub struct ConfigValidate {}
+
+#[derive(Parser, Debug, Clone)]
+pub struct ConfigMigrate {}
+
+#[derive(Parser, Debug, Clone)]
+pub struct FlushQueue {}
+
+#[derive(Parser, Debug, Clone)]
+pub struct DiffRemote {
+    #[arg(long, default_value_t = false)]
+    /// Print the projects as a JSON object with stale and unimported arrays of id and name
+    json: bool,
+}
+
 #[derive(Parser, Debug, Clone)]
 pub struct About {}
 
@@ -78,10 +128,59 @@ pub struct About {}
 pub struct Edit {}
 
 #[derive(Parser, Debug, Clone)]
-pub struct SetTimezone {
+pub struct SetToken {
     #[arg(short, long)]
+    /// Todoist developer API token, read from stdin when not given
+    token: Option<String>,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct ExportEnv {
+    #[arg(short, long, value_enum, default_value_t = Shell::Bash)]
+    /// Shell syntax to print
+    shell: Shell,
+
+    #[arg(long, default_value_t = false)]
+    /// Print the real token, without it only the first characters are shown
+    show_token: bool,
+}
+
+/// Shells that `config export-env` can print for
+#[derive(clap::ValueEnum, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct SetDefaultProject {
+    #[arg(short, long)]
+    /// Project to use, selected from the projects in config when not given
+    project: Option<String>,
+
+    #[arg(short, long, default_value_t = false)]
+    /// Remove the default project so quick add uses the inbox
+    clear: bool,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct SetTimezone {
+    #[arg(short, long, conflicts_with_all = ["list", "select"])]
     /// Explicitly set a `TimeZone`, i.e. "Canada/Pacific")
     timezone: Option<String>,
+
+    #[arg(short, long, default_value_t = false, conflicts_with = "select")]
+    /// Print the valid IANA timezone names without changing anything
+    list: bool,
+
+    #[arg(requires = "list")]
+    /// Only list timezone names containing this text, i.e. "europe"
+    filter: Option<String>,
+
+    #[arg(short, long, default_value_t = false)]
+    /// Pick the timezone from the list of valid names instead of using the Todoist timezone
+    select: bool,
 }
 pub async fn check_version(args: &CheckVersion, mock_url: Option<String>) -> Result<String, Error> {
     let CheckVersion { force, repo } = args;
@@ -220,17 +319,159 @@ where
         return Ok("Config check completed. No changes saved.".to_string());
     }
 
-    let string = serde_json::to_string_pretty(&repaired.value)?;
+    write_config_value(&path, &repaired.value).await?;
+
+    Ok(format!("Removed invalid config values: {field_list}"))
+}
+
+/// Runs each check in turn and prints a line for each, stopping early when later checks can't run.
+/// Returns an error holding the checklist when the config or token is unusable.
+pub async fn validate(cli_config_path: Option<PathBuf>) -> Result<String, Error> {
+    let path = resolve_config_path(cli_config_path).await?;
+    let mut checklist = Vec::new();
+    let critical_failure = |mut checklist: Vec<String>, line: String, kind: ErrorKind| {
+        checklist.push(format!("✗ {line}"));
+        Err(Error::new("config_validate", &checklist.join("\n")).with_kind(kind))
+    };
+
+    if !tokio::fs::try_exists(&path).await? {
+        return critical_failure(
+            checklist,
+            format!(
+                "No config file at {}, run 'tod auth login' to create one",
+                path.display()
+            ),
+            ErrorKind::NotFound,
+        );
+    }
+    let config = match Config::load(&path).await {
+        Ok(mut config) => {
+            config.path = path.clone();
+            config
+        }
+        Err(e) => {
+            return critical_failure(
+                checklist,
+                format!(
+                    "Config file could not be read, run 'tod config check' to repair it. {}",
+                    e.message
+                ),
+                ErrorKind::General,
+            );
+        }
+    };
+    checklist.push(format!("✓ Config file at {} loads", path.display()));
+
+    if config
+        .token
+        .as_ref()
+        .is_none_or(|token| token.trim().is_empty())
+    {
+        return critical_failure(
+            checklist,
+            "No API token, run 'tod auth login' or 'tod config set-token'".to_string(),
+            ErrorKind::Auth,
+        );
+    }
+    if let Err(e) = todoist::get_user_data(&config).await {
+        return critical_failure(
+            checklist,
+            format!("Todoist did not accept the API token. {}", e.message),
+            e.kind,
+        );
+    }
+    checklist.push("✓ Todoist accepted the API token".to_string());
+
+    let configured = config.projects().await?.len();
+    match projects::missing_projects(&config).await {
+        Ok(missing) if missing.is_empty() => checklist.push(format!(
+            "✓ All {configured} project(s) in config exist in Todoist"
+        )),
+        Ok(missing) => {
+            let names = missing
+                .iter()
+                .map(|project| format!("'{}'", project.name))
+                .collect::<Vec<String>>()
+                .join(", ");
+            checklist.push(format!(
+                "✗ {} project(s) in config no longer exist in Todoist: {names}. Run 'tod project remove --auto' to remove them",
+                missing.len()
+            ));
+        }
+        Err(e) => checklist.push(format!("✗ Could not fetch projects. {}", e.message)),
+    }
+
+    Ok(checklist.join("\n"))
+}
+
+pub async fn migrate(cli_config_path: Option<PathBuf>) -> Result<String, Error> {
+    let path = resolve_config_path(cli_config_path).await?;
+
+    if !tokio::fs::try_exists(&path).await? {
+        return Err(Error::new(
+            "config_migrate",
+            &format!(
+                "No config file found at {}. Run 'tod auth login' to initialize tod.",
+                path.display()
+            ),
+        ));
+    }
+
+    let json = tokio::fs::read_to_string(&path).await?;
+    let original: Value = serde_json::from_str(&json).map_err(|e| {
+        Error::new(
+            "config_migrate",
+            &format!(
+                "Config file at {} could not be parsed as JSON:\n{e}",
+                path.display()
+            ),
+        )
+    })?;
+
+    // Unknown fields are dropped rather than failing, they show up in the diff
+    let repaired = repair_unknown_fields(original.clone()).map_err(|e| {
+        Error::new(
+            "config_migrate",
+            &format!(
+                "Config file at {} has invalid values, run 'tod config check' or 'tod config reset':\n{e}",
+                path.display()
+            ),
+        )
+    })?;
+    let config: Config = serde_json::from_value(repaired.value)?;
+    let mut config = config.with_default_sort_order();
+    config.config_version = Some(config::CONFIG_VERSION);
+    let migrated = serde_json::to_value(&config)?;
+
+    let changes = diff::changes(&original, &migrated);
+    if changes.is_empty() {
+        return Ok(format!(
+            "Config file at {} is already up to date (version {}).",
+            path.display(),
+            config::CONFIG_VERSION
+        ));
+    }
+
+    write_config_value(&path, &migrated).await?;
+    Ok(format!(
+        "Migrated config file at {} to version {}:\n{}",
+        path.display(),
+        config::CONFIG_VERSION,
+        changes.join("\n")
+    ))
+}
+
+async fn write_config_value(path: &Path, value: &Value) -> Result<(), Error> {
+    let string = serde_json::to_string_pretty(value)?;
     tokio::fs::OpenOptions::new()
         .write(true)
         .read(true)
         .truncate(true)
-        .open(&path)
+        .open(path)
         .await?
         .write_all(string.as_bytes())
         .await?;
-
-    Ok(format!("Removed invalid config values: {field_list}"))
+    Ok(())
 }
 
 struct RepairedConfig {
@@ -317,7 +558,37 @@ fn confirm(message: &str, default: bool) -> Result<bool, Error> {
         .map_err(Error::from)
 }
 
-pub async fn set_timezone(config: Config, _args: &SetTimezone) -> Result<String, Error> {
+pub async fn set_timezone(config: Config, args: &SetTimezone) -> Result<String, Error> {
+    let SetTimezone {
+        timezone,
+        list,
+        filter,
+        select,
+    } = args;
+
+    if *list {
+        return list_timezones(filter.as_deref());
+    }
+    let timezone = match timezone {
+        Some(timezone) => Some(timezone.clone()),
+        None if *select => {
+            let names = time::timezone_names(None);
+            let name = input::fuzzy_select(
+                input::TIMEZONE,
+                names,
+                config.fuzzy_select(),
+                config.mock_select,
+            )?;
+            Some(name.to_string())
+        }
+        None => None,
+    };
+    if let Some(timezone) = timezone {
+        let updated_config = config.set_timezone_to(&timezone).await?;
+        let tz = updated_config.get_timezone()?;
+        return Ok(format!("Timezone set successfully to: {tz}"));
+    }
+
     if config
         .token
         .as_ref()
@@ -341,6 +612,135 @@ pub async fn set_timezone(config: Config, _args: &SetTimezone) -> Result<String,
     }
 }
 
+fn list_timezones(filter: Option<&str>) -> Result<String, Error> {
+    let names = time::timezone_names(filter);
+    if names.is_empty() {
+        let filter = filter.unwrap_or_default();
+        return Err(Error::new(
+            "config set-timezone",
+            &format!("No timezones contain '{filter}'"),
+        ));
+    }
+    Ok(names.join("\n"))
+}
+
+pub async fn set_default_project(
+    mut config: Config,
+    args: &SetDefaultProject,
+) -> Result<String, Error> {
+    let SetDefaultProject { project, clear } = args;
+
+    if *clear {
+        config.set_default_project(None).await?;
+        return Ok("Default project cleared, quick add will use the inbox".to_string());
+    }
+
+    let project = match super::fetch_project(project.as_deref(), &config).await? {
+        Flag::Project(project) => project,
+        Flag::Filter(_) => unreachable!(),
+    };
+    config.set_default_project(Some(&project)).await?;
+    Ok(format!("Default project set to: {}", project.name))
+}
+
+/// Prints the token as a shell variable assignment, only ever to stdout
+pub fn export_env(config: &Config, args: &ExportEnv) -> Result<String, Error> {
+    let ExportEnv { shell, show_token } = args;
+    let Some(token) = config
+        .token
+        .as_deref()
+        .filter(|token| !token.trim().is_empty())
+    else {
+        return Err(Error::new(
+            "config export-env",
+            "No auth present - run \"tod auth login\"",
+        ));
+    };
+    let value = if *show_token {
+        token.to_string()
+    } else {
+        debug::redact_token(token)
+    };
+    let line = match shell {
+        Shell::Bash | Shell::Zsh => format!("export {TOKEN_ENV_VAR}='{value}'"),
+        Shell::Fish => format!("set -gx {TOKEN_ENV_VAR} '{value}'"),
+    };
+    if *show_token {
+        Ok(line)
+    } else {
+        Ok(format!(
+            "{line}\n# The token is redacted, add --show-token to print it"
+        ))
+    }
+}
+
+pub async fn diff_remote(config: &Config, args: &DiffRemote) -> Result<String, Error> {
+    let DiffRemote { json } = args;
+    projects::diff_remote(config, *json).await
+}
+
+/// Sends the queued requests, erroring when Todoist still can't take them so that scripts can retry
+pub async fn flush_queue(config: &Config) -> Result<String, Error> {
+    let result = todoist::flush_queue(config).await?;
+    if result.is_unchanged() && result.remaining > 0 {
+        let kind = result
+            .stopped_by
+            .as_ref()
+            .map_or(ErrorKind::Network, |error| error.kind);
+        return Err(Error::new("config flush-queue", &result.summary()).with_kind(kind));
+    }
+    Ok(result.summary())
+}
+
+/// Saves a developer API token without any prompts, once Todoist has accepted it
+pub async fn set_token(config_path: Option<PathBuf>, args: &SetToken) -> Result<String, Error> {
+    let token = match &args.token {
+        Some(token) => token.clone(),
+        // Anything that isn't UTF-8 fails the format check below
+        None => String::from_utf8_lossy(&input::read_stdin().await?).into_owned(),
+    };
+    let token = check_token_format(&token)?;
+    let config = super::auth_commands::load_or_create_config(config_path).await?;
+    save_checked_token(config, token).await
+}
+
+/// Trims the token and rejects it when it can't be a developer token, before calling Todoist
+fn check_token_format(token: &str) -> Result<&str, Error> {
+    let token = token.trim();
+    let length = token.chars().count();
+    if length != debug::TOKEN_LENGTH || !token.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(Error::new(
+            "config set-token",
+            &format!(
+                "Expected a token of {} letters and numbers, got {length} characters. Copy it from https://todoist.com/prefs/integrations",
+                debug::TOKEN_LENGTH
+            ),
+        )
+        .with_kind(ErrorKind::Auth));
+    }
+    Ok(token)
+}
+
+async fn save_checked_token(config: Config, token: &str) -> Result<String, Error> {
+    // Without the OAuth refresh token a rejected token fails instead of being swapped for a refreshed one
+    let mut candidate = config.with_token(token);
+    candidate.refresh_token = None;
+    candidate.token_expires_at = None;
+    if let Err(e) = todoist::get_user_data(&candidate).await {
+        return Err(Error::new(
+            "config set-token",
+            &format!("Todoist did not accept the token, it was not saved. {e}"),
+        )
+        .with_kind(e.kind));
+    }
+    let path = config.path.clone();
+    config.set_developer_token(token).await?;
+    Ok(format::green_string(&format!(
+        "✓ API token checked and saved to {}",
+        path.display()
+    )))
+}
+
 pub async fn edit(config: Config, _args: &Edit) -> Result<String, Error> {
     config.edit_interactive().await
 }
@@ -360,6 +760,159 @@ mod tests {
     use mockito::Server;
     use tempfile::tempdir;
 
+    const VALID_TOKEN: &str = "0123456789abcdef0123456789abcdef01234567";
+
+    #[test]
+    fn check_token_format_rejects_wrong_length() {
+        assert_eq!(
+            check_token_format(&format!(" {VALID_TOKEN}\n")),
+            Ok(VALID_TOKEN)
+        );
+
+        let error = check_token_format("abc123").expect_err("short token should be rejected");
+        assert_eq!(error.kind, ErrorKind::Auth);
+        assert!(error.message.contains("got 6 characters"));
+
+        let with_symbol = format!("{}!", &VALID_TOKEN[1..]);
+        assert!(check_token_format(&with_symbol).is_err());
+    }
+
+    #[tokio::test]
+    async fn validate_flags_projects_missing_from_todoist() {
+        let mut server = Server::new_async().await;
+        let user_mock = server
+            .mock("GET", "/api/v1/user")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::User.read().await)
+            .create_async()
+            .await;
+        let projects_mock = server
+            .mock("GET", "/api/v1/projects?limit=200")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::NewProjects.read().await)
+            .create_async()
+            .await;
+
+        let config = crate::test::fixtures::config()
+            .await
+            .with_mock_url(server.url())
+            .create()
+            .await
+            .expect("config should be created");
+
+        let result = validate(Some(config.path.clone()))
+            .await
+            .expect("stale projects are not critical");
+        let lines = result.lines().collect::<Vec<&str>>();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("✓ Config file at"));
+        assert_eq!(lines[1], "✓ Todoist accepted the API token");
+        assert_eq!(
+            lines[2],
+            "✗ 1 project(s) in config no longer exist in Todoist: 'myproject'. Run 'tod project remove --auto' to remove them"
+        );
+        user_mock.assert();
+        projects_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn validate_fails_when_token_is_rejected() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api/v1/user")
+            .with_status(401)
+            .create_async()
+            .await;
+
+        let config = crate::test::fixtures::config()
+            .await
+            .with_mock_url(server.url())
+            .create()
+            .await
+            .expect("config should be created");
+
+        let error = validate(Some(config.path.clone()))
+            .await
+            .expect_err("rejected token should fail validation");
+        assert_eq!(error.source, "config_validate");
+        assert_eq!(error.kind, ErrorKind::Auth);
+        assert!(error.message.contains("✓ Config file at"));
+        assert!(
+            error
+                .message
+                .contains("✗ Todoist did not accept the API token")
+        );
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn validate_fails_without_config_file() {
+        let dir = tempdir().expect("should create temp dir");
+        let error = validate(Some(dir.path().join("missing.cfg")))
+            .await
+            .expect_err("missing config should fail validation");
+        assert!(error.message.starts_with("✗ No config file at"));
+    }
+
+    #[tokio::test]
+    async fn save_checked_token_saves_accepted_token() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api/v1/user")
+            .match_header("authorization", format!("Bearer {VALID_TOKEN}").as_str())
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::User.read().await)
+            .create_async()
+            .await;
+
+        let config = crate::test::fixtures::config()
+            .await
+            .with_mock_url(server.url())
+            .create()
+            .await
+            .expect("config should be created");
+        let path = config.path.clone();
+
+        let result = save_checked_token(config, VALID_TOKEN).await;
+        assert!(result.expect("token should be saved").contains("saved to"));
+        let saved = Config::load(&path)
+            .await
+            .expect("config should be readable");
+        assert_eq!(saved.token.as_deref(), Some(VALID_TOKEN));
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn save_checked_token_keeps_old_token_when_rejected() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api/v1/user")
+            .with_status(401)
+            .create_async()
+            .await;
+
+        let config = crate::test::fixtures::config()
+            .await
+            .with_mock_url(server.url())
+            .create()
+            .await
+            .expect("config should be created");
+        let path = config.path.clone();
+
+        let error = save_checked_token(config, VALID_TOKEN)
+            .await
+            .expect_err("rejected token should not be saved");
+        assert_eq!(error.kind, ErrorKind::Auth);
+        let saved = Config::load(&path)
+            .await
+            .expect("config should be readable");
+        assert_eq!(saved.token.as_deref(), Some("alreadycreated"));
+        mock.assert();
+    }
+
     #[tokio::test]
     async fn test_config_check_removes_unknown_key_when_confirmed() {
         let dir = tempdir().expect("temp dir should be created");
@@ -588,9 +1141,17 @@ mod tests {
     async fn test_set_timezone_requires_auth() {
         let config = Config::default();
 
-        let error = set_timezone(config, &SetTimezone { timezone: None })
-            .await
-            .expect_err("set-timezone should fail when no auth token is present");
+        let error = set_timezone(
+            config,
+            &SetTimezone {
+                timezone: None,
+                list: false,
+                filter: None,
+                select: false,
+            },
+        )
+        .await
+        .expect_err("set-timezone should fail when no auth token is present");
 
         assert_eq!(error.source, "config set-timezone");
         assert!(
@@ -600,4 +1161,139 @@ mod tests {
             "error should guide user to auth login"
         );
     }
+
+    #[tokio::test]
+    async fn test_export_env_redacts_unless_shown() {
+        let config = crate::test::fixtures::config().await;
+        let args = |shell, show_token| ExportEnv { shell, show_token };
+
+        assert_eq!(
+            export_env(&config, &args(Shell::Bash, true)),
+            Ok("export TODOIST_API_TOKEN='alreadycreated'".to_string())
+        );
+        assert_eq!(
+            export_env(&config, &args(Shell::Fish, true)),
+            Ok("set -gx TODOIST_API_TOKEN 'alreadycreated'".to_string())
+        );
+        let redacted = export_env(&config, &args(Shell::Zsh, false)).expect("should export");
+        assert!(redacted.starts_with("export TODOIST_API_TOKEN='alrexxxxxxxxxx'\n"));
+        assert!(!redacted.contains("alreadycreated"));
+
+        let error = export_env(&Config::default(), &args(Shell::Bash, true))
+            .expect_err("export-env should fail without a token");
+        assert_eq!(error.source, "config export-env");
+    }
+
+    #[tokio::test]
+    async fn test_set_timezone_lists_and_validates_zones() {
+        let dir = tempdir().expect("temp dir should be created");
+        let config = crate::test::fixtures::config()
+            .await
+            .with_path(dir.path().join("tod.cfg"))
+            .mock_select(0)
+            .create()
+            .await
+            .expect("config should be created");
+        let args = |timezone: Option<&str>, list: bool, filter: Option<&str>| SetTimezone {
+            timezone: timezone.map(String::from),
+            list,
+            filter: filter.map(String::from),
+            select: false,
+        };
+
+        let listed = set_timezone(config.clone(), &args(None, true, Some("berlin"))).await;
+        assert_eq!(listed, Ok("Europe/Berlin".to_string()));
+        let error = set_timezone(config.clone(), &args(None, true, Some("Atlantis")))
+            .await
+            .expect_err("unknown filter should fail");
+        assert_eq!(error.message, "No timezones contain 'Atlantis'");
+
+        let error = set_timezone(config.clone(), &args(Some("Mars/Olympus"), false, None))
+            .await
+            .expect_err("invalid timezone should be rejected");
+        assert_eq!(error.source, "config set-timezone");
+        let saved = Config::load(&config.path)
+            .await
+            .expect("saved config should load");
+        assert_eq!(saved.get_timezone(), config.get_timezone());
+
+        let result = set_timezone(config.clone(), &args(Some("Europe/Berlin"), false, None)).await;
+        assert_eq!(
+            result,
+            Ok("Timezone set successfully to: Europe/Berlin".to_string())
+        );
+        let saved = Config::load(&config.path)
+            .await
+            .expect("saved config should load");
+        assert_eq!(saved.get_timezone(), Ok("Europe/Berlin".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_set_default_project_selects_from_config_projects() {
+        let dir = tempdir().expect("temp dir should be created");
+        let config = crate::test::fixtures::config()
+            .await
+            .with_path(dir.path().join("tod.cfg"))
+            .mock_select(0)
+            .create()
+            .await
+            .expect("config should be created");
+        let args = SetDefaultProject {
+            project: None,
+            clear: false,
+        };
+
+        let result = set_default_project(config.clone(), &args).await;
+        assert_eq!(result, Ok("Default project set to: myproject".to_string()));
+
+        let saved = Config::load(&config.path)
+            .await
+            .expect("saved config should load");
+        assert_eq!(saved.default_project, Some("123".to_string()));
+
+        let args = SetDefaultProject {
+            project: None,
+            clear: true,
+        };
+        set_default_project(saved, &args)
+            .await
+            .expect("clearing default project should succeed");
+        let saved = Config::load(&config.path)
+            .await
+            .expect("saved config should load");
+        assert_eq!(saved.default_project, None);
+    }
+
+    #[tokio::test]
+    async fn test_config_migrate_fills_defaults_and_is_idempotent() {
+        let dir = tempdir().expect("temp dir should be created");
+        let path = dir.path().join("tod.cfg");
+        let contents = serde_json::json!({
+            "path": path,
+            "timezone": "UTC",
+            "unknown_key": []
+        })
+        .to_string();
+        tokio::fs::write(&path, contents)
+            .await
+            .expect("config should be written");
+
+        let response = migrate(Some(path.clone()))
+            .await
+            .expect("config migrate should succeed");
+        assert!(response.contains("to version 1"));
+        assert!(response.contains("+ config_version: 1"));
+        assert!(response.contains("+ bell_on_failure: true"));
+        assert!(response.contains("- unknown_key: []"));
+
+        let migrated = Config::load(&path)
+            .await
+            .expect("migrated config should load");
+        assert_eq!(migrated.config_version, Some(config::CONFIG_VERSION));
+
+        let response = migrate(Some(path.clone()))
+            .await
+            .expect("second migrate should succeed");
+        assert!(response.contains("already up to date"));
+    }
 }
@@ -0,0 +1,253 @@
+use crate::{config::Config, errors::Error, format, input, labels, todoist};
+use clap::{Parser, Subcommand};
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum LabelCommands {
+    #[clap(alias = "r")]
+    /// (r) Rename a label in Todoist, updating every task that has it
+    Rename(Rename),
+
+    #[clap(alias = "d")]
+    /// (d) Delete a label in Todoist, removing it from every task that has it
+    Delete(Delete),
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct Rename {
+    #[arg(long)]
+    /// Name of the label to rename
+    from: String,
+
+    #[arg(long)]
+    /// New name for the label
+    to: String,
+
+    #[arg(long, default_value_t = false)]
+    /// Skip the confirmation showing how many tasks will change
+    force: bool,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct Delete {
+    #[arg(short, long)]
+    /// Name of the label to delete
+    label: String,
+
+    #[arg(long, default_value_t = false)]
+    /// Skip the confirmation showing how many tasks have the label
+    force: bool,
+}
+
+pub async fn rename(config: &mut Config, args: &Rename) -> Result<String, Error> {
+    let Rename { from, to, force } = args;
+    let to = to.trim();
+    if to.is_empty() {
+        return Err(Error::new("label_rename", "--to can't be empty"));
+    }
+    let all_labels = labels::get_labels(config, true).await?;
+    let label = labels::find_label(&all_labels, from)?;
+    if all_labels.iter().any(|label| label.name == to) {
+        return Err(Error::new(
+            "label_rename",
+            &format!("Label '{to}' already exists, pick another name"),
+        ));
+    }
+
+    let count = labeled_task_count(config, &label.name).await?;
+    let desc = format!("Rename '{from}' to '{to}' on {count} task(s)");
+    if !super::confirm_if_over(config, count, *force, &desc, input::RENAME)? {
+        return Ok("Cancelled".into());
+    }
+
+    todoist::rename_label(config, &label, to, true).await?;
+    if config.rename_label(&label.name, to) {
+        config.save().await?;
+    }
+    Ok(format::green_string(&format!(
+        "Renamed label '{from}' to '{to}' on {count} task(s)"
+    )))
+}
+
+pub async fn delete(config: &mut Config, args: &Delete) -> Result<String, Error> {
+    let Delete { label, force } = args;
+    let all_labels = labels::get_labels(config, true).await?;
+    let label = labels::find_label(&all_labels, label)?;
+
+    let count = labeled_task_count(config, &label.name).await?;
+    let desc = format!("Delete '{}' from {count} task(s)", label.name);
+    if !super::confirm_if_over(config, count, *force, &desc, input::DELETE)? {
+        return Ok("Cancelled".into());
+    }
+
+    todoist::delete_label(config, &label, true).await?;
+    if config.remove_label(&label.name) {
+        config.save().await?;
+    }
+    Ok(format::green_string(&format!(
+        "Deleted label '{}' from {count} task(s)",
+        label.name
+    )))
+}
+
+/// Number of active tasks that have the label
+async fn labeled_task_count(config: &Config, name: &str) -> Result<usize, Error> {
+    let (_, tasks) = todoist::all_tasks_by_filter(config, &label_filter(name), None).await?;
+    Ok(tasks.len())
+}
+
+/// Todoist filter for a label, with spaces and the characters filters give a meaning to escaped by a backslash
+fn label_filter(name: &str) -> String {
+    let mut filter = String::from("@");
+    for c in name.chars() {
+        if c.is_whitespace() || "&|!(),\\".contains(c) {
+            filter.push('\\');
+        }
+        filter.push(c);
+    }
+    filter
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::{self, responses::ResponseFromFile};
+
+    #[tokio::test]
+    async fn rename_renames_existing_label_after_counting_tasks() {
+        let mut server = mockito::Server::new_async().await;
+        let labels_mock = server
+            .mock("GET", "/api/v1/labels?limit=200")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::Labels.read().await)
+            .expect(2)
+            .create_async()
+            .await;
+        let tasks_mock = server
+            .mock("GET", "/api/v1/tasks/filter?query=%40345&limit=200")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::TodayTasks.read().await)
+            .create_async()
+            .await;
+        let rename_mock = server
+            .mock("POST", "/api/v1/labels/123")
+            .match_body(mockito::Matcher::Json(
+                serde_json::json!({"name": "errands"}),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::Label.read().await)
+            .create_async()
+            .await;
+
+        let mut config = test::fixtures::config()
+            .await
+            .with_mock_url(server.url())
+            .create()
+            .await
+            .expect("config should be created");
+        config.label_projects = Some(
+            [("345".to_string(), "myproject".to_string())]
+                .into_iter()
+                .collect(),
+        );
+        let args = Rename {
+            from: "345".to_string(),
+            to: "errands".to_string(),
+            force: true,
+        };
+        assert_eq!(
+            rename(&mut config, &args).await,
+            Ok(format::green_string(
+                "Renamed label '345' to 'errands' on 1 task(s)"
+            ))
+        );
+
+        let args = Rename {
+            from: "missing".to_string(),
+            to: "errands".to_string(),
+            force: true,
+        };
+        let error = rename(&mut config, &args)
+            .await
+            .expect_err("missing label should fail");
+        assert!(error.message.contains("available labels are: 345"));
+        assert_eq!(
+            config.label_projects,
+            Some(
+                [("errands".to_string(), "myproject".to_string())]
+                    .into_iter()
+                    .collect()
+            )
+        );
+        labels_mock.assert();
+        tasks_mock.assert();
+        rename_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn delete_removes_label_and_its_label_projects_entry() {
+        let mut server = mockito::Server::new_async().await;
+        let labels_mock = server
+            .mock("GET", "/api/v1/labels?limit=200")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::Labels.read().await)
+            .create_async()
+            .await;
+        let tasks_mock = server
+            .mock("GET", "/api/v1/tasks/filter?query=%40345&limit=200")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::TodayTasks.read().await)
+            .create_async()
+            .await;
+        let delete_mock = server
+            .mock("DELETE", "/api/v1/labels/123")
+            .with_status(204)
+            .create_async()
+            .await;
+
+        let mut config = test::fixtures::config()
+            .await
+            .with_mock_url(server.url())
+            .create()
+            .await
+            .expect("config should be created");
+        config.label_projects = Some(
+            [
+                ("345".to_string(), "myproject".to_string()),
+                ("home".to_string(), "myproject".to_string()),
+            ]
+            .into_iter()
+            .collect(),
+        );
+        let args = Delete {
+            label: "345".to_string(),
+            force: true,
+        };
+        assert_eq!(
+            delete(&mut config, &args).await,
+            Ok(format::green_string("Deleted label '345' from 1 task(s)"))
+        );
+        assert_eq!(
+            config.label_projects,
+            Some(
+                [("home".to_string(), "myproject".to_string())]
+                    .into_iter()
+                    .collect()
+            )
+        );
+        labels_mock.assert();
+        tasks_mock.assert();
+        delete_mock.assert();
+    }
+
+    #[test]
+    fn label_filter_escapes_spaces_and_operators() {
+        assert_eq!(label_filter("errands"), "@errands");
+        assert_eq!(label_filter("waiting for"), "@waiting\\ for");
+        assert_eq!(label_filter("r&d"), "@r\\&d");
+    }
+}
@@ -1,14 +1,20 @@
+use chrono::NaiveDate;
 use clap::{Parser, Subcommand};
+use std::io::Write;
 use std::path::Path;
+use std::time::Duration;
 use walkdir::WalkDir;
 
+use super::Strict;
 use crate::{
     config::Config,
+    debug,
     errors::Error,
-    filters, input,
-    lists::{self, Flag},
-    projects,
-    tasks::SortOrder,
+    filters, format, input,
+    lists::{self, Flag, GroupBy, IncludeCompleted, ViewOutput},
+    projects::{self, Project},
+    tasks::{self, DueRange, SortOptions, SortOrder, Task, TaskColumn, TimeboxDefaults, priority},
+    time, todoist,
 };
 
 #[derive(Subcommand, Debug, Clone)]
@@ -53,11 +59,12 @@ pub enum ListCommands {
 #[derive(Parser, Debug, Clone)]
 pub struct View {
     #[arg(short, long)]
-    /// The project containing the tasks
+    /// The project containing the tasks. Can add multiple projects separated by commas.
     project: Option<String>,
 
     #[arg(short, long)]
     /// The filter containing the tasks. Can add multiple filters separated by commas.
+    /// With --project, only tasks in the project that match are shown, i.e. `#work & (today)`
     filter: Option<String>,
 
     #[arg(
@@ -67,8 +74,150 @@ pub struct View {
         default_missing_value = "value",
         num_args = 0..=1
     )]
-    /// Choose how results should be sorted
+    /// Choose how results should be sorted. `priority` is an alias for `value`
     sort: SortOrder,
+
+    #[arg(long)]
+    /// Seed for --sort random, the same seed gives the same order for the same tasks
+    seed: Option<u64>,
+
+    #[arg(long, default_value_t = false)]
+    /// Instead of listing tasks, print a count of tasks for each recurrence pattern
+    summary: bool,
+
+    #[arg(short, long, default_value_t = GroupBy::None)]
+    /// Group tasks under headings, sorted within each group
+    group_by: GroupBy,
+
+    #[arg(
+        long,
+        default_value_t = false,
+        conflicts_with_all = ["filter", "overdue", "upcoming"]
+    )]
+    /// Only tasks due today, shorthand for the filter `today`. Combines with --project
+    today: bool,
+
+    #[arg(
+        long,
+        default_value_t = false,
+        conflicts_with_all = ["filter", "upcoming"]
+    )]
+    /// Only overdue tasks, shorthand for the filter `overdue`. Combines with --project
+    overdue: bool,
+
+    #[arg(
+        long,
+        value_name = "DAYS",
+        conflicts_with = "filter",
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    /// Only tasks due in the next DAYS days, shorthand for the filter `next DAYS days`. Combines with --project
+    upcoming: Option<u32>,
+
+    #[arg(
+        short = 'r',
+        long,
+        visible_alias = "priority",
+        value_parser = clap::value_parser!(u8).range(1..=4)
+    )]
+    /// Only include tasks with at least this priority, from 1 (without priority) to 4 (highest)
+    min_priority: Option<u8>,
+
+    #[arg(long, value_name = "DATE", value_parser = parse_date, conflicts_with = "summary")]
+    /// Only tasks due after this date (YYYY-MM-DD), not including it. Excludes tasks without a due date
+    due_after: Option<NaiveDate>,
+
+    #[arg(long, value_name = "DATE", value_parser = parse_date, conflicts_with = "summary")]
+    /// Only tasks due before this date (YYYY-MM-DD), not including it. Excludes tasks without a due date
+    due_before: Option<NaiveDate>,
+
+    #[arg(short, long, default_value_t = false, conflicts_with = "summary")]
+    /// Keep the list on screen, fetching and redrawing it every --interval seconds until Ctrl-C
+    watch: bool,
+
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        default_value_t = 30,
+        requires = "watch",
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    /// How often --watch refreshes the list
+    interval: u64,
+
+    #[arg(long, default_value_t = false, conflicts_with = "summary")]
+    /// Also list tasks completed today, or since --since, marked with [x]
+    include_completed: bool,
+
+    #[arg(long, value_name = "WHEN", requires = "include_completed")]
+    /// Include tasks completed from the start of this date instead of today, as YYYY-MM-DD, yesterday, 7 days ago or last 2 weeks.
+    /// At most 3 months back, unless --batch-size is given
+    since: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "DAYS",
+        requires = "include_completed",
+        value_parser = clap::value_parser!(u32).range(1..=89)
+    )]
+    /// Fetch completed tasks this many days at a time, up to --max-concurrency requests at once.
+    /// Lets --since go back further than 3 months, which Todoist won't return in one request
+    batch_size: Option<u32>,
+
+    #[arg(long, default_value_t = false, conflicts_with_all = ["no_recurring", "summary"])]
+    /// Only list recurring tasks
+    recurring_only: bool,
+
+    #[arg(long, default_value_t = false, conflicts_with = "summary")]
+    /// Leave recurring tasks out of the list
+    no_recurring: bool,
+
+    #[arg(
+        long,
+        value_name = "COLUMNS",
+        value_delimiter = ',',
+        conflicts_with = "summary"
+    )]
+    /// Comma separated parts of each task to show on one line, in this order, i.e. "priority,content,due"
+    columns: Option<Vec<TaskColumn>>,
+
+    #[arg(long, value_name = "TEXT", conflicts_with = "summary")]
+    /// Only tasks whose content or description contains this text, ignoring case
+    content_contains: Option<String>,
+
+    #[arg(long, default_value_t = false, requires = "content_contains")]
+    /// Treat --content-contains as a regular expression
+    regex: bool,
+
+    #[arg(long, default_value_t = false, conflicts_with = "summary")]
+    /// Show how many days overdue tasks are, i.e. "(3d overdue)", in red after a week
+    show_age: bool,
+
+    #[arg(long, default_value_t = false, conflicts_with = "summary")]
+    /// Only print the number of matching tasks, after all other filters are applied
+    count_only: bool,
+
+    #[command(flatten)]
+    reverse: Reverse,
+}
+
+/// `--reverse` for the list commands that sort tasks
+#[derive(clap::Args, Debug, Copy, Clone)]
+pub struct Reverse {
+    #[arg(long, default_value_t = false)]
+    /// Reverse the sorted list, i.e. lowest priority first
+    reverse: bool,
+}
+
+impl Reverse {
+    /// Sort options for the order given with `--sort`, flipped when `--reverse` was passed
+    fn sort(self, order: SortOrder) -> SortOptions {
+        SortOptions {
+            order,
+            seed: None,
+            reverse: self.reverse,
+        }
+    }
 }
 
 #[derive(Parser, Debug, Clone)]
@@ -79,6 +228,7 @@ pub struct Process {
 
     #[arg(short, long)]
     /// The filter containing the tasks. Can add multiple filters separated by commas.
+    /// With --project, only tasks in the project that match are processed, i.e. `#work & (today)`
     filter: Option<String>,
 
     #[arg(
@@ -88,8 +238,24 @@ pub struct Process {
         default_missing_value = "value",
         num_args = 0..=1
     )]
-    /// Choose how results should be sorted
+    /// Choose how results should be sorted. `priority` is an alias for `value`
     sort: SortOrder,
+
+    #[arg(
+        short = 'r',
+        long,
+        visible_alias = "priority",
+        value_parser = clap::value_parser!(u8).range(1..=4)
+    )]
+    /// Only include tasks with at least this priority, from 1 (without priority) to 4 (highest)
+    min_priority: Option<u8>,
+
+    #[command(flatten)]
+    reverse: Reverse,
+
+    #[arg(long, default_value_t = false)]
+    /// Only print the number of matching tasks instead of going through them
+    count_only: bool,
 }
 
 #[derive(Parser, Debug, Clone)]
@@ -109,8 +275,19 @@ pub struct Timebox {
         default_missing_value = "value",
         num_args = 0..=1
     )]
-    /// Choose how results should be sorted
+    /// Choose how results should be sorted. `priority` is an alias for `value`
     sort: SortOrder,
+
+    #[command(flatten)]
+    reverse: Reverse,
+
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    /// Duration filled in for every task so Enter accepts it, i.e. 30 or 1h30m
+    default_duration: Option<u32>,
+
+    #[arg(long, default_value_t = false)]
+    /// Only set the duration of tasks that already have a due date, keeping the date and time as they are
+    skip_date: bool,
 }
 
 #[derive(Parser, Debug, Clone)]
@@ -121,6 +298,7 @@ pub struct Prioritize {
 
     #[arg(short, long)]
     /// The filter containing the tasks. Can add multiple filters separated by commas.
+    /// With --project, only tasks in the project that match are prioritized, i.e. `#work & (today)`
     filter: Option<String>,
 
     #[arg(
@@ -130,8 +308,24 @@ pub struct Prioritize {
         default_missing_value = "value",
         num_args = 0..=1
     )]
-    /// Choose how results should be sorted
+    /// Choose how results should be sorted. `priority` is an alias for `value`
     sort: SortOrder,
+
+    #[arg(
+        short = 'r',
+        long,
+        visible_alias = "priority",
+        value_parser = clap::value_parser!(u8).range(1..=4)
+    )]
+    /// Only include tasks with at least this priority, from 1 (without priority) to 4 (highest)
+    min_priority: Option<u8>,
+
+    #[command(flatten)]
+    reverse: Reverse,
+
+    #[arg(long, default_value_t = false)]
+    /// Only print the number of matching tasks instead of going through them
+    count_only: bool,
 }
 
 #[derive(Parser, Debug, Clone)]
@@ -151,7 +345,7 @@ pub struct Remind {
         default_missing_value = "value",
         num_args = 0..=1
     )]
-    /// Choose how results should be sorted
+    /// Choose how results should be sorted. `priority` is an alias for `value`
     sort: SortOrder,
 }
 
@@ -176,8 +370,15 @@ pub struct Label {
         default_missing_value = "value",
         num_args = 0..=1
     )]
-    /// Choose how results should be sorted
+    /// Choose how results should be sorted. `priority` is an alias for `value`
     sort: SortOrder,
+
+    #[arg(short, long, default_value_t = false)]
+    /// Add the one label given with --label to every task without asking
+    auto: bool,
+
+    #[command(flatten)]
+    strict: Strict,
 }
 
 #[derive(Parser, Debug, Clone)]
@@ -205,8 +406,26 @@ pub struct Schedule {
         default_missing_value = "value",
         num_args = 0..=1
     )]
-    /// Choose how results should be sorted
+    /// Choose how results should be sorted. `priority` is an alias for `value`
     sort: SortOrder,
+
+    #[arg(long, default_value_t = false)]
+    /// Offer Today, Tomorrow, the start of next week and This Weekend before the usual date prompt
+    smart: bool,
+
+    #[command(flatten)]
+    reverse: Reverse,
+
+    #[arg(long, value_name = "DATE", conflicts_with = "smart")]
+    /// Give every matching task this due date instead of prompting for each, i.e. "next monday"
+    all_to: Option<String>,
+
+    #[arg(long, default_value_t = false, requires = "all_to")]
+    /// Don't confirm before scheduling with --all-to, unless over confirm_threshold in config
+    force: bool,
+
+    #[command(flatten)]
+    strict: Strict,
 }
 
 #[derive(Parser, Debug, Clone)]
@@ -226,8 +445,22 @@ pub struct Deadline {
         default_missing_value = "value",
         num_args = 0..=1
     )]
-    /// Choose how results should be sorted
+    /// Choose how results should be sorted. `priority` is an alias for `value`
     sort: SortOrder,
+
+    #[arg(long, default_value_t = false)]
+    /// Remove existing deadlines instead of setting new ones
+    clear: bool,
+
+    #[arg(long, default_value_t = false, requires = "clear")]
+    /// Don't ask before clearing deadlines
+    force: bool,
+
+    #[command(flatten)]
+    reverse: Reverse,
+
+    #[command(flatten)]
+    strict: Strict,
 }
 
 #[derive(Parser, Debug, Clone)]
@@ -236,16 +469,243 @@ pub struct Import {
     /// The file or directory to fuzzy find in
     path: Option<String>,
 }
-pub async fn view(config: &mut Config, args: &View) -> Result<String, Error> {
+pub async fn view(config: &Config, args: &View) -> Result<String, Error> {
     let View {
         project,
         filter,
         sort,
+        seed,
+        summary,
+        group_by,
+        today,
+        overdue,
+        upcoming,
+        min_priority,
+        due_after,
+        due_before,
+        watch,
+        interval,
+        include_completed,
+        since,
+        batch_size,
+        recurring_only,
+        no_recurring,
+        reverse,
+        columns,
+        content_contains,
+        regex,
+        show_age,
+        count_only,
     } = args;
+    let sort = &SortOptions {
+        seed: *seed,
+        ..reverse.sort(*sort)
+    };
+    let recurring = match (recurring_only, no_recurring) {
+        (true, _) => Some(true),
+        (_, true) => Some(false),
+        _ => None,
+    };
+    let content = match content_contains {
+        Some(text) => Some(tasks::content_matcher(text, *regex)?),
+        None => None,
+    };
+    let output = ViewOutput {
+        columns: columns.clone(),
+        show_age: *show_age,
+        count_only: *count_only,
+    };
+    let due_query = due_filter(*today, *overdue, *upcoming);
+    let min_priority = priority::from_integer(*min_priority)?;
+    let due_range = DueRange {
+        after: *due_after,
+        before: *due_before,
+    };
+    if let (Some(after), Some(before)) = (due_after, due_before)
+        && after >= before
+    {
+        return Err(Error::new(
+            "list_view",
+            "--due-after must be earlier than --due-before",
+        ));
+    }
 
-    let flag =
-        super::fetch_project_or_filter(project.as_deref(), filter.as_deref(), config).await?;
-    lists::view(config, flag, sort).await
+    if let Some(project_names) = project.as_deref().filter(|p| p.contains(','))
+        && filter.is_none()
+        && due_query.is_none()
+    {
+        if *summary || *group_by != GroupBy::None || *watch || *include_completed {
+            return Err(Error::new(
+                "list_view",
+                "--summary, --group-by, --watch and --include-completed do not support multiple projects",
+            ));
+        }
+        let projects = super::fetch_projects(project_names, config).await?;
+        return lists::view_projects(
+            config,
+            &projects,
+            sort,
+            min_priority,
+            due_range,
+            recurring,
+            content.as_ref(),
+            &output,
+        )
+        .await;
+    }
+
+    let flag = match due_query {
+        Some(query) => {
+            let query = match project {
+                Some(project_names) => {
+                    let projects = super::fetch_projects(project_names, config).await?;
+                    scope_to_projects(&query, &projects)
+                }
+                None => query,
+            };
+            debug::maybe_print(config, &format!("Using filter: {query}"));
+            Flag::Filter(query)
+        }
+        None => fetch_scoped_flag(project.as_deref(), filter.as_deref(), config).await?,
+    };
+    if *summary {
+        lists::recurrence_summary(config, flag).await
+    } else {
+        let completed = match (*include_completed, since) {
+            (false, _) => None,
+            (true, Some(since)) => Some(time::since_date(since, config, batch_size.is_some())?),
+            (true, None) => Some(time::naive_date_today(config)?),
+        }
+        .map(|since| IncludeCompleted {
+            since,
+            batch_days: *batch_size,
+        });
+        let render = || {
+            lists::view(
+                config,
+                flag.clone(),
+                sort,
+                *group_by,
+                min_priority,
+                due_range,
+                recurring,
+                completed,
+                content.as_ref(),
+                &output,
+            )
+        };
+        // Tests and mock servers get a single render instead of a loop that only Ctrl-C ends
+        if *watch && !cfg!(test) && config.mock_url.is_none() {
+            let interval = Duration::from_secs(*interval);
+            watch_list(config, interval, None, &mut std::io::stdout(), render).await
+        } else {
+            render().await
+        }
+    }
+}
+
+/// Clears the screen and redraws the list every `interval` until Ctrl-C, or until it has been drawn `limit` times
+async fn watch_list<F, Fut>(
+    config: &Config,
+    interval: Duration,
+    limit: Option<usize>,
+    out: &mut impl Write,
+    render: F,
+) -> Result<String, Error>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<String, Error>>,
+{
+    let mut ticker = tokio::time::interval(interval);
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+    let footer = format!(
+        "Refreshing every {}s, press Ctrl-C to stop",
+        interval.as_secs()
+    );
+    let mut drawn = 0;
+    while limit.is_none_or(|limit| drawn < limit) {
+        tokio::select! {
+            _ = ticker.tick() => {
+                let text = match tokio::time::timeout(todoist::get_timeout(config), render()).await {
+                    Ok(Ok(text)) => text,
+                    Ok(Err(e)) => e.to_string(),
+                    Err(_) => format::red_string("Timed out fetching tasks, retrying at the next refresh"),
+                };
+                crossterm::queue!(
+                    out,
+                    crossterm::terminal::Clear(crossterm::terminal::ClearType::All),
+                    crossterm::cursor::MoveTo(0, 0)
+                )?;
+                writeln!(out, "{text}\n\n{}", format::cyan_string(&footer))?;
+                out.flush()?;
+                drawn += 1;
+            }
+            result = &mut ctrl_c => {
+                result?;
+                break;
+            }
+        }
+    }
+    Ok(String::new())
+}
+
+/// Parses a YYYY-MM-DD date given on the command line
+fn parse_date(string: &str) -> Result<NaiveDate, String> {
+    if !time::is_date(string) {
+        return Err("expected a date in YYYY-MM-DD format".to_string());
+    }
+    NaiveDate::parse_from_str(string, time::FORMAT_DATE).map_err(|e| e.to_string())
+}
+
+/// Parses a duration given on the command line into minutes
+fn parse_duration(string: &str) -> Result<u32, String> {
+    time::duration_to_minutes(string).map_err(|e| e.message)
+}
+
+/// Todoist filter query for the due date shorthand flags, None when none of them are used
+fn due_filter(today: bool, overdue: bool, upcoming: Option<u32>) -> Option<String> {
+    if today {
+        Some("today".to_string())
+    } else if overdue {
+        Some("overdue".to_string())
+    } else {
+        upcoming.map(|days| format!("next {days} days"))
+    }
+}
+
+/// Limits a filter query to tasks in any of the projects
+fn scope_to_projects(query: &str, projects: &[Project]) -> String {
+    let scope = projects
+        .iter()
+        .map(|project| format!("#{}", project.name))
+        .collect::<Vec<String>>()
+        .join(" | ");
+    if projects.len() == 1 {
+        format!("{scope} & ({query})")
+    } else {
+        format!("({scope}) & ({query})")
+    }
+}
+
+/// Resolves --project and --filter like the other list commands, except that both can be given.
+/// Every comma separated filter is then limited to the projects, i.e. `#work & (today)`
+async fn fetch_scoped_flag(
+    project: Option<&str>,
+    filter: Option<&str>,
+    config: &Config,
+) -> Result<Flag, Error> {
+    let (Some(project_names), Some(filter)) = (project, filter) else {
+        return super::fetch_project_or_filter(project, filter, config).await;
+    };
+    let projects = super::fetch_projects(project_names, config).await?;
+    let query = filter
+        .split(',')
+        .map(|query| scope_to_projects(query.trim(), &projects))
+        .collect::<Vec<String>>()
+        .join(",");
+    debug::maybe_print(config, &format!("Using filter: {query}"));
+    Ok(Flag::Filter(query))
 }
 
 pub async fn label(config: Config, args: &Label) -> Result<String, Error> {
@@ -254,7 +714,24 @@ pub async fn label(config: Config, args: &Label) -> Result<String, Error> {
         project,
         labels,
         sort,
+        auto,
+        strict,
     } = args;
+    let sort = &SortOptions::from(*sort);
+    if *auto {
+        let [label] = labels.as_slice() else {
+            return Err(Error::new(
+                "list_label",
+                "--auto needs exactly one --label to add to every task",
+            ));
+        };
+        let flag =
+            super::fetch_project_or_filter(project.as_deref(), filter.as_deref(), &config).await?;
+        return lists::label_all(&config, flag, label, strict.strict).await;
+    }
+    if strict.strict {
+        return Err(Error::new("list_label", "--strict only works with --auto"));
+    }
     let labels = super::maybe_fetch_labels(&config, labels).await?;
     let flag =
         super::fetch_project_or_filter(project.as_deref(), filter.as_deref(), &config).await?;
@@ -266,10 +743,14 @@ pub async fn process(config: Config, args: &Process) -> Result<String, Error> {
         project,
         filter,
         sort,
+        min_priority,
+        reverse,
+        count_only,
     } = args;
-    let flag =
-        super::fetch_project_or_filter(project.as_deref(), filter.as_deref(), &config).await?;
-    lists::process(&config, flag, sort).await
+    let sort = &reverse.sort(*sort);
+    let min_priority = priority::from_integer(*min_priority)?;
+    let flag = fetch_scoped_flag(project.as_deref(), filter.as_deref(), &config).await?;
+    lists::process(&config, flag, sort, min_priority, *count_only).await
 }
 
 pub async fn timebox(config: Config, args: &Timebox) -> Result<String, Error> {
@@ -277,10 +758,18 @@ pub async fn timebox(config: Config, args: &Timebox) -> Result<String, Error> {
         project,
         filter,
         sort,
+        reverse,
+        default_duration,
+        skip_date,
     } = args;
+    let sort = &reverse.sort(*sort);
     let flag =
         super::fetch_project_or_filter(project.as_deref(), filter.as_deref(), &config).await?;
-    lists::timebox(&config, flag, sort).await
+    let defaults = TimeboxDefaults {
+        duration: *default_duration,
+        skip_date: *skip_date,
+    };
+    lists::timebox(&config, flag, sort, defaults).await
 }
 
 pub async fn prioritize(config: Config, args: &Prioritize) -> Result<String, Error> {
@@ -288,10 +777,14 @@ pub async fn prioritize(config: Config, args: &Prioritize) -> Result<String, Err
         project,
         filter,
         sort,
+        min_priority,
+        reverse,
+        count_only,
     } = args;
-    let flag =
-        super::fetch_project_or_filter(project.as_deref(), filter.as_deref(), &config).await?;
-    lists::prioritize(&config, flag, sort).await
+    let sort = &reverse.sort(*sort);
+    let min_priority = priority::from_integer(*min_priority)?;
+    let flag = fetch_scoped_flag(project.as_deref(), filter.as_deref(), &config).await?;
+    lists::prioritize(&config, flag, sort, min_priority, *count_only).await
 }
 
 pub async fn remind(config: Config, args: &Remind) -> Result<String, Error> {
@@ -300,6 +793,7 @@ pub async fn remind(config: Config, args: &Remind) -> Result<String, Error> {
         filter,
         sort,
     } = args;
+    let sort = &SortOptions::from(*sort);
     let flag =
         super::fetch_project_or_filter(project.as_deref(), filter.as_deref(), &config).await?;
     lists::remind(&config, flag, sort).await
@@ -327,16 +821,21 @@ fn select_file(path_or_file: String, config: &Config) -> Result<String, Error> {
             .collect::<Vec<String>>();
         options.sort();
         options.dedup();
-        let path = input::select("Select file to process", options, config.mock_select)?;
+        let path = input::fuzzy_select(
+            "Select file to process",
+            options,
+            config.fuzzy_select(),
+            config.mock_select,
+        )?;
 
         Ok(path)
     } else if Path::is_file(path) {
         Ok(path_or_file)
     } else {
-        Err(Error {
-            source: "select_file".to_string(),
-            message: format!("{path_or_file} is neither a file nor a directory"),
-        })
+        Err(Error::new(
+            "select_file",
+            &format!("{path_or_file} is neither a file nor a directory"),
+        ))
     }
 }
 
@@ -353,17 +852,47 @@ pub async fn schedule(config: Config, args: &Schedule) -> Result<String, Error>
         skip_recurring,
         overdue,
         sort,
+        smart,
+        reverse,
+        all_to,
+        force,
+        strict,
     } = args;
-    match super::fetch_project_or_filter(project.as_deref(), filter.as_deref(), &config).await? {
-        Flag::Filter(filter) => filters::schedule(&config, &filter, sort).await,
+    let sort = &reverse.sort(*sort);
+    let task_filter = if *overdue {
+        projects::TaskFilter::Overdue
+    } else {
+        projects::TaskFilter::Unscheduled
+    };
+    let flag =
+        super::fetch_project_or_filter(project.as_deref(), filter.as_deref(), &config).await?;
+    if let Some(date) = all_to {
+        return schedule_all_to(
+            &config,
+            flag,
+            date,
+            task_filter,
+            *skip_recurring,
+            *force,
+            strict.strict,
+        )
+        .await;
+    }
+    match flag {
+        Flag::Filter(filter) => {
+            filters::schedule(&config, &filter, sort, *smart, strict.strict).await
+        }
         Flag::Project(project) => {
-            let task_filter = if *overdue {
-                projects::TaskFilter::Overdue
-            } else {
-                projects::TaskFilter::Unscheduled
-            };
-
-            projects::schedule(&config, &project, task_filter, *skip_recurring, sort).await
+            projects::schedule(
+                &config,
+                &project,
+                task_filter,
+                *skip_recurring,
+                sort,
+                *smart,
+                strict.strict,
+            )
+            .await
         }
     }
 }
@@ -373,16 +902,113 @@ pub async fn deadline(config: Config, args: &Deadline) -> Result<String, Error>
         project,
         filter,
         sort,
+        clear,
+        force,
+        reverse,
+        strict,
     } = args;
-    match super::fetch_project_or_filter(project.as_deref(), filter.as_deref(), &config).await? {
-        Flag::Filter(filter) => filters::deadline(&config, &filter, sort).await,
-        Flag::Project(project) => projects::deadline(&config, &project, sort).await,
+    let sort = &reverse.sort(*sort);
+    let flag =
+        super::fetch_project_or_filter(project.as_deref(), filter.as_deref(), &config).await?;
+    if *clear {
+        return clear_deadlines(&config, flag, *force, strict.strict).await;
+    }
+    match flag {
+        Flag::Filter(filter) => filters::deadline(&config, &filter, sort, strict.strict).await,
+        Flag::Project(project) => projects::deadline(&config, &project, sort, strict.strict).await,
     }
 }
 
+async fn schedule_all_to(
+    config: &Config,
+    flag: Flag,
+    date: &str,
+    task_filter: projects::TaskFilter,
+    skip_recurring: bool,
+    force: bool,
+    strict: bool,
+) -> Result<String, Error> {
+    time::validate_due(date)?;
+    let keep = |task: &Task| !(skip_recurring && task.is_recurring());
+    let project_filter = |task: &Task| keep(task) && task.filter(config, &task_filter);
+    let tasks = lists::fetch_tasks_by_flag(config, &flag, project_filter, keep).await?;
+
+    let desc = format!("Schedule {} tasks in {flag} for {date}", tasks.len());
+    if !super::confirm_if_over(config, tasks.len(), force, &desc, input::SCHEDULE)? {
+        return Ok("Cancelled".into());
+    }
+    lists::schedule_all_to(config, &flag, tasks, date, strict).await
+}
+
+async fn clear_deadlines(
+    config: &Config,
+    flag: Flag,
+    force: bool,
+    strict: bool,
+) -> Result<String, Error> {
+    let has_deadline = |task: &Task| task.deadline.is_some();
+    let tasks = lists::fetch_tasks_by_flag(config, &flag, has_deadline, has_deadline).await?;
+
+    let desc = format!("Clear deadlines from {} tasks in {flag}", tasks.len());
+    if !super::confirm_if_over(config, tasks.len(), force, &desc, input::CLEAR)? {
+        return Ok("Cancelled".into());
+    }
+    lists::clear_deadlines(config, &flag, tasks, strict).await
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::errors::ErrorKind;
+    use crate::test;
+    use crate::test::responses::ResponseFromFile;
+
+    #[tokio::test]
+    async fn watch_list_redraws_every_interval_until_the_limit() {
+        let config = test::fixtures::config().await;
+        let renders = std::sync::atomic::AtomicUsize::new(0);
+        let counter = &renders;
+        let render = move || async move {
+            let count = counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+            if count == 2 {
+                Err(Error::new("render", "Todoist is down"))
+            } else {
+                Ok(format!("render {count}"))
+            }
+        };
+        let mut out = Vec::new();
+
+        let result = watch_list(&config, Duration::from_millis(1), Some(3), &mut out, render).await;
+        assert_eq!(result, Ok(String::new()));
+        assert_eq!(renders.load(std::sync::atomic::Ordering::SeqCst), 3);
+
+        let out = String::from_utf8(out).expect("output should be UTF-8");
+        assert!(out.contains("render 1"));
+        assert!(out.contains("Todoist is down"));
+        assert!(out.contains("render 3"));
+        assert_eq!(out.matches("press Ctrl-C to stop").count(), 3);
+    }
+
+    #[tokio::test]
+    async fn view_watch_renders_once_with_a_mock_server() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api/v1/tasks/filter?query=today&limit=200")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::TodayTasks.read().await)
+            .create_async()
+            .await;
+        let config = test::fixtures::config().await.with_mock_url(server.url());
+        let args = View::try_parse_from(["tod", "--filter", "today", "--watch"])
+            .expect("--watch should be valid");
+
+        let result = tokio::time::timeout(Duration::from_secs(5), view(&config, &args))
+            .await
+            .expect("--watch should not loop with a mock server");
+        assert!(result.is_ok());
+        mock.assert();
+    }
 
     #[test]
     fn view_sort_without_value_uses_configured_sort() {
@@ -390,9 +1016,229 @@ mod tests {
         assert_eq!(args.sort.to_string(), "value");
     }
 
+    #[test]
+    fn view_sort_priority_is_alias_for_value() {
+        let args = View::try_parse_from(["tod", "--sort", "priority"])
+            .expect("--sort priority should be valid");
+        assert_eq!(args.sort.to_string(), "value");
+    }
+
     #[test]
     fn view_without_sort_keeps_datetime_default() {
         let args = View::try_parse_from(["tod"]).expect("view arguments should be valid");
         assert_eq!(args.sort.to_string(), "datetime");
     }
+
+    #[test]
+    fn view_group_by_defaults_to_none() {
+        let args = View::try_parse_from(["tod"]).expect("view arguments should be valid");
+        assert_eq!(args.group_by, GroupBy::None);
+
+        let args = View::try_parse_from(["tod", "--group-by", "section"])
+            .expect("--group-by section should be valid");
+        assert_eq!(args.group_by, GroupBy::Section);
+    }
+
+    #[test]
+    fn view_columns_are_comma_separated_and_checked() {
+        let args = View::try_parse_from(["tod", "--columns", "priority,content,due"])
+            .expect("--columns should be valid");
+        assert_eq!(
+            args.columns,
+            Some(vec![
+                TaskColumn::Priority,
+                TaskColumn::Content,
+                TaskColumn::Due
+            ])
+        );
+
+        let error = View::try_parse_from(["tod", "--columns", "content,size"])
+            .expect_err("unknown column should fail");
+        assert!(error.to_string().contains("labels"));
+    }
+
+    #[test]
+    fn view_due_shorthands_conflict_with_filter() {
+        let result = View::try_parse_from(["tod", "--today", "--filter", "p1"]);
+        assert!(result.is_err());
+
+        let result = View::try_parse_from(["tod", "--overdue", "--upcoming", "3"]);
+        assert!(result.is_err());
+
+        let args = View::try_parse_from(["tod", "--upcoming", "7", "--project", "work"])
+            .expect("--upcoming with --project should be valid");
+        assert_eq!(args.upcoming, Some(7));
+    }
+
+    #[test]
+    fn due_range_flags_parse_dates() {
+        let args = View::try_parse_from([
+            "tod",
+            "--due-after",
+            "2025-01-01",
+            "--due-before",
+            "2025-02-01",
+        ])
+        .expect("view arguments should parse");
+        assert_eq!(args.due_after, NaiveDate::from_ymd_opt(2025, 1, 1));
+        assert_eq!(args.due_before, NaiveDate::from_ymd_opt(2025, 2, 1));
+
+        assert!(View::try_parse_from(["tod", "--due-after", "tomorrow"]).is_err());
+        assert!(View::try_parse_from(["tod", "--due-before", "2025-02-30"]).is_err());
+    }
+
+    #[test]
+    fn due_filter_builds_query() {
+        assert_eq!(due_filter(true, false, None), Some("today".to_string()));
+        assert_eq!(due_filter(false, true, None), Some("overdue".to_string()));
+        assert_eq!(
+            due_filter(false, false, Some(3)),
+            Some("next 3 days".to_string())
+        );
+        assert_eq!(due_filter(false, false, None), None);
+    }
+
+    #[test]
+    fn scope_to_projects_ands_project_scope() {
+        let work = Project {
+            name: "work".to_string(),
+            ..test::fixtures::project()
+        };
+        let projects = vec![test::fixtures::project(), work];
+        assert_eq!(
+            scope_to_projects("today", &projects),
+            "(#myproject | #work) & (today)"
+        );
+    }
+
+    #[tokio::test]
+    async fn project_and_filter_are_combined_into_one_query() {
+        let config = test::fixtures::config().await;
+
+        let flag = fetch_scoped_flag(Some("myproject"), Some("today, p1"), &config)
+            .await
+            .expect("project and filter should combine");
+        assert!(matches!(
+            flag,
+            Flag::Filter(query) if query == "#myproject & (today),#myproject & (p1)"
+        ));
+
+        let flag = fetch_scoped_flag(None, Some("today"), &config)
+            .await
+            .expect("filter alone should still work");
+        assert!(matches!(flag, Flag::Filter(query) if query == "today"));
+
+        let Err(error) = fetch_scoped_flag(Some("missing"), Some("today"), &config).await else {
+            panic!("unknown project should error");
+        };
+        assert_eq!(error.kind, ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn min_priority_accepts_priority_alias() {
+        let args =
+            View::try_parse_from(["tod", "--priority", "4"]).expect("--priority should be valid");
+        assert_eq!(args.min_priority, Some(4));
+
+        let args = Process::try_parse_from(["tod", "--min-priority", "3"])
+            .expect("--min-priority should be valid");
+        assert_eq!(args.min_priority, Some(3));
+
+        assert!(Prioritize::try_parse_from(["tod", "-r", "5"]).is_err());
+    }
+
+    #[test]
+    fn reverse_flips_the_sort_options() {
+        let args = Schedule::try_parse_from(["tod", "--reverse", "--sort", "datetime"])
+            .expect("--reverse should be valid");
+        let sort = args.reverse.sort(args.sort);
+        assert!(sort.reverse);
+        assert!(matches!(sort.order, SortOrder::Datetime));
+
+        let args = Deadline::try_parse_from(["tod"]).expect("--reverse should be optional");
+        assert!(!args.reverse.sort(args.sort).reverse);
+    }
+
+    #[test]
+    fn strict_is_given_on_the_bulk_commands() {
+        let args = Schedule::try_parse_from(["tod", "--strict"]).expect("--strict should be valid");
+        assert!(args.strict.strict);
+
+        let args = Deadline::try_parse_from(["tod"]).expect("--strict should be optional");
+        assert!(!args.strict.strict);
+    }
+
+    #[test]
+    fn recurring_flags_conflict() {
+        assert!(View::try_parse_from(["tod", "--recurring-only", "--no-recurring"]).is_err());
+        assert!(View::try_parse_from(["tod", "--no-recurring", "--summary"]).is_err());
+
+        let args = View::try_parse_from(["tod", "--no-recurring", "--project", "work"])
+            .expect("--no-recurring with --project should be valid");
+        assert!(args.no_recurring);
+    }
+
+    #[tokio::test]
+    async fn label_auto_needs_exactly_one_label() {
+        let config = test::fixtures::config().await;
+        for labels in [vec![], vec!["home", "work"]] {
+            let mut arguments = vec!["tod", "--auto", "--filter", "today"];
+            for label in labels {
+                arguments.extend(["--label", label]);
+            }
+            let args = Label::try_parse_from(arguments).expect("label arguments should parse");
+            let error = label(config.clone(), &args)
+                .await
+                .expect_err("--auto should need exactly one label");
+            assert!(error.message.contains("exactly one --label"));
+        }
+    }
+
+    #[test]
+    fn deadline_force_requires_clear() {
+        assert!(Deadline::try_parse_from(["tod", "--force"]).is_err());
+
+        let args = Deadline::try_parse_from(["tod", "--clear", "--force"])
+            .expect("--clear --force should be valid");
+        assert!(args.clear);
+        assert!(args.force);
+    }
+
+    #[tokio::test]
+    async fn schedule_all_to_sets_one_date_on_every_task() {
+        assert!(Schedule::try_parse_from(["tod", "--force"]).is_err());
+        assert!(Schedule::try_parse_from(["tod", "--all-to", "friday", "--smart"]).is_err());
+
+        let mut server = mockito::Server::new_async().await;
+        let tasks_mock = server
+            .mock("GET", "/api/v1/tasks/filter?query=today&limit=200")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::TodayTasks.read().await)
+            .create_async()
+            .await;
+        let update_mock = server
+            .mock("POST", "/api/v1/tasks/6Xqhv4cwxgjwG9w8")
+            .match_body(mockito::Matcher::PartialJson(
+                serde_json::json!({"due_string": "friday"}),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::TodayTask.read().await)
+            .create_async()
+            .await;
+
+        let config = test::fixtures::config().await.with_mock_url(server.url());
+        let args =
+            Schedule::try_parse_from(["tod", "--filter", "today", "--all-to", "friday", "--force"])
+                .expect("schedule arguments should parse");
+
+        let result = schedule(config, &args).await;
+        assert_eq!(
+            result,
+            Ok(String::from("Scheduled 1 task(s) in 'today' for friday"))
+        );
+        tasks_mock.assert();
+        update_mock.assert();
+    }
 }
@@ -1,11 +1,14 @@
-use crate::config::Config;
-use crate::errors::Error;
+use crate::config::{Config, diff};
+use crate::debug::LogLevel;
+use crate::errors::{Error, ErrorKind};
 use crate::lists::Flag;
+use crate::projects::Project;
 use crate::tasks::priority::{self, Priority};
 use crate::{CommandResult, input, labels};
 use auth_commands::AuthCommands;
-use clap::{Parser, Subcommand};
+use clap::{ArgMatches, CommandFactory, FromArgMatches, Parser, Subcommand};
 use config_commands::ConfigCommands;
+use label_commands::LabelCommands;
 use list_commands::ListCommands;
 use project_commands::ProjectCommands;
 use reminder_commands::ReminderCommands;
@@ -19,6 +22,7 @@ use tokio::sync::mpsc::UnboundedSender;
 
 mod auth_commands;
 mod config_commands;
+mod label_commands;
 mod list_commands;
 mod project_commands;
 mod reminder_commands;
@@ -52,15 +56,68 @@ pub struct Cli {
     pub verbose: bool,
 
     #[arg(short, long)]
-    /// Absolute path to configuration file. Defaults to `$XDG_CONFIG_HOME/tod.cfg`
+    /// Absolute path to configuration file. Defaults to `$TOD_CONFIG`, then `$XDG_CONFIG_HOME/tod.cfg`, then `~/.config/tod.cfg`
     pub config: Option<PathBuf>,
 
     #[arg(short, long)]
     /// Time to wait for a response from API in seconds. Defaults to 30.
     pub timeout: Option<u64>,
 
+    #[arg(long)]
+    /// Write timestamped summaries of API requests and responses to this file instead of stdout
+    pub log_file: Option<PathBuf>,
+
+    #[arg(long, value_enum, value_name = "LEVEL", default_value_t = LogLevel::Warn)]
+    /// Most detailed messages to log, info adds API requests with their timings and debug matches --verbose
+    pub log_level: LogLevel,
+
+    #[arg(short, long, default_value_t = false)]
+    /// Hide the progress counter shown while working through many tasks
+    pub quiet: bool,
+
+    #[arg(long, default_value_t = false)]
+    /// Print dates as RFC 3339 timestamps with a timezone offset, i.e. 2025-01-31T09:00:00-08:00
+    pub iso_dates: bool,
+
+    #[arg(long, default_value_t = false)]
+    /// Only print ASCII in lists, dropping emoji from task content. NO_COLOR also makes priority symbols ASCII
+    pub ascii: bool,
+
+    #[arg(long, value_name = "PATH")]
+    /// Write the result to this file instead of stdout, prompts and errors stay on the terminal
+    pub output: Option<PathBuf>,
+
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    /// Most API requests to run at once in bulk commands, 1 runs them one after another. Defaults to 4
+    pub max_concurrency: Option<u64>,
+
     #[command(subcommand)]
     pub command: Commands,
+
+    #[arg(skip)]
+    /// Name of the subcommand, i.e. "task create", filled in by `Cli::parse_with_name`
+    pub command_name: Option<String>,
+}
+
+impl Cli {
+    /// Parses the command line like `Cli::parse`, also keeping the name of the subcommand that was used
+    pub fn parse_with_name() -> Cli {
+        let matches = Cli::command().get_matches();
+        let mut cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+        cli.command_name = command_name(&matches);
+        cli
+    }
+}
+
+/// Full name of the subcommand, with aliases resolved, i.e. "task create" for `tod t c`
+fn command_name(matches: &ArgMatches) -> Option<String> {
+    let mut names = Vec::new();
+    let mut matches = matches;
+    while let Some((name, subcommand)) = matches.subcommand() {
+        names.push(name);
+        matches = subcommand;
+    }
+    (!names.is_empty()).then(|| names.join(" "))
 }
 
 #[derive(Subcommand, Debug, Clone)]
@@ -85,6 +142,11 @@ pub enum Commands {
     /// (l) Commands for multiple tasks
     List(ListCommands),
 
+    #[command(subcommand)]
+    #[clap(alias = "b")]
+    /// (b) Commands for labels
+    Label(LabelCommands),
+
     #[command(subcommand)]
     #[clap(alias = "r")]
     /// (r) Commands for reminders. Only available on Pro Todoist plans
@@ -111,6 +173,14 @@ pub enum Commands {
     Test(TestCommands),
 }
 
+/// `--strict` for the bulk commands that update one task after another
+#[derive(clap::Args, Debug, Copy, Clone, Default)]
+pub struct Strict {
+    #[arg(long, default_value_t = false)]
+    /// Stop at the first failed update and exit with an error, instead of reporting failures at the end
+    pub strict: bool,
+}
+
 enum FlagOptions {
     Project,
     Filter,
@@ -133,6 +203,7 @@ pub async fn select_command(cli: Cli, tx: UnboundedSender<Error>) -> Result<Comm
     match &cli.command {
         Commands::Auth(command) => auth_command(command, &cli).await,
         Commands::Config(command) => config_command(command, &cli, &tx).await,
+        Commands::Label(command) => label_command(command, &cli, &tx).await,
         Commands::List(command) => list_command(command, &cli, &tx).await,
         Commands::Project(command) => project_command(command, &cli, &tx).await,
         Commands::Reminder(command) => reminder_command(command, &cli, &tx).await,
@@ -150,6 +221,29 @@ async fn shell_command(command: &ShellCommands) -> Result<CommandResult, Error>
             let result = shell_commands::completions(args).await;
             Ok(build_command_result_without_config(result))
         }
+        ShellCommands::Aliases(args) => {
+            let result = shell_commands::aliases(args).await;
+            Ok(build_command_result_without_config(result))
+        }
+    }
+}
+
+async fn label_command(
+    command: &LabelCommands,
+    cli: &Cli,
+    tx: &UnboundedSender<Error>,
+) -> Result<CommandResult, Error> {
+    match command {
+        LabelCommands::Rename(args) => {
+            let mut config = fetch_config(cli, tx).await?;
+            let result = label_commands::rename(&mut config, args).await;
+            Ok(build_command_result(result, &config))
+        }
+        LabelCommands::Delete(args) => {
+            let mut config = fetch_config(cli, tx).await?;
+            let result = label_commands::delete(&mut config, args).await;
+            Ok(build_command_result(result, &config))
+        }
     }
 }
 
@@ -178,6 +272,11 @@ async fn section_command(
             let result = section_commands::create(&config, args).await;
             Ok(build_command_result(result, &config))
         }
+        SectionCommands::List(args) => {
+            let config = fetch_config(cli, tx).await?;
+            let result = section_commands::list(&config, args).await;
+            Ok(build_command_result(result, &config))
+        }
     }
 }
 
@@ -199,7 +298,7 @@ async fn project_command(
         }
         ProjectCommands::Remove(args) => {
             let mut config = fetch_config(cli, tx).await?;
-            let result = project_commands::remove(&mut config, args).await;
+            let result = with_config_diff(cli, project_commands::remove(&mut config, args)).await;
             Ok(build_command_result(result, &config))
         }
         ProjectCommands::Rename(args) => {
@@ -209,7 +308,7 @@ async fn project_command(
         }
         ProjectCommands::Import(args) => {
             let mut config = fetch_config(cli, tx).await?;
-            let result = project_commands::import(&mut config, args).await;
+            let result = with_config_diff(cli, project_commands::import(&mut config, args)).await;
             Ok(build_command_result(result, &config))
         }
         ProjectCommands::Empty(args) => {
@@ -222,6 +321,31 @@ async fn project_command(
             let result = project_commands::delete(&mut config, args).await;
             Ok(build_command_result(result, &config))
         }
+        ProjectCommands::Merge(args) => {
+            let mut config = fetch_config(cli, tx).await?;
+            let result = project_commands::merge(&mut config, args).await;
+            Ok(build_command_result(result, &config))
+        }
+        ProjectCommands::Stats(args) => {
+            let config = fetch_config(cli, tx).await?;
+            let result = project_commands::stats(&config, args).await;
+            Ok(build_command_result(result, &config))
+        }
+        ProjectCommands::Dedupe(args) => {
+            let config = fetch_config(cli, tx).await?;
+            let result = project_commands::dedupe(&config, args).await;
+            Ok(build_command_result(result, &config))
+        }
+        ProjectCommands::Favorite(args) => {
+            let mut config = fetch_config(cli, tx).await?;
+            let result = project_commands::favorite(&mut config, args, true).await;
+            Ok(build_command_result(result, &config))
+        }
+        ProjectCommands::Unfavorite(args) => {
+            let mut config = fetch_config(cli, tx).await?;
+            let result = project_commands::favorite(&mut config, args, false).await;
+            Ok(build_command_result(result, &config))
+        }
     }
 }
 
@@ -261,6 +385,21 @@ async fn task_command(
             let result = task_commands::comment(config.clone(), args).await;
             Ok(build_command_result(result, &config))
         }
+        TaskCommands::Links(args) => {
+            let config = fetch_config(cli, tx).await?;
+            let result = task_commands::links(config.clone(), args).await;
+            Ok(build_command_result(result, &config))
+        }
+        TaskCommands::Snooze(args) => {
+            let config = fetch_config(cli, tx).await?;
+            let result = task_commands::snooze(config.clone(), args).await;
+            Ok(build_command_result(result, &config))
+        }
+        TaskCommands::Move(args) => {
+            let config = fetch_config(cli, tx).await?;
+            let result = task_commands::move_task(config.clone(), args).await;
+            Ok(build_command_result(result, &config))
+        }
     }
 }
 
@@ -271,8 +410,8 @@ async fn list_command(
 ) -> Result<CommandResult, Error> {
     match command {
         ListCommands::View(args) => {
-            let mut config = fetch_config(cli, tx).await?;
-            let result = list_commands::view(&mut config, args).await;
+            let config = fetch_config(cli, tx).await?;
+            let result = list_commands::view(&config, args).await;
             Ok(build_command_result(result, &config))
         }
         ListCommands::Process(args) => {
@@ -326,7 +465,17 @@ async fn config_command(
     match command {
         ConfigCommands::SetTimezone(args) => {
             let config = fetch_config(cli, tx).await?;
-            let result = config_commands::set_timezone(config.clone(), args).await;
+            let result =
+                with_config_diff(cli, config_commands::set_timezone(config.clone(), args)).await;
+            Ok(build_command_result(result, &config))
+        }
+        ConfigCommands::SetDefaultProject(args) => {
+            let config = fetch_config(cli, tx).await?;
+            let result = with_config_diff(
+                cli,
+                config_commands::set_default_project(config.clone(), args),
+            )
+            .await;
             Ok(build_command_result(result, &config))
         }
         ConfigCommands::Edit(args) => {
@@ -343,6 +492,38 @@ async fn config_command(
             let result = config_commands::check(cli.config.clone()).await;
             Ok(build_command_result_without_config(result))
         }
+        ConfigCommands::ExportEnv(args) => {
+            if cli.output.is_some() {
+                return Err(Error::new(
+                    "config export-env",
+                    "The token is only printed to stdout, it can't be used with --output",
+                ));
+            }
+            let config = get_existing_config_exists(cli.config.clone()).await?;
+            let result = config_commands::export_env(&config, args);
+            Ok(build_command_result(result, &config))
+        }
+        ConfigCommands::DiffRemote(args) => {
+            let config = fetch_config(cli, tx).await?;
+            let result = config_commands::diff_remote(&config, args).await;
+            Ok(build_command_result(result, &config))
+        }
+        ConfigCommands::FlushQueue(_args) => {
+            // Loaded without fetch_config, which would send the queue before this command gets to
+            let config = get_existing_config_exists(cli.config.clone()).await?;
+            let config = with_cli_context(config, cli, tx);
+            ensure_auth_present(&config, "config flush-queue")?;
+            let result = config_commands::flush_queue(&config).await;
+            Ok(build_command_result(result, &config))
+        }
+        ConfigCommands::Validate(_args) => {
+            let result = config_commands::validate(cli.config.clone()).await;
+            Ok(build_command_result_without_config(result))
+        }
+        ConfigCommands::Migrate(_args) => {
+            let result = config_commands::migrate(cli.config.clone()).await;
+            Ok(build_command_result_without_config(result))
+        }
         ConfigCommands::About(args) => {
             let result = config_commands::about(args).await;
             Ok(build_command_result_without_config(result))
@@ -351,6 +532,11 @@ async fn config_command(
             let result = crate::config::config_reset(cli.config.clone(), args.force).await;
             Ok(build_command_result_without_config(result))
         }
+        ConfigCommands::SetToken(args) => {
+            let result =
+                with_config_diff(cli, config_commands::set_token(cli.config.clone(), args)).await;
+            Ok(build_command_result_without_config(result))
+        }
         ConfigCommands::Open(_args) => {
             let result = crate::config::config_open(cli.config.clone()).await;
             Ok(build_command_result_without_config(result))
@@ -370,6 +556,12 @@ async fn auth_command(command: &AuthCommands, cli: &Cli) -> Result<CommandResult
             let result = auth_commands::token(cli.config.clone(), args).await;
             Ok(build_command_result_without_config(result))
         }
+
+        AuthCommands::Logout(args) => {
+            let mut config = get_existing_config_exists(cli.config.clone()).await?;
+            let result = auth_commands::logout(&mut config, args).await;
+            Ok(build_command_result(result, &config))
+        }
     }
 }
 
@@ -384,6 +576,11 @@ async fn test_command(
             let result = test_commands::all(&config, args).await;
             Ok(build_command_result(result, &config))
         }
+        TestCommands::Endpoint(args) => {
+            let config = fetch_config(cli, tx).await?;
+            let result = test_commands::endpoint(&config, args).await;
+            Ok(build_command_result(result, &config))
+        }
     }
 }
 
@@ -403,14 +600,52 @@ fn build_command_result_without_config(result: Result<String, Error>) -> Command
     }
 }
 
+/// Runs a command that rewrites the config file and appends the keys it changed, unless --quiet
+async fn with_config_diff(
+    cli: &Cli,
+    command: impl Future<Output = Result<String, Error>>,
+) -> Result<String, Error> {
+    let path = crate::config::resolve_config_path(cli.config.clone()).await?;
+    let before = diff::snapshot(&path).await;
+    let text = command.await?;
+    if cli.quiet {
+        return Ok(text);
+    }
+
+    let after = diff::snapshot(&path).await;
+    let changes = diff::render(before.as_ref(), after.as_ref());
+    if changes.is_empty() {
+        Ok(text)
+    } else {
+        Ok(format!("{text}\n\n{changes}"))
+    }
+}
+
 /// Load existing config and ensure auth is present.
 async fn fetch_config(cli: &Cli, tx: &UnboundedSender<Error>) -> Result<Config, Error> {
-    let config = get_existing_config_exists(cli.config.clone()).await?;
+    let (path, source) = crate::config::resolve_config_path_with_source(cli.config.clone()).await?;
+    let config = get_existing_config_exists(Some(path.clone())).await?;
     let config = with_cli_context(config, cli, tx);
+    crate::debug::maybe_print(
+        &config,
+        &format!("Using config file {} from {source}", path.display()),
+    );
     crate::debug::maybe_print_redacted_config(&config);
     ensure_auth_present(&config, "fetch_config")?;
     let config = config.check_for_latest_version().await?;
-    config.maybe_set_timezone().await
+    let config = config.maybe_set_timezone().await?;
+    maybe_flush_queue(&config).await;
+    Ok(config)
+}
+
+/// Sends requests queued while Todoist could not be reached, reporting on stderr so that stdout stays the command's own.
+/// A queue that can't be sent yet never stops the command
+async fn maybe_flush_queue(config: &Config) {
+    match crate::todoist::flush_queue(config).await {
+        Ok(result) if result.is_unchanged() || config.args.quiet => (),
+        Ok(result) => eprintln!("{}\n", result.summary()),
+        Err(e) => crate::debug::log(config, LogLevel::Warn, &e.to_string()),
+    }
 }
 
 /// Only fetches the config if it exists, otherwise errors.
@@ -424,6 +659,15 @@ async fn get_existing_config_exists(config_path: Option<PathBuf>) -> Result<Conf
 fn with_cli_context(mut config: Config, cli: &Cli, tx: &UnboundedSender<Error>) -> Config {
     config.args.verbose = cli.verbose;
     config.args.timeout = cli.timeout;
+    config.args.log_file.clone_from(&cli.log_file);
+    config.args.log_level = cli.log_level;
+    config.args.quiet = cli.quiet;
+    config.args.iso_dates = cli.iso_dates;
+    config.args.ascii = cli.ascii;
+    config.args.max_concurrency = cli
+        .max_concurrency
+        .map(|n| usize::try_from(n).unwrap_or(usize::MAX));
+    config.args.command.clone_from(&cli.command_name);
     config.internal.tx = Some(tx.clone());
     config
 }
@@ -465,17 +709,57 @@ async fn fetch_project(project_name: Option<&str>, config: &Config) -> Result<Fl
             .find(|p| p.name == project_name)
             .map_or_else(
                 || {
-                    Err(Error::new(
-                        "fetch_project",
-                        "Could not find project in config",
-                    ))
+                    Err(
+                        Error::new("fetch_project", "Could not find project in config")
+                            .with_kind(ErrorKind::NotFound),
+                    )
                 },
                 |p| Ok(Flag::Project(p.to_owned())),
             ),
-        None => input::select(input::PROJECT, projects, config.mock_select).map(Flag::Project),
+        None => input::fuzzy_select(
+            input::PROJECT,
+            projects,
+            config.fuzzy_select(),
+            config.mock_select,
+        )
+        .map(Flag::Project),
     }
 }
 
+/// Resolves a comma separated list of project names from config
+async fn fetch_projects(project_names: &str, config: &Config) -> Result<Vec<Project>, Error> {
+    let projects = config.projects().await?;
+    if projects.is_empty() {
+        return Err(Error::new("fetch_projects", NO_PROJECTS_ERR));
+    }
+
+    project_names
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            projects
+                .iter()
+                .find(|p| p.name == name)
+                .cloned()
+                .ok_or_else(|| {
+                    let available = projects
+                        .iter()
+                        .map(|p| p.name.as_str())
+                        .collect::<Vec<&str>>()
+                        .join(", ");
+                    Error::new(
+                        "fetch_projects",
+                        &format!(
+                            "Could not find project '{name}' in config. Available projects: {available}"
+                        ),
+                    )
+                    .with_kind(ErrorKind::NotFound)
+                })
+        })
+        .collect()
+}
+
 fn fetch_filter(filter: Option<&str>, config: &Config) -> Result<Flag, Error> {
     if let Some(string) = filter {
         Ok(Flag::Filter(string.to_owned()))
@@ -507,6 +791,27 @@ async fn fetch_project_or_filter(
     }
 }
 
+/// Asks before a bulk change to `count` tasks, returning false when the user cancels.
+/// `--force` skips the prompt unless `confirm_threshold` is set and `count` is over it
+fn confirm_if_over(
+    config: &Config,
+    count: usize,
+    force: bool,
+    desc: &str,
+    action: &'static str,
+) -> Result<bool, Error> {
+    let over_threshold = config
+        .confirm_threshold
+        .is_some_and(|threshold| count > threshold);
+    if count == 0 || (force && !over_threshold) {
+        return Ok(true);
+    }
+
+    println!();
+    let options = vec![input::CANCEL, action];
+    Ok(input::select(desc, options, config.mock_select)? != input::CANCEL)
+}
+
 fn fetch_priority(priority: Option<u8>, config: &Config) -> Result<Priority, Error> {
     if let Some(priority) = priority::from_integer(priority)? {
         Ok(priority)
@@ -521,16 +826,18 @@ fn fetch_priority(priority: Option<u8>, config: &Config) -> Result<Priority, Err
     }
 }
 
-async fn maybe_fetch_labels(config: &Config, labels: &[String]) -> Result<Vec<String>, Error> {
+/// Fetches all labels with their colors, unless label names were given on the command line
+async fn maybe_fetch_labels(
+    config: &Config,
+    labels: &[String],
+) -> Result<Vec<labels::Label>, Error> {
     if labels.is_empty() {
-        let labels = labels::get_labels(config, false)
-            .await?
-            .into_iter()
-            .map(|l| l.name)
-            .collect();
-        Ok(labels)
+        labels::get_labels(config, false).await
     } else {
-        Ok(labels.to_vec())
+        Ok(labels
+            .iter()
+            .map(|name| labels::Label::from_name(name))
+            .collect())
     }
 }
 
@@ -538,6 +845,19 @@ async fn maybe_fetch_labels(config: &Config, labels: &[String]) -> Result<Vec<St
 mod tests {
     use super::*;
 
+    #[test]
+    fn command_name_resolves_aliases() {
+        let matches = Cli::command()
+            .try_get_matches_from(["tod", "t", "c", "--content", "milk"])
+            .expect("arguments should parse");
+        assert_eq!(command_name(&matches), Some("task create".to_string()));
+
+        let matches = Cli::command()
+            .try_get_matches_from(["tod", "--timeout", "5", "test", "all"])
+            .expect("arguments should parse");
+        assert_eq!(command_name(&matches), Some("test all".to_string()));
+    }
+
     #[test]
     fn build_command_result_uses_config_bell_settings() {
         let mut config = Config::default();
@@ -558,6 +878,57 @@ mod tests {
         assert!(matches!(result.result, Ok(text) if text == "ok"));
     }
 
+    #[tokio::test]
+    async fn fetch_projects_resolves_comma_separated_names() {
+        let config = crate::test::fixtures::config().await;
+
+        let projects = fetch_projects("myproject, myproject", &config)
+            .await
+            .expect("known projects should resolve");
+        assert_eq!(projects.len(), 2);
+        assert!(projects.iter().all(|p| p.name == "myproject"));
+
+        let error = fetch_projects("myproject,missing", &config)
+            .await
+            .expect_err("unknown project should fail");
+        assert_eq!(error.source, "fetch_projects");
+        assert!(error.message.contains("'missing'"));
+        assert!(error.message.contains("Available projects: myproject"));
+    }
+
+    #[tokio::test]
+    async fn confirm_if_over_respects_force_and_threshold() {
+        // Selecting the first option cancels whenever a prompt is shown
+        let mut config = crate::test::fixtures::config().await.mock_select(0);
+
+        assert_eq!(
+            confirm_if_over(&config, 0, false, "", input::DELETE),
+            Ok(true)
+        );
+        assert_eq!(
+            confirm_if_over(&config, 3, false, "", input::DELETE),
+            Ok(false)
+        );
+        assert_eq!(
+            confirm_if_over(&config, 3, true, "", input::DELETE),
+            Ok(true)
+        );
+
+        config.confirm_threshold = Some(5);
+        assert_eq!(
+            confirm_if_over(&config, 5, true, "", input::DELETE),
+            Ok(true)
+        );
+        assert_eq!(
+            confirm_if_over(&config, 6, true, "", input::DELETE),
+            Ok(false)
+        );
+        assert_eq!(
+            confirm_if_over(&config, 5, false, "", input::DELETE),
+            Ok(false)
+        );
+    }
+
     #[test]
     fn ensure_auth_present_errors_when_token_missing() {
         let mut config = Config::default();
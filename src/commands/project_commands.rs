@@ -1,6 +1,17 @@
 use clap::{Parser, Subcommand};
-
-use crate::{config::Config, debug, errors::Error, input, lists::Flag, projects, todoist};
+use std::path::PathBuf;
+
+use super::Strict;
+use crate::{
+    config::Config,
+    debug,
+    errors::Error,
+    input,
+    lists::Flag,
+    projects::{self, EmptyStrategy, MergeSource, Project},
+    templates::ProjectTemplate,
+    todoist,
+};
 
 #[derive(Subcommand, Debug, Clone)]
 pub enum ProjectCommands {
@@ -21,7 +32,7 @@ pub enum ProjectCommands {
     Delete(Delete),
 
     #[clap(alias = "n")]
-    /// (n) Rename a project in config (not in Todoist)
+    /// (n) Rename a project in config, use --remote to rename it in Todoist as well
     Rename(Rename),
 
     #[clap(alias = "i")]
@@ -31,10 +42,38 @@ pub enum ProjectCommands {
     #[clap(alias = "e")]
     /// (e) Empty a project by putting tasks in other projects
     Empty(Empty),
+
+    #[clap(alias = "m")]
+    /// (m) Move all tasks from one project into another
+    Merge(Merge),
+
+    #[clap(alias = "s")]
+    /// (s) Show how many tasks a project has, how many are overdue or due today, and a count for each priority
+    Stats(Stats),
+
+    #[clap(alias = "u")]
+    /// (u) Find tasks with the same content in a project and keep one of each
+    Dedupe(Dedupe),
+
+    #[clap(alias = "f")]
+    /// (f) Mark a project as favorite in Todoist
+    Favorite(Favorite),
+
+    #[clap(alias = "uf")]
+    /// (uf) Remove a project from favorites in Todoist
+    Unfavorite(Favorite),
 }
 
 #[derive(Parser, Debug, Clone)]
-pub struct List {}
+pub struct List {
+    #[arg(short, long, default_value_t = false, conflicts_with = "json")]
+    /// Show the number of active tasks in each project, i.e. "Work (12)"
+    counts: bool,
+
+    #[arg(long, default_value_t = false)]
+    /// Print the projects as a JSON array of id, name, is_favorite and color
+    json: bool,
+}
 
 #[derive(Parser, Debug, Clone)]
 pub struct Create {
@@ -49,6 +88,14 @@ pub struct Create {
     #[arg(short, long, default_value_t = false)]
     /// Whether the project is marked as favorite
     is_favorite: bool,
+
+    #[arg(short, long)]
+    /// Path to a JSON template listing sections and tasks to create in the new project
+    template: Option<PathBuf>,
+
+    #[arg(long, default_value_t = false, requires = "template")]
+    /// Validate the template and show what would be created without calling Todoist
+    dry_run: bool,
 }
 
 #[derive(Parser, Debug, Clone)]
@@ -62,6 +109,15 @@ pub struct Import {
     /// Add all projects to config that are not there already
     auto: bool,
 
+    #[arg(
+        short = 's',
+        long,
+        default_value_t = false,
+        conflicts_with_all = ["auto", "project", "id"]
+    )]
+    /// Pick several projects that are not in config yet from a list and add them all at once
+    select: bool,
+
     #[arg(short = 'p', long, conflicts_with = "id")]
     /// Import a specific project by name from Todoist
     project: Option<String>,
@@ -114,12 +170,88 @@ pub struct Rename {
     #[arg(short, long)]
     /// New project name
     name: Option<String>,
+
+    #[arg(short, long, default_value_t = false)]
+    /// Also rename the project in Todoist, not just in config
+    remote: bool,
+}
+#[derive(Parser, Debug, Clone)]
+pub struct Merge {
+    #[arg(long)]
+    /// Project to move the tasks out of
+    from: Option<String>,
+
+    #[arg(long)]
+    /// Project to move the tasks into
+    into: Option<String>,
+
+    #[arg(short, long, default_value_t = false)]
+    /// Skip confirming the number of tasks to move
+    force: bool,
+
+    #[arg(short, long, default_value_t = false, conflicts_with = "delete")]
+    /// Archive the source project in Todoist once it is empty
+    archive: bool,
+
+    #[arg(short, long, default_value_t = false)]
+    /// Delete the source project from Todoist once it is empty
+    delete: bool,
+
+    #[command(flatten)]
+    strict: Strict,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct Stats {
+    #[arg(short, long)]
+    /// Project to summarise
+    project: Option<String>,
+
+    #[arg(long, default_value_t = false)]
+    /// Print the summary as a JSON object
+    json: bool,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct Dedupe {
+    #[arg(short, long)]
+    /// Project to look for duplicate tasks in
+    project: Option<String>,
+
+    #[arg(short, long, default_value_t = false)]
+    /// Keep the oldest task of each duplicate group without asking
+    auto: bool,
+
+    #[arg(short, long, default_value_t = false)]
+    /// Delete the extra copies instead of completing them
+    delete: bool,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct Favorite {
+    #[arg(short, long)]
+    /// Project to mark or unmark as favorite
+    project: Option<String>,
 }
+
 #[derive(Parser, Debug, Clone)]
 pub struct Empty {
     #[arg(short, long)]
     /// Project to remove
     project: Option<String>,
+
+    #[arg(short, long, value_enum, default_value_t = EmptyStrategy::Prompt)]
+    /// Where tasks go: ask for each one, move all into --into, or use label_projects from config
+    strategy: EmptyStrategy,
+
+    #[arg(
+        short,
+        long,
+        value_name = "PROJECT",
+        required_if_eq("strategy", "single")
+    )]
+    /// The project to move every task into with --strategy single
+    into: Option<String>,
 }
 
 pub async fn create(config: &mut Config, args: &Create) -> Result<String, Error> {
@@ -127,15 +259,31 @@ pub async fn create(config: &mut Config, args: &Create) -> Result<String, Error>
         name,
         description,
         is_favorite,
+        template,
+        dry_run,
     } = args;
+    let template = match template {
+        Some(path) => Some(ProjectTemplate::load(path).await?),
+        None => None,
+    };
     let name = super::fetch_string(name.as_deref(), config, input::NAME)?;
+    if *dry_run && let Some(template) = &template {
+        return Ok(template.preview(&name));
+    }
     let description = description.as_deref().unwrap_or_default();
 
-    projects::create(config, name, description, *is_favorite).await
+    projects::create(config, name, description, *is_favorite, template.as_ref()).await
 }
 
-pub async fn list(config: &mut Config, _args: &List) -> Result<String, Error> {
-    projects::list(config).await
+pub async fn list(config: &mut Config, args: &List) -> Result<String, Error> {
+    let List { counts, json } = args;
+    if *json {
+        projects::list_json(config).await
+    } else if *counts {
+        projects::list_with_counts(config).await
+    } else {
+        projects::list(config).await
+    }
 }
 
 pub async fn remove(config: &mut Config, args: &Remove) -> Result<String, Error> {
@@ -176,16 +324,10 @@ pub async fn delete(config: &mut Config, args: &Delete) -> Result<String, Error>
         };
         let tasks = todoist::all_tasks_by_project(config, &project, None).await?;
 
-        if !force && !tasks.is_empty() {
-            println!();
-            let options = vec![input::CANCEL, input::DELETE];
-            let num_tasks = tasks.len();
-            let desc = format!("Project has {num_tasks} tasks, confirm deletion");
-            let result = input::select(&desc, options, config.mock_select)?;
-
-            if result == input::CANCEL {
-                return Ok("Cancelled".into());
-            }
+        let num_tasks = tasks.len();
+        let desc = format!("Project has {num_tasks} tasks, confirm deletion");
+        if !super::confirm_if_over(config, num_tasks, *force, &desc, input::DELETE)? {
+            return Ok("Cancelled".into());
         }
         let value = projects::delete(config, &project).await;
 
@@ -196,7 +338,11 @@ pub async fn delete(config: &mut Config, args: &Delete) -> Result<String, Error>
 }
 
 pub async fn rename(config: &mut Config, args: &Rename) -> Result<String, Error> {
-    let Rename { project, name } = args;
+    let Rename {
+        project,
+        name,
+        remote,
+    } = args;
     let project = match super::fetch_project(project.as_deref(), config).await? {
         Flag::Project(project) => project,
         Flag::Filter(_) => unreachable!(),
@@ -205,28 +351,142 @@ pub async fn rename(config: &mut Config, args: &Rename) -> Result<String, Error>
         config,
         &format!("Calling projects::rename with project:\n{project}"),
     );
-    projects::rename(config, &project, name.as_deref()).await
+    projects::rename(config, &project, name.as_deref(), *remote).await
 }
 
 pub async fn import(config: &mut Config, args: &Import) -> Result<String, Error> {
-    let Import { auto, project, id } = args;
+    let Import {
+        auto,
+        select,
+        project,
+        id,
+    } = args;
+    if *select {
+        return projects::import_selected(config).await;
+    }
     projects::import(config, auto, project.as_deref(), id.as_deref()).await
 }
 
+pub async fn merge(config: &mut Config, args: &Merge) -> Result<String, Error> {
+    let Merge {
+        from,
+        into,
+        force,
+        archive,
+        delete,
+        strict,
+    } = args;
+    let from = fetch_single_project(from.as_deref(), config).await?;
+    let into = fetch_single_project(into.as_deref(), config).await?;
+    if from.id == into.id {
+        return Err(Error::new(
+            "project_merge",
+            "Cannot merge a project into itself",
+        ));
+    }
+
+    let tasks = todoist::all_tasks_by_project(config, &from, None).await?;
+    let desc = format!(
+        "Move {} tasks from '{}' into '{}'",
+        tasks.len(),
+        from.name,
+        into.name
+    );
+    if !super::confirm_if_over(config, tasks.len(), *force, &desc, input::MERGE)? {
+        return Ok("Cancelled".into());
+    }
+
+    let source = match (archive, delete) {
+        (true, _) => MergeSource::Archive,
+        (_, true) => MergeSource::Delete,
+        _ => MergeSource::Keep,
+    };
+    projects::merge(config, &from, &into, tasks, source, strict.strict).await
+}
+
+pub async fn stats(config: &Config, args: &Stats) -> Result<String, Error> {
+    let Stats { project, json } = args;
+    let project = fetch_single_project(project.as_deref(), config).await?;
+    projects::stats(config, &project, *json).await
+}
+
+pub async fn dedupe(config: &Config, args: &Dedupe) -> Result<String, Error> {
+    let Dedupe {
+        project,
+        auto,
+        delete,
+    } = args;
+    let project = fetch_single_project(project.as_deref(), config).await?;
+    projects::dedupe(config, &project, *auto, *delete).await
+}
+
+pub async fn favorite(
+    config: &mut Config,
+    args: &Favorite,
+    is_favorite: bool,
+) -> Result<String, Error> {
+    let Favorite { project } = args;
+    let project = fetch_single_project(project.as_deref(), config).await?;
+    projects::favorite(config, &project, is_favorite).await
+}
+
+async fn fetch_single_project(project: Option<&str>, config: &Config) -> Result<Project, Error> {
+    match super::fetch_project(project, config).await? {
+        Flag::Project(project) => Ok(project),
+        Flag::Filter(_) => unreachable!(),
+    }
+}
+
 pub async fn empty(config: &mut Config, args: &Empty) -> Result<String, Error> {
-    let Empty { project } = args;
+    let Empty {
+        project,
+        strategy,
+        into,
+    } = args;
+    if into.is_some() && *strategy != EmptyStrategy::Single {
+        return Err(Error::new(
+            "project_empty",
+            "--into only applies with --strategy single",
+        ));
+    }
     let project = match super::fetch_project(project.as_deref(), config).await? {
         Flag::Project(project) => project,
         Flag::Filter(_) => unreachable!(),
     };
+    let into = match (strategy, into) {
+        (EmptyStrategy::Single, Some(name)) => {
+            match super::fetch_project(Some(name), config).await? {
+                Flag::Project(into) if into.id == project.id => {
+                    return Err(Error::new(
+                        "project_empty",
+                        "--into must be a different project from the one being emptied",
+                    ));
+                }
+                Flag::Project(into) => Some(into),
+                Flag::Filter(_) => unreachable!(),
+            }
+        }
+        _ => None,
+    };
+    // Emptying already asks where each task goes, so it only confirms when over confirm_threshold
+    if config.confirm_threshold.is_some() {
+        let count = todoist::all_tasks_by_project(config, &project, None)
+            .await?
+            .len();
+        let desc = format!("Empty {count} tasks from '{}'", project.name);
+        if !super::confirm_if_over(config, count, true, &desc, input::EMPTY)? {
+            return Ok("Cancelled".into());
+        }
+    }
 
-    projects::empty(config, &project).await
+    projects::empty(config, &project, *strategy, into.as_ref()).await
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::test;
+    use crate::test::responses::ResponseFromFile;
 
     #[tokio::test]
     async fn remove_rejects_conflicting_all_and_auto_flags() {
@@ -245,6 +505,19 @@ mod tests {
         assert_eq!(error.message, "Incorrect flags provided");
     }
 
+    #[test]
+    fn list_counts_flag_parses() {
+        let args = List::try_parse_from(["tod", "--counts"]).expect("list arguments should parse");
+        assert!(args.counts);
+
+        let args = List::try_parse_from(["tod"]).expect("list arguments should parse");
+        assert!(!args.counts);
+        assert!(!args.json);
+
+        let result = List::try_parse_from(["tod", "--json", "--counts"]);
+        assert!(result.is_err());
+    }
+
     #[test]
     fn delete_force_flag_parses() {
         let args =
@@ -252,6 +525,13 @@ mod tests {
         assert!(args.force);
     }
 
+    #[test]
+    fn favorite_project_flag_parses() {
+        let args = Favorite::try_parse_from(["tod", "-p", "myproject"])
+            .expect("favorite arguments should parse");
+        assert_eq!(args.project.as_deref(), Some("myproject"));
+    }
+
     #[test]
     fn rename_name_flag_parses() {
         let args = Rename::try_parse_from(["tod", "-p", "myproject", "-n", "renamed"])
@@ -260,6 +540,18 @@ mod tests {
         assert_eq!(args.name.as_deref(), Some("renamed"));
     }
 
+    #[tokio::test]
+    async fn empty_rejects_into_without_single_strategy() {
+        let mut config = test::fixtures::config().await;
+        let args = Empty::try_parse_from(["tod", "-p", "myproject", "--into", "other"])
+            .expect("empty arguments should parse");
+
+        let error = empty(&mut config, &args)
+            .await
+            .expect_err("--into with the prompt strategy should fail");
+        assert_eq!(error.message, "--into only applies with --strategy single");
+    }
+
     #[tokio::test]
     async fn rename_uses_name_flag_without_prompt() {
         let mut config = test::fixtures::config()
@@ -270,10 +562,14 @@ mod tests {
         let args = Rename {
             project: Some("myproject".to_string()),
             name: Some("renamed-project".to_string()),
+            remote: false,
         };
 
         let result = rename(&mut config, &args).await;
-        assert_eq!(result, Ok("✓".to_string()));
+        assert_eq!(
+            result,
+            Ok("Renamed project 'myproject' to 'renamed-project'".to_string())
+        );
 
         let projects = config
             .projects()
@@ -287,4 +583,138 @@ mod tests {
         assert!(project_names.contains(&"renamed-project"));
         assert!(!project_names.contains(&"myproject"));
     }
+
+    #[tokio::test]
+    async fn rename_with_remote_renames_in_todoist() {
+        let renamed = Project {
+            name: "renamed-project".to_string(),
+            ..test::fixtures::project()
+        };
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/api/v1/projects/123")
+            .match_body(mockito::Matcher::Json(
+                serde_json::json!({"name": "renamed-project"}),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(serde_json::to_string(&renamed).expect("project should serialize"))
+            .create_async()
+            .await;
+
+        let mut config = test::fixtures::config()
+            .await
+            .with_mock_url(server.url())
+            .create()
+            .await
+            .expect("creating config should succeed");
+        let args = Rename {
+            project: Some("myproject".to_string()),
+            name: Some("renamed-project".to_string()),
+            remote: true,
+        };
+
+        let result = rename(&mut config, &args).await;
+        assert_eq!(
+            result,
+            Ok("Renamed project 'myproject' to 'renamed-project'".to_string())
+        );
+        mock.assert();
+
+        let projects = config
+            .projects()
+            .await
+            .expect("loading projects should succeed");
+        assert_eq!(projects, vec![renamed]);
+    }
+
+    #[tokio::test]
+    async fn merge_rejects_merging_project_into_itself() {
+        let mut config = test::fixtures::config().await;
+        let args = Merge {
+            from: Some("myproject".to_string()),
+            into: Some("myproject".to_string()),
+            force: true,
+            archive: false,
+            delete: false,
+            strict: Strict::default(),
+        };
+
+        let error = merge(&mut config, &args)
+            .await
+            .expect_err("merging a project into itself should fail");
+        assert_eq!(error.source, "project_merge");
+    }
+
+    #[tokio::test]
+    async fn merge_moves_tasks_and_archives_source() {
+        let mut server = mockito::Server::new_async().await;
+        let tasks_mock = server
+            .mock("GET", "/api/v1/tasks/?project_id=123&limit=200")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::TodayTasks.read().await)
+            .create_async()
+            .await;
+        let move_mock = server
+            .mock("POST", "/api/v1/tasks/6Xqhv4cwxgjwG9w8/move")
+            .match_body(mockito::Matcher::Json(
+                serde_json::json!({"project_id": "456"}),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::TodayTask.read().await)
+            .create_async()
+            .await;
+        let archive_mock = server
+            .mock("POST", "/api/v1/projects/123/archive")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::Project.read().await)
+            .create_async()
+            .await;
+
+        let mut config = test::fixtures::config()
+            .await
+            .with_mock_url(server.url())
+            .mock_select(1);
+        config.add_project(Project {
+            id: "456".to_string(),
+            name: "destination".to_string(),
+            ..test::fixtures::project()
+        });
+        let mut config = config
+            .create()
+            .await
+            .expect("creating config should succeed");
+        let args = Merge {
+            from: Some("myproject".to_string()),
+            into: Some("destination".to_string()),
+            force: false,
+            archive: true,
+            delete: false,
+            strict: Strict::default(),
+        };
+
+        let result = merge(&mut config, &args).await;
+        assert_eq!(
+            result,
+            Ok(crate::format::green_string(
+                "Moved 1 task(s) from 'myproject' into 'destination'\nArchived 'myproject'"
+            ))
+        );
+        tasks_mock.assert();
+        move_mock.assert();
+        archive_mock.assert();
+
+        let projects = config
+            .projects()
+            .await
+            .expect("loading projects should succeed");
+        let project_names = projects
+            .iter()
+            .map(|project| project.name.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(project_names, vec!["destination"]);
+    }
 }
@@ -1,4 +1,4 @@
-use crate::{config::Config, errors::Error, format, input, lists::Flag, todoist};
+use crate::{config::Config, errors::Error, format, input, lists::Flag, sections, todoist};
 use clap::{Parser, Subcommand};
 
 #[derive(Subcommand, Debug, Clone)]
@@ -6,6 +6,10 @@ pub enum SectionCommands {
     #[clap(alias = "c")]
     /// (c) Create a new section for a project in Todoist
     Create(Create),
+
+    #[clap(alias = "l")]
+    /// (l) List the sections of a project with their task counts
+    List(List),
 }
 
 #[derive(Parser, Debug, Clone)]
@@ -19,6 +23,27 @@ pub struct Create {
     project: Option<String>,
 }
 
+#[derive(Parser, Debug, Clone)]
+pub struct List {
+    #[arg(short, long)]
+    /// Project to list the sections of
+    project: Option<String>,
+
+    #[arg(long, default_value_t = false)]
+    /// Print the sections as a JSON array of id, name and task count
+    json: bool,
+}
+
+pub async fn list(config: &Config, args: &List) -> Result<String, Error> {
+    let List { project, json } = args;
+    let project = match super::fetch_project(project.as_deref(), config).await? {
+        Flag::Project(project) => project,
+        Flag::Filter(_) => unreachable!(),
+    };
+
+    sections::list(config, &project, *json).await
+}
+
 pub async fn create(config: &Config, args: &Create) -> Result<String, Error> {
     let Create { name, project } = args;
     let name = super::fetch_string(name.as_deref(), config, input::NAME)?;
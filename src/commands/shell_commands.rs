@@ -10,6 +10,10 @@ pub enum ShellCommands {
     #[clap(alias = "b")]
     /// (b) Generate shell completions for various shells. Does not need a configuration file
     Completions(Completions),
+
+    #[clap(alias = "a")]
+    /// (a) Generate short aliases for the most common commands, i.e. tq for task quick-add. Does not need a configuration file
+    Aliases(Aliases),
 }
 
 #[derive(Parser, Debug, Clone)]
@@ -17,9 +21,19 @@ pub struct Completions {
     shell: Shell,
 }
 
+#[derive(Parser, Debug, Clone)]
+pub struct Aliases {
+    shell: Shell,
+}
+
 #[allow(clippy::unused_async)]
 pub async fn completions(args: &Completions) -> Result<String, Error> {
     shell::generate_completions(args.shell);
 
     Ok(String::new())
 }
+
+#[allow(clippy::unused_async)]
+pub async fn aliases(args: &Aliases) -> Result<String, Error> {
+    Ok(shell::generate_aliases(args.shell))
+}
@@ -1,15 +1,19 @@
 use clap::{Parser, Subcommand};
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
 
 use crate::{
-    config::Config,
-    errors::Error,
-    filters, format,
+    config::{self, Config},
+    errors::{Error, ErrorKind},
+    filters, format, id,
     input::{self, DateTimeInput},
     labels,
     lists::Flag,
-    projects, sections,
-    tasks::{self, TaskAttribute, priority::Priority},
-    todoist,
+    projects::{self, Project},
+    sections::{self, Section},
+    shell,
+    tasks::{self, SortOptions, SortOrder, Task, TaskAttribute, priority::Priority},
+    time, todoist, users,
 };
 
 #[derive(Subcommand, Debug, Clone)]
@@ -20,7 +24,7 @@ pub enum TaskCommands {
 
     #[clap(alias = "c")]
     /// (c) Create a new task (without NLP)
-    Create(Create),
+    Create(Box<Create>),
 
     #[clap(alias = "e")]
     /// (e) Edit an existing task's content
@@ -31,12 +35,24 @@ pub enum TaskCommands {
     Next(Next),
 
     #[clap(alias = "o")]
-    /// (o) Complete the last task fetched with the next command
+    /// (o) Complete the last task fetched with the next command, or pick one with --project or --filter
     Complete(Complete),
 
     #[clap(alias = "m")]
-    /// (m) Add a comment to the last task fetched with the next command
+    /// (m) Add a comment to the last task fetched with the next command, or list its comments with --list
     Comment(Comment),
+
+    #[clap(alias = "l")]
+    /// (l) List the markdown links in the content and description of the last task fetched with the next command
+    Links(Links),
+
+    #[clap(alias = "z")]
+    /// (z) Push back the due date of the last task fetched with the next command, i.e. by 2h, 3d or to tomorrow
+    Snooze(Snooze),
+
+    #[clap(alias = "v")]
+    /// (v) Move the last task fetched with the next command to another section of its project
+    Move(Move),
 }
 
 #[derive(Parser, Debug, Clone)]
@@ -45,6 +61,22 @@ pub struct QuickAdd {
     /// Content for task. Add a reminder at the end by prefixing the natural language date with `!`.
     /// Example: Get milk on sunday !saturday 4pm
     content: Option<Vec<String>>,
+
+    #[arg(value_name = "CONTENT", conflicts_with = "content")]
+    /// Content for task without --content, i.e. tod task q buy milk tomorrow
+    words: Vec<String>,
+
+    #[arg(short, long)]
+    /// The project into which the task will be added, overrides default_project in config
+    project: Option<String>,
+
+    #[arg(long, default_value_t = false, conflicts_with = "project")]
+    /// Add the task to the Todoist inbox, ignoring default_project in config
+    inbox: bool,
+
+    #[arg(long, value_name = "ZONE")]
+    /// IANA timezone the due time in the content is in, i.e. Europe/Berlin. Defaults to the config timezone
+    due_tz: Option<String>,
 }
 
 #[derive(Parser, Debug, Clone)]
@@ -53,22 +85,58 @@ pub struct Create {
     /// The project into which the task will be added
     project: Option<String>,
 
+    #[arg(long, default_value_t = false, conflicts_with = "project")]
+    /// Add the task to the Todoist inbox without it being in config or picking a project
+    inbox: bool,
+
     #[arg(short = 'u', long)]
     /// Date date in format YYYY-MM-DD, YYYY-MM-DD HH:MM, or natural language
     due: Option<String>,
 
+    #[arg(long, value_name = "PATTERN", conflicts_with = "due")]
+    /// Make the task recurring with a Todoist pattern such as "every monday", "every 2 weeks" or "every weekday at 9am". Sets the due date, so can't be used with --due
+    repeat: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "LANG",
+        value_parser = clap::builder::PossibleValuesParser::new(config::LANGUAGES)
+    )]
+    /// Language the natural language due date is written in, for this task only. Overrides language in config
+    due_lang: Option<String>,
+
+    #[arg(long, value_name = "ZONE", requires = "due")]
+    /// IANA timezone the --due time is in, i.e. Europe/Berlin, for this task only. Needs --due in format YYYY-MM-DD HH:MM
+    due_tz: Option<String>,
+
     #[arg(short, long, default_value_t = String::new())]
     /// Description for task
     description: String,
 
+    #[arg(long, default_value_t = false, requires = "content", conflicts_with_all = ["description", "content_from_file", "editor"])]
+    /// Read the description from everything piped into stdin, i.e. command | tod task create -c "Review output" --description-stdin
+    description_stdin: bool,
+
     #[arg(short, long)]
     /// Content for task
     content: Option<String>,
 
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["content", "description"])]
+    /// Read the content from the first line of a file, and the description from the rest of it
+    content_from_file: Option<PathBuf>,
+
+    #[arg(long, default_value_t = false, conflicts_with_all = ["content", "content_from_file", "description"])]
+    /// Write the content in $EDITOR, the first line is the content and the rest is the description
+    editor: bool,
+
     #[arg(short, long, default_value_t = false)]
     /// Do not prompt for section
     no_section: bool,
 
+    #[arg(long, conflicts_with = "no_section")]
+    /// Name of the section in the project to add the task to, instead of prompting for one
+    section: Option<String>,
+
     #[arg(short = 'r', long)]
     /// Priority from 1 (without priority) to 4 (highest)
     priority: Option<u8>,
@@ -76,10 +144,26 @@ pub struct Create {
     #[arg(short, long)]
     /// List of labels to choose from, to be applied to each entry. Use flag once per label
     label: Vec<String>,
+
+    #[arg(long, value_name = "LABELS")]
+    /// Comma separated labels, i.e. "home,errand,urgent". Combines with --label
+    labels: Option<String>,
+
+    #[arg(short, long)]
+    /// Name or email of the collaborator to assign the task to. Ignored for projects that aren't shared
+    assignee: Option<String>,
+
+    #[arg(long, value_name = "WHEN")]
+    /// Add a reminder at this time, in format YYYY-MM-DD HH:MM or natural language. Use flag once per reminder
+    remind: Vec<String>,
 }
 
 #[derive(Parser, Debug, Clone)]
 pub struct Edit {
+    #[arg(value_parser = clap::value_parser!(u32).range(1..))]
+    /// Number of the task in the last list view, use with --project or --filter if that was not the last list viewed
+    index: Option<u32>,
+
     #[arg(short, long)]
     /// The project containing the task
     project: Option<String>,
@@ -87,6 +171,10 @@ pub struct Edit {
     #[arg(short, long)]
     /// The filter containing the task
     filter: Option<String>,
+
+    #[arg(long, default_value_t = false)]
+    /// Edit the content and description in $EDITOR instead of choosing attributes
+    editor: bool,
 }
 
 #[derive(Parser, Debug, Clone)]
@@ -98,20 +186,127 @@ pub struct Next {
     #[arg(short, long)]
     /// The filter containing the task
     filter: Option<String>,
+
+    #[arg(short, long, default_value_t = false, conflicts_with = "reset_skips")]
+    /// Pass over the current next task without completing it and get the one after
+    skip: bool,
+
+    #[arg(long, default_value_t = false)]
+    /// Clear the tasks passed over with --skip so they are shown again
+    reset_skips: bool,
+
+    #[arg(short = 't', long, default_value_t = SortOrder::Value)]
+    /// How to pick the next task, `random` picks any task that is due or undated
+    sort: SortOrder,
+
+    #[arg(long)]
+    /// Seed for --sort random, the same seed picks the same task from the same list
+    seed: Option<u64>,
+
+    #[arg(short, long, default_value_t = false)]
+    /// Open the task in Todoist in the browser, it is still stored as the next task. Skipped with --quiet
+    open: bool,
+
+    #[arg(long, default_value_t = false)]
+    /// List the links in the task's comments beneath it
+    links: bool,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct Complete {
+    #[arg(value_parser = clap::value_parser!(u32).range(1..))]
+    /// Number of the task in the last list view, use with --project or --filter if that was not the last list viewed
+    index: Option<u32>,
+
+    #[arg(short, long)]
+    /// Pick the task to complete from this project instead of using the next task
+    project: Option<String>,
+
+    #[arg(short, long)]
+    /// Pick the task to complete from this filter instead of using the next task
+    filter: Option<String>,
+
+    #[arg(long, default_value_t = false)]
+    /// Skip the confirmation when completing a recurring task
+    force: bool,
+
+    #[arg(short, long, value_name = "TEXT")]
+    /// Leave a comment on the task before completing it. The task is not completed if the comment fails
+    comment: Option<String>,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct Links {
+    #[arg(short, long, default_value_t = false)]
+    /// Open the first link in the default browser
+    open_first: bool,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct Snooze {
+    #[arg(required = true, num_args(1..))]
+    /// How long to snooze for, such as 30m, 2h or 3d, counted from the current due date or from now when there isn't one.
+    /// Anything else is used as a natural language date, i.e. tomorrow or next monday
+    when: Vec<String>,
+
+    #[arg(long, default_value_t = false)]
+    /// Snooze a recurring task anyway, restarting its recurrence from the new date
+    force: bool,
 }
 
 #[derive(Parser, Debug, Clone)]
-pub struct Complete {}
+pub struct Move {
+    #[arg(short, long, conflicts_with = "no_section")]
+    /// Name of the section to move the task into, ignoring case. Prompts for one when not given
+    section: Option<String>,
+
+    #[arg(short, long, default_value_t = false)]
+    /// Move the task out of its section, keeping it in the same project
+    no_section: bool,
+}
 
 #[derive(Parser, Debug, Clone)]
 pub struct Comment {
     #[arg(short, long)]
     /// Content for comment
     content: Option<String>,
+
+    #[arg(short, long)]
+    /// Path of a file to upload and attach to the comment
+    file: Option<PathBuf>,
+
+    #[arg(short, long, default_value_t = false, conflicts_with_all = ["content", "file"])]
+    /// List the comments on the task instead of adding one, newest first
+    list: bool,
+
+    #[arg(long, default_value_t = 10, requires = "list", value_parser = clap::value_parser!(u32).range(1..))]
+    /// Number of comments to show on each page of --list
+    limit: u32,
+
+    #[arg(long, default_value_t = 1, requires = "list", value_parser = clap::value_parser!(u32).range(1..))]
+    /// Page of comments to show with --list
+    page: u32,
+
+    #[arg(short, long, default_value_t = false, requires = "list")]
+    /// Show the oldest comments first with --list
+    reverse: bool,
 }
 pub async fn quick_add(config: &Config, args: &QuickAdd) -> Result<String, Error> {
-    let QuickAdd { content } = args;
-    let maybe_string = content.as_ref().map(|c| c.join(" "));
+    let QuickAdd {
+        content,
+        words,
+        project,
+        inbox,
+        due_tz,
+    } = args;
+    if let Some(zone) = due_tz {
+        time::iana_timezone(zone)?;
+    }
+    let maybe_string = match content {
+        Some(content) => Some(content.join(" ")),
+        None if !words.is_empty() => Some(words.join(" ")),
+        None => None,
+    };
     let content = super::fetch_string(maybe_string.as_deref(), config, input::CONTENT)?;
     let (content, reminder) = if let Some(index) = content.find('!') {
         let (before, after) = content.split_at(index);
@@ -123,26 +318,83 @@ pub async fn quick_add(config: &Config, args: &QuickAdd) -> Result<String, Error
     } else {
         (content, None)
     };
-    todoist::quick_create_task(config, &content, reminder).await?;
+    let project = match project {
+        Some(name) => match super::fetch_project(Some(name), config).await? {
+            Flag::Project(project) => Some(project),
+            Flag::Filter(_) => unreachable!(),
+        },
+        // Todoist quick adds to the inbox when no project is sent
+        None if *inbox => None,
+        None => config.default_project().await?,
+    };
+    let task = todoist::quick_create_task(config, &content, reminder, project.as_ref()).await?;
+    if let Some(zone) = due_tz
+        && let Err(e) = apply_due_tz(config, &task, zone).await
+    {
+        // Todoist only parses the due time when it creates the task, so take the task back out rather than leave it in the wrong zone
+        todoist::delete_task(config, &task.id, false).await?;
+        let message = format!(
+            "Did not add the task, --due-tz could not be applied: {}",
+            e.message
+        );
+        return Err(Error::new("task_quick_add", &message).with_kind(e.kind));
+    }
     Ok(format::green_string("✓"))
 }
 
+/// Moves the due time Todoist parsed from the content to the same time on the clock in zone
+async fn apply_due_tz(config: &Config, task: &Task, zone: &str) -> Result<(), Error> {
+    let Some(due_datetime) = task
+        .due
+        .as_ref()
+        .map(|due| time::move_due_to_zone(due, zone, config))
+        .transpose()?
+        .flatten()
+    else {
+        return Err(Error::new("task_quick_add", "the content has no due time"));
+    };
+    todoist::update_task_due_datetime(config, task, &due_datetime, true).await?;
+    Ok(())
+}
+
 /// User does not want to use sections
 fn is_no_sections(args: &Create, config: &Config) -> bool {
     args.no_section || config.no_sections.unwrap_or_default()
 }
 
+/// Uses the section named with --section, otherwise prompts for one unless sections are turned off
+async fn fetch_section(
+    args: &Create,
+    config: &Config,
+    project: &Project,
+) -> Result<Option<Section>, Error> {
+    if let Some(name) = &args.section {
+        return sections::find_section(config, project, name)
+            .await
+            .map(Some);
+    }
+    if is_no_sections(args, config) {
+        Ok(None)
+    } else {
+        sections::select_section(config, project).await
+    }
+}
+
 pub async fn create(config: Config, args: &Create) -> Result<String, Error> {
     if no_flags_used(args) {
         let options = tasks::create_task_attributes();
         let selections = input::multi_select(input::ATTRIBUTES, options, config.mock_select)?;
 
-        let content = super::fetch_string(None, &config, input::CONTENT)?;
-
-        let description = if selections.contains(&TaskAttribute::Description) {
-            super::fetch_string(None, &config, input::DESCRIPTION)?
+        let (content, description) = if config.use_editor() {
+            content_from_editor()?
         } else {
-            String::new()
+            let content = super::fetch_string(None, &config, input::CONTENT)?;
+            let description = if selections.contains(&TaskAttribute::Description) {
+                super::fetch_string(None, &config, input::DESCRIPTION)?
+            } else {
+                String::new()
+            };
+            (content, description)
         };
 
         let priority = if selections.contains(&TaskAttribute::Priority) {
@@ -170,7 +422,12 @@ pub async fn create(config: Config, args: &Create) -> Result<String, Error> {
 
         let labels = if selections.contains(&TaskAttribute::Labels) {
             let all_labels = labels::get_labels(&config, false).await?;
-            input::multi_select(input::LABELS, all_labels, config.mock_select)?
+            input::fuzzy_multi_select(
+                input::LABELS,
+                all_labels,
+                config.fuzzy_select(),
+                config.mock_select,
+            )?
         } else {
             Vec::new()
         }
@@ -178,16 +435,10 @@ pub async fn create(config: Config, args: &Create) -> Result<String, Error> {
         .map(|l| l.name.clone())
         .collect::<Vec<String>>();
 
-        let project = match super::fetch_project(args.project.as_deref(), &config).await? {
-            Flag::Project(project) => project,
-            Flag::Filter(_) => unreachable!(),
-        };
+        let project = fetch_create_project(args.project.as_deref(), args.inbox, &config).await?;
 
-        let section = if is_no_sections(args, &config) {
-            None
-        } else {
-            sections::select_section(&config, &project).await?
-        };
+        let section = fetch_section(args, &config, &project).await?;
+        let assignee = users::select_assignee(&config, &project, None).await?;
 
         todoist::create_task(
             &config,
@@ -197,88 +448,328 @@ pub async fn create(config: Config, args: &Create) -> Result<String, Error> {
             priority,
             &description,
             due.as_deref(),
+            args.due_lang.as_deref(),
             &labels,
+            assignee.as_ref(),
         )
         .await?;
     } else {
         let Create {
             project,
+            inbox,
             due,
             description,
+            description_stdin,
             content,
+            content_from_file,
+            editor,
             priority,
-            label: labels,
+            label,
+            labels,
+            assignee,
+            remind,
             no_section: _no_section,
+            section: _section,
+            due_lang,
+            due_tz,
+            repeat,
         } = args;
-        let project = match super::fetch_project(project.as_deref(), &config).await? {
-            Flag::Project(project) => project,
-            Flag::Filter(_) => unreachable!(),
+        if let Some(due) = due {
+            time::validate_due(due)?;
+        }
+        let due = match (due, due_tz, repeat) {
+            (Some(due), Some(zone), _) => Some(time::due_datetime_in_zone(due, zone)?),
+            (None, _, Some(pattern)) => Some(repeat_pattern(pattern)?),
+            (due, _, _) => due.clone(),
         };
-
-        let section = if is_no_sections(args, &config) {
-            None
+        for when in remind {
+            time::validate_due(when)?;
+        }
+        let labels = merge_labels(label, labels.as_deref())?;
+        let description = if *description_stdin {
+            description_from_stdin().await?
         } else {
-            sections::select_section(&config, &project).await?
+            description.clone()
+        };
+        let use_editor = *editor || (content.is_none() && config.use_editor());
+        let written = match content_from_file {
+            Some(path) => Some(read_content_file(path).await?),
+            None if use_editor => Some(content_from_editor()?),
+            None => None,
+        };
+        let project = fetch_create_project(project.as_deref(), *inbox, &config).await?;
+
+        let section = fetch_section(args, &config, &project).await?;
+        let (content, description) = match written {
+            Some(written) => written,
+            None => (
+                super::fetch_string(content.as_deref(), &config, input::CONTENT)?,
+                description,
+            ),
         };
-        let content = super::fetch_string(content.as_deref(), &config, input::CONTENT)?;
         let priority = super::fetch_priority(*priority, &config)?;
+        let assignee = users::select_assignee(&config, &project, assignee.as_deref()).await?;
 
-        todoist::create_task(
+        let task = todoist::create_task(
             &config,
             &content,
             &project,
             section.as_ref(),
             priority,
-            description,
+            &description,
             due.as_deref(),
-            labels,
+            due_lang.as_deref(),
+            &labels,
+            assignee.as_ref(),
         )
         .await?;
+        for when in remind {
+            if let Err(e) = todoist::create_reminder(&config, &task, when, false).await {
+                let message = format!(
+                    "Created the task but could not add the reminder for '{when}': {}",
+                    e.message
+                );
+                return Err(Error::new("task_create", &message).with_kind(e.kind));
+            }
+        }
     }
     Ok(format::green_string("✓"))
 }
 
+/// Checks a --repeat pattern, which Todoist reads as a recurring due date
+fn repeat_pattern(pattern: &str) -> Result<String, Error> {
+    let pattern = pattern.trim();
+    if pattern.is_empty() {
+        return Err(Error::new(
+            "task_create",
+            "--repeat needs a pattern such as \"every monday\"",
+        ));
+    }
+    Ok(pattern.to_string())
+}
+
+/// The inbox with --inbox, otherwise the project given with --project or picked from config
+async fn fetch_create_project(
+    project: Option<&str>,
+    inbox: bool,
+    config: &Config,
+) -> Result<Project, Error> {
+    if inbox {
+        return todoist::inbox_project(config).await;
+    }
+    match super::fetch_project(project, config).await? {
+        Flag::Project(project) => Ok(project),
+        Flag::Filter(_) => unreachable!(),
+    }
+}
+
 fn no_flags_used(args: &Create) -> bool {
     let Create {
         project,
+        inbox: _inbox,
+        repeat,
         due,
         description,
+        description_stdin,
         content,
+        content_from_file,
+        editor,
         no_section: _no_section,
+        due_lang: _due_lang,
+        due_tz: _due_tz,
+        section,
         priority,
         label,
+        labels,
+        assignee,
+        remind,
     } = args;
 
     project.is_none()
         && due.is_none()
+        && repeat.is_none()
         && description.is_empty()
+        && !description_stdin
         && content.is_none()
+        && content_from_file.is_none()
+        && !editor
         && priority.is_none()
         && label.is_empty()
+        && labels.is_none()
+        && assignee.is_none()
+        && remind.is_empty()
+        && section.is_none()
+}
+
+/// Combines repeated --label values with the comma separated --labels, keeping the first of any duplicates
+fn merge_labels(label: &[String], labels: Option<&str>) -> Result<Vec<String>, Error> {
+    let mut merged: Vec<String> = Vec::new();
+    for name in label
+        .iter()
+        .map(String::as_str)
+        .chain(labels.into_iter().flat_map(|l| l.split(',')))
+    {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(Error::new(
+                "task_create",
+                "Label names can't be empty, check --label and --labels for stray commas",
+            ));
+        }
+        if !merged.iter().any(|existing| existing == name) {
+            merged.push(name.to_string());
+        }
+    }
+    Ok(merged)
+}
+
+/// Reads task content from the first line of a file and the description from the remaining lines
+async fn read_content_file(path: &Path) -> Result<(String, String), Error> {
+    let bytes = tokio::fs::read(path).await.map_err(|e| {
+        Error::new(
+            "content_from_file",
+            &format!("Could not read {}: {e}", path.display()),
+        )
+    })?;
+    let text = String::from_utf8(bytes).map_err(|_| {
+        Error::new(
+            "content_from_file",
+            &format!("{} is not valid UTF-8", path.display()),
+        )
+    })?;
+
+    tasks::split_content(&text).ok_or_else(|| {
+        Error::new(
+            "content_from_file",
+            &format!(
+                "The first line of {} must contain the task content",
+                path.display()
+            ),
+        )
+    })
+}
+
+/// The whole of stdin as the description, for `--description-stdin`
+async fn description_from_stdin() -> Result<String, Error> {
+    if std::io::stdin().is_terminal() {
+        return Err(Error::new(
+            "description_stdin",
+            "--description-stdin reads from a pipe, i.e. command | tod task create -c \"Review output\" --description-stdin",
+        ));
+    }
+    description_from_bytes(input::read_stdin().await?)
+}
+
+/// Rejects binary input, which would otherwise end up in Todoist as garbled text
+fn description_from_bytes(bytes: Vec<u8>) -> Result<String, Error> {
+    let binary = || {
+        Error::new(
+            "description_stdin",
+            "stdin is not UTF-8 text, --description-stdin can't use binary input",
+        )
+    };
+    let text = String::from_utf8(bytes).map_err(|_| binary())?;
+    if text.contains('\0') {
+        return Err(binary());
+    }
+    Ok(text.trim_end().to_string())
+}
+
+/// Content from the first line written in $EDITOR and description from the rest
+fn content_from_editor() -> Result<(String, String), Error> {
+    let text = shell::edit_text("")?;
+    tasks::split_content(&text).ok_or_else(|| {
+        Error::new(
+            "task_create",
+            "The editor was closed without any task content, the task was not created",
+        )
+    })
 }
 
 pub async fn edit(config: Config, args: &Edit) -> Result<String, Error> {
-    let Edit { project, filter } = args;
+    let Edit {
+        index,
+        project,
+        filter,
+        editor,
+    } = args;
+    if let Some(index) = index {
+        let task =
+            fetch_indexed_task(&config, *index, project.as_deref(), filter.as_deref()).await?;
+        return tasks::edit(&config, &task, *editor).await;
+    }
     match super::fetch_project_or_filter(project.as_deref(), filter.as_deref(), &config).await? {
-        Flag::Project(project) => projects::edit_task(&config, &project).await,
-        Flag::Filter(filter) => filters::edit_task(&config, filter).await,
+        Flag::Project(project) => projects::edit_task(&config, &project, *editor).await,
+        Flag::Filter(filter) => filters::edit_task(&config, filter, *editor).await,
     }
 }
 pub async fn next(config: Config, args: &Next) -> Result<String, Error> {
-    let Next { project, filter } = args;
+    let Next {
+        project,
+        filter,
+        skip,
+        reset_skips,
+        sort,
+        seed,
+        open,
+        links,
+    } = args;
+    let mut config = if *reset_skips {
+        config.clear_skipped_tasks()
+    } else if *skip {
+        match config.next_task() {
+            Some(task) => config.skip_task(&task.id).clear_next_task(),
+            None => {
+                return Err(Error::new(
+                    "task_next",
+                    "There is no next task to skip, run tod task next first",
+                ));
+            }
+        }
+    } else {
+        config
+    };
+    if *reset_skips || *skip {
+        config.save().await?;
+    }
+    let sort = &SortOptions {
+        seed: *seed,
+        ..SortOptions::from(*sort)
+    };
+
     match super::fetch_project_or_filter(project.as_deref(), filter.as_deref(), &config).await? {
-        Flag::Project(project) => projects::next_task(config, &project).await,
-        Flag::Filter(filter) => filters::next_task(&config, &filter).await,
+        Flag::Project(project) => projects::next_task(config, &project, sort, *open, *links).await,
+        Flag::Filter(filter) => filters::next_task(&config, &filter, sort, *open, *links).await,
     }
 }
 
-pub async fn complete(config: Config, _args: &Complete) -> Result<String, Error> {
-    match config.next_task() {
-        Some(task) => {
-            todoist::complete_task(&config, &task.id, true).await?;
+pub async fn complete(config: Config, args: &Complete) -> Result<String, Error> {
+    let Complete {
+        index,
+        project,
+        filter,
+        force,
+        comment,
+    } = args;
+    let comment = comment.as_deref();
+    if let Some(index) = index {
+        let task =
+            fetch_indexed_task(&config, *index, project.as_deref(), filter.as_deref()).await?;
+        return complete_task(&config, &task, *force, comment).await;
+    }
+    if project.is_some() || filter.is_some() {
+        return complete_selected(
+            &config,
+            project.as_deref(),
+            filter.as_deref(),
+            *force,
+            comment,
+        )
+        .await;
+    }
 
-            Ok(format::green_string("Task completed successfully"))
-        }
+    match config.next_task() {
+        Some(task) => complete_task(&config, &task, *force, comment).await,
         None => Err(Error::new(
             "task_complete",
             "There is nothing to complete. A task must first be marked as 'next'.",
@@ -286,12 +777,111 @@ pub async fn complete(config: Config, _args: &Complete) -> Result<String, Error>
     }
 }
 
+/// Looks up a task by its number in the last list view of the project or filter, or of whatever was listed last
+async fn fetch_indexed_task(
+    config: &Config,
+    index: u32,
+    project: Option<&str>,
+    filter: Option<&str>,
+) -> Result<Task, Error> {
+    let flag = if project.is_some() || filter.is_some() {
+        Some(super::fetch_project_or_filter(project, filter, config).await?)
+    } else {
+        None
+    };
+    id::resolve(config, flag.as_ref(), index).await
+}
+
+/// Prompt for a task in a project or filter and complete it, bypassing the next task
+async fn complete_selected(
+    config: &Config,
+    project: Option<&str>,
+    filter: Option<&str>,
+    force: bool,
+    comment: Option<&str>,
+) -> Result<String, Error> {
+    let tasks = match super::fetch_project_or_filter(project, filter, config).await? {
+        Flag::Project(project) => todoist::all_tasks_by_project(config, &project, None).await?,
+        Flag::Filter(filter) => todoist::all_tasks_by_filters(config, &filter)
+            .await?
+            .into_iter()
+            .flat_map(|(_, tasks)| tasks)
+            .collect(),
+    };
+    if tasks.is_empty() {
+        return Ok(format::green_string("No tasks on list"));
+    }
+
+    let task = input::fuzzy_select(
+        input::TASK,
+        tasks,
+        config.fuzzy_select(),
+        config.mock_select,
+    )?;
+    complete_task(config, &task, force, comment).await
+}
+
+/// Complete the task, first confirming when it is recurring as Todoist will reschedule it.
+/// A comment is posted before completing, so a failed comment leaves the task open.
+async fn complete_task(
+    config: &Config,
+    task: &Task,
+    force: bool,
+    comment: Option<&str>,
+) -> Result<String, Error> {
+    if !force && let Some(recurrence) = task.recurrence() {
+        let due_date = task
+            .due
+            .as_ref()
+            .map(|due| due.date.chars().take(10).collect::<String>())
+            .unwrap_or_default();
+        println!(
+            "This is recurring ({recurrence}) and due {due_date}; completing it moves it on to its next occurrence instead of closing it"
+        );
+        let options = vec![input::CANCEL, input::COMPLETE];
+        if input::select(input::OPTION, options, config.mock_select)? == input::CANCEL {
+            return Ok("Cancelled".into());
+        }
+    }
+
+    let Some(comment) = comment else {
+        todoist::complete_task(config, task, true).await?;
+        return Ok(format::green_string("Task completed successfully"));
+    };
+    todoist::create_comment(config, &task.id, comment, None, true).await?;
+    if let Err(e) = todoist::complete_task(config, task, true).await {
+        let message = format!(
+            "Added the comment but could not complete the task: {}",
+            e.message
+        );
+        return Err(Error::new("task_complete", &message).with_kind(e.kind));
+    }
+    Ok(format::green_string(
+        "Comment added and task completed successfully",
+    ))
+}
+
 pub async fn comment(config: Config, args: &Comment) -> Result<String, Error> {
-    let Comment { content } = args;
+    let Comment {
+        content,
+        file,
+        list,
+        limit,
+        page,
+        reverse,
+    } = args;
     match config.next_task() {
+        Some(task) if *list => list_comments(&config, &task, *limit, *page, *reverse).await,
         Some(task) => {
+            if let Some(path) = file {
+                todoist::validate_upload(path).await?;
+            }
             let content = super::fetch_string(content.as_deref(), &config, input::CONTENT)?;
-            todoist::create_comment(&config, &task.id, &content, true).await?;
+            let attachment = match file {
+                Some(path) => Some(todoist::upload_file(&config, path, true).await?),
+                None => None,
+            };
+            todoist::create_comment(&config, &task.id, &content, attachment.as_ref(), true).await?;
             Ok(format::green_string("Comment created successfully"))
         }
         None => Err(Error::new(
@@ -301,22 +891,198 @@ pub async fn comment(config: Config, args: &Comment) -> Result<String, Error> {
     }
 }
 
+pub async fn links(config: Config, args: &Links) -> Result<String, Error> {
+    let Links { open_first } = args;
+    let Some(task) = config.next_task() else {
+        return Err(Error::new(
+            "task_links",
+            "There are no links to show. A task must first be marked as 'next'.",
+        ));
+    };
+    let links = tasks::markdown_links(&task);
+    let Some((_, first_url)) = links.first() else {
+        return Ok(format::green_string("No links in this task"));
+    };
+    if *open_first && !cfg!(test) {
+        shell::open_url(first_url)?;
+    }
+
+    let lines = links
+        .iter()
+        .map(|(text, url)| format!(" - {text}: {}", format::cyan_string(url)))
+        .collect::<Vec<String>>()
+        .join("\n");
+    Ok(format!("Links in {}\n{lines}", task.content))
+}
+
+pub async fn snooze(config: Config, args: &Snooze) -> Result<String, Error> {
+    let Snooze { when, force } = args;
+    let Some(task) = config.next_task() else {
+        return Err(Error::new(
+            "task_snooze",
+            "There is nothing to snooze. A task must first be marked as 'next'.",
+        ));
+    };
+    if !force && let Some(recurrence) = task.recurrence() {
+        return Err(Error::new(
+            "task_snooze",
+            &format!(
+                "'{}' repeats {recurrence}, snoozing it would restart the recurrence from the new date. Use --force to snooze it anyway.",
+                task.content
+            ),
+        ));
+    }
+    let when = when.join(" ");
+    let due_string = match time::snooze_offset(&when) {
+        Some(offset) => {
+            let due = task.due.as_ref().map(|due| due.date.as_str());
+            time::snooze_due(due, offset, &config)?
+        }
+        None => {
+            time::validate_due(&when)?;
+            when.trim().to_string()
+        }
+    };
+
+    todoist::update_task_due_natural_language(&config, &task, due_string.clone(), None, true)
+        .await?;
+    Ok(format::green_string(&format!(
+        "Snoozed '{}' to {due_string}",
+        task.content
+    )))
+}
+
+pub async fn move_task(config: Config, args: &Move) -> Result<String, Error> {
+    let Move {
+        section,
+        no_section,
+    } = args;
+    let Some(task) = config.next_task() else {
+        return Err(Error::new(
+            "task_move",
+            "There is nothing to move. A task must first be marked as 'next'.",
+        ));
+    };
+    let Some(project) = config
+        .projects()
+        .await?
+        .into_iter()
+        .find(|project| project.id == task.project_id)
+    else {
+        let message = format!(
+            "The project of '{}' is not in config, add it with \"tod project import\"",
+            task.content
+        );
+        return Err(Error::new("task_move", &message).with_kind(ErrorKind::NotFound));
+    };
+
+    let target = match section {
+        _ if *no_section => None,
+        Some(name) => Some(sections::find_section(&config, &project, name).await?),
+        None => {
+            let sections = todoist::all_sections_by_project(&config, &project, None).await?;
+            if sections.is_empty() {
+                let message = format!("'{}' has no sections to move the task into", project.name);
+                return Err(Error::new("task_move", &message));
+            }
+            sections::pick_section(&config, &sections)?
+        }
+    };
+
+    let message = match target {
+        Some(section) if task.section_id.as_ref() == Some(&section.id) => {
+            format!("'{}' is already in '{}'", task.content, section.name)
+        }
+        Some(section) => {
+            let moved = todoist::move_task_to_section(&config, &task, &section, true).await?;
+            config.set_next_task(moved).save().await?;
+            format!("Moved '{}' to '{}'", task.content, section.name)
+        }
+        None if task.section_id.is_none() => format!("'{}' is not in a section", task.content),
+        None => {
+            let moved = todoist::move_task_out_of_section(&config, &task, true).await?;
+            config.set_next_task(moved).save().await?;
+            format!("Moved '{}' out of its section", task.content)
+        }
+    };
+    Ok(format::green_string(&message))
+}
+
+/// Prints one page of comments on a task followed by a "Page 2 of 5" footer
+async fn list_comments(
+    config: &Config,
+    task: &Task,
+    limit: u32,
+    page: u32,
+    oldest_first: bool,
+) -> Result<String, Error> {
+    let comments = todoist::all_comments(config, &task.id, None).await?;
+    if comments.is_empty() {
+        return Ok(format::green_string("No comments on this task"));
+    }
+
+    let (comments, total_pages) = crate::comments::Comment::page(
+        comments,
+        usize::try_from(limit)?,
+        usize::try_from(page)?,
+        oldest_first,
+    )?;
+    let comments = comments
+        .iter()
+        .map(|comment| comment.fmt(config))
+        .collect::<Result<Vec<String>, Error>>()?
+        .join("\n\n");
+
+    Ok(format!("{comments}\n\nPage {page} of {total_pages}"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test;
+    use crate::test::responses::ResponseFromFile;
 
     fn create_args() -> Create {
         Create {
             project: None,
             due: None,
             description: String::new(),
+            description_stdin: false,
             content: None,
+            content_from_file: None,
+            editor: false,
             no_section: false,
+            section: None,
+            due_lang: None,
+            due_tz: None,
+            inbox: false,
+            repeat: None,
             priority: None,
             label: Vec::new(),
+            labels: None,
+            assignee: None,
+            remind: Vec::new(),
         }
     }
 
+    #[test]
+    fn merge_labels_combines_and_dedupes() {
+        let label = vec!["home".to_string(), "urgent".to_string()];
+        assert_eq!(
+            merge_labels(&label, Some("errand, home,urgent")),
+            Ok(vec![
+                "home".to_string(),
+                "urgent".to_string(),
+                "errand".to_string()
+            ])
+        );
+        assert_eq!(merge_labels(&label, None), Ok(label.clone()));
+
+        let error = merge_labels(&label, Some("home,,errand")).expect_err("empty label");
+        assert_eq!(error.source, "task_create");
+        assert!(merge_labels(&[" ".to_string()], None).is_err());
+    }
+
     #[test]
     fn no_flags_used_returns_true_for_default_create_args() {
         let args = create_args();
@@ -330,6 +1096,280 @@ mod tests {
         assert!(!no_flags_used(&args));
     }
 
+    #[test]
+    fn description_from_bytes_rejects_binary_input() {
+        assert_eq!(
+            description_from_bytes(b"Line one\nLine two\n\n".to_vec()),
+            Ok("Line one\nLine two".to_string())
+        );
+        for bytes in [vec![0xff, 0xfe, 0x00], b"text\0more".to_vec()] {
+            let error = description_from_bytes(bytes).expect_err("binary input should be rejected");
+            assert_eq!(error.source, "description_stdin");
+        }
+
+        assert!(Create::try_parse_from(["tod", "--description-stdin"]).is_err());
+        assert!(
+            Create::try_parse_from(["tod", "-c", "Review", "-d", "x", "--description-stdin"])
+                .is_err()
+        );
+        let args = Create::try_parse_from(["tod", "-c", "Review output", "--description-stdin"])
+            .expect("--description-stdin with --content should be valid");
+        assert!(!no_flags_used(&args));
+    }
+
+    #[test]
+    fn section_flag_conflicts_with_no_section() {
+        assert!(Create::try_parse_from(["tod", "--section", "Bread", "--no-section"]).is_err());
+
+        let args = Create::try_parse_from(["tod", "--section", "Bread"])
+            .expect("--section should be valid");
+        assert_eq!(args.section.as_deref(), Some("Bread"));
+        assert!(!no_flags_used(&args));
+    }
+
+    #[tokio::test]
+    async fn create_rejects_invalid_due_before_calling_api() {
+        let config = test::fixtures::config().await;
+        let mut args = create_args();
+        args.content = Some("Write more rust".to_string());
+        args.due = Some("2024-02-30".to_string());
+
+        let error = create(config, &args)
+            .await
+            .expect_err("invalid due date should fail");
+        assert_eq!(error.source, "validate_due");
+    }
+
+    #[tokio::test]
+    async fn create_sends_due_lang_over_config_language() {
+        assert!(Create::try_parse_from(["tod", "--due-lang", "xx"]).is_err());
+
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/api/v1/tasks/")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "due_string": "mañana",
+                "due_lang": "es",
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::TodayTask.read().await)
+            .create_async()
+            .await;
+
+        let mut config = test::fixtures::config().await.with_mock_url(server.url());
+        config.language = Some("de".to_string());
+        let args = Create::try_parse_from([
+            "tod",
+            "--content",
+            "Buy milk",
+            "--project",
+            "myproject",
+            "--due",
+            "mañana",
+            "--due-lang",
+            "es",
+            "--priority",
+            "1",
+            "--no-section",
+        ])
+        .expect("create arguments should parse");
+
+        let result = create(config, &args).await;
+        assert_eq!(result, Ok("✓".to_string()));
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn create_with_repeat_sends_pattern_as_due_string() {
+        assert!(
+            Create::try_parse_from(["tod", "--repeat", "every day", "--due", "today"]).is_err()
+        );
+
+        let config = test::fixtures::config().await;
+        let mut args = create_args();
+        args.content = Some("Water plants".to_string());
+        args.repeat = Some("  ".to_string());
+        let error = create(config, &args)
+            .await
+            .expect_err("empty --repeat should fail");
+        assert_eq!(error.source, "task_create");
+
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/api/v1/tasks/")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "due_string": "every monday",
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::TodayTask.read().await)
+            .create_async()
+            .await;
+
+        let config = test::fixtures::config().await.with_mock_url(server.url());
+        let args = Create::try_parse_from([
+            "tod",
+            "--content",
+            "Water plants",
+            "--project",
+            "myproject",
+            "--repeat",
+            "every monday",
+            "--priority",
+            "1",
+            "--no-section",
+        ])
+        .expect("create arguments should parse");
+
+        let result = create(config, &args).await;
+        assert_eq!(result, Ok("✓".to_string()));
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn create_with_inbox_skips_project_picker() {
+        assert!(Create::try_parse_from(["tod", "--inbox", "--project", "myproject"]).is_err());
+
+        let mut server = mockito::Server::new_async().await;
+        let projects_mock = server
+            .mock("GET", "/api/v1/projects?limit=200")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::InboxProjects.read().await)
+            .create_async()
+            .await;
+        let task_mock = server
+            .mock("POST", "/api/v1/tasks/")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "project_id": "456",
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::TodayTask.read().await)
+            .create_async()
+            .await;
+
+        let config = test::fixtures::config().await.with_mock_url(server.url());
+        let args = Create::try_parse_from([
+            "tod",
+            "--content",
+            "Buy milk",
+            "--inbox",
+            "--priority",
+            "1",
+            "--no-section",
+        ])
+        .expect("create arguments should parse");
+
+        let result = create(config, &args).await;
+        assert_eq!(result, Ok("✓".to_string()));
+        projects_mock.assert();
+        task_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn create_sends_due_in_due_tz_as_utc() {
+        assert!(Create::try_parse_from(["tod", "--due-tz", "Europe/Berlin"]).is_err());
+
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/api/v1/tasks/")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "due_datetime": "2025-05-12T07:00:00Z",
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::TodayTask.read().await)
+            .create_async()
+            .await;
+
+        let config = test::fixtures::config().await.with_mock_url(server.url());
+        let args = Create::try_parse_from([
+            "tod",
+            "--content",
+            "Call Berlin",
+            "--project",
+            "myproject",
+            "--due",
+            "2025-05-12 09:00",
+            "--due-tz",
+            "Europe/Berlin",
+            "--priority",
+            "1",
+            "--no-section",
+        ])
+        .expect("create arguments should parse");
+
+        let result = create(config, &args).await;
+        assert_eq!(result, Ok("✓".to_string()));
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn create_adds_each_reminder() {
+        let mut server = mockito::Server::new_async().await;
+        let task_mock = server
+            .mock("POST", "/api/v1/tasks/")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::TodayTask.read().await)
+            .create_async()
+            .await;
+        let mut reminder_mocks = Vec::new();
+        for when in ["2026-01-18 17:00", "tomorrow 9am"] {
+            let mock = server
+                .mock("POST", "/api/v1/reminders")
+                .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                    "task_id": "6Xqhv4cwxgjwG9w8",
+                    "due": {"string": when},
+                })))
+                .with_status(200)
+                .with_header("content-type", "application/json")
+                .with_body(ResponseFromFile::Reminder.read().await)
+                .create_async()
+                .await;
+            reminder_mocks.push(mock);
+        }
+
+        let config = test::fixtures::config().await.with_mock_url(server.url());
+        let args = Create::try_parse_from([
+            "tod",
+            "--content",
+            "Buy milk",
+            "--project",
+            "myproject",
+            "--priority",
+            "1",
+            "--no-section",
+            "--remind",
+            "2026-01-18 17:00",
+            "--remind",
+            "tomorrow 9am",
+        ])
+        .expect("create arguments should parse");
+
+        let result = create(config, &args).await;
+        assert_eq!(result, Ok("✓".to_string()));
+        task_mock.assert();
+        for mock in reminder_mocks {
+            mock.assert();
+        }
+    }
+
+    #[tokio::test]
+    async fn create_rejects_invalid_reminder_before_calling_api() {
+        let config = test::fixtures::config().await;
+        let mut args = create_args();
+        args.content = Some("Write more rust".to_string());
+        args.remind = vec!["2024-02-30 10:00".to_string()];
+
+        let error = create(config, &args)
+            .await
+            .expect_err("invalid reminder should fail");
+        assert_eq!(error.source, "validate_due");
+    }
+
     #[test]
     fn is_no_sections_respects_argument_flag() {
         let mut args = create_args();
@@ -347,4 +1387,752 @@ mod tests {
 
         assert!(is_no_sections(&args, &config));
     }
+
+    #[tokio::test]
+    async fn quick_add_uses_default_project() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/api/v1/tasks/quick")
+            .match_body(mockito::Matcher::PartialJson(
+                serde_json::json!({"text": "Buy milk", "project_id": "123"}),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::TodayTask.read().await)
+            .create_async()
+            .await;
+
+        let mut config = test::fixtures::config().await.with_mock_url(server.url());
+        config.default_project = Some("123".to_string());
+        let args = QuickAdd {
+            content: Some(vec!["Buy milk".to_string()]),
+            words: Vec::new(),
+            project: None,
+            inbox: false,
+            due_tz: None,
+        };
+
+        let result = quick_add(&config, &args).await;
+        assert_eq!(result, Ok(format::green_string("✓")));
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn quick_add_joins_bare_words_into_content() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/api/v1/tasks/quick")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "text": "Buy milk tomorrow",
+                "reminder": "saturday 4pm"
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::TodayTask.read().await)
+            .create_async()
+            .await;
+
+        let config = test::fixtures::config().await.with_mock_url(server.url());
+        let args = QuickAdd::try_parse_from(["tod", "Buy", "milk", "tomorrow", "!saturday", "4pm"])
+            .expect("bare words should be valid");
+
+        let result = quick_add(&config, &args).await;
+        assert_eq!(result, Ok(format::green_string("✓")));
+        mock.assert();
+
+        assert!(QuickAdd::try_parse_from(["tod", "Buy", "-c", "milk"]).is_err());
+    }
+
+    #[tokio::test]
+    async fn quick_add_removes_task_when_due_tz_cannot_be_applied() {
+        let mut server = mockito::Server::new_async().await;
+        let create_mock = server
+            .mock("POST", "/api/v1/tasks/quick")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::Task.read().await)
+            .create_async()
+            .await;
+        let delete_mock = server
+            .mock("DELETE", "/api/v1/tasks/999999")
+            .with_status(204)
+            .create_async()
+            .await;
+
+        let config = test::fixtures::config().await.with_mock_url(server.url());
+        let args = QuickAdd::try_parse_from(["tod", "--due-tz", "Europe/Berlin", "Call", "Berlin"])
+            .expect("quick add arguments should parse");
+
+        let error = quick_add(&config, &args)
+            .await
+            .expect_err("a task without a due time can't be moved to another zone");
+        assert_eq!(
+            error.message,
+            "Did not add the task, --due-tz could not be applied: the content has no due time"
+        );
+        create_mock.assert();
+        delete_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn quick_add_with_missing_default_project_errors() {
+        let mut config = test::fixtures::config().await;
+        config.default_project = Some("999".to_string());
+        let args = QuickAdd {
+            content: Some(vec!["Buy milk".to_string()]),
+            words: Vec::new(),
+            project: None,
+            inbox: false,
+            due_tz: None,
+        };
+
+        let error = quick_add(&config, &args)
+            .await
+            .expect_err("default project missing from config should fail");
+        assert_eq!(error.source, "default_project");
+    }
+
+    #[tokio::test]
+    async fn complete_with_project_completes_selected_task() {
+        let mut server = mockito::Server::new_async().await;
+        let tasks_mock = server
+            .mock("GET", "/api/v1/tasks/?project_id=123&limit=200")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::TodayTasks.read().await)
+            .create_async()
+            .await;
+        let complete_mock = server
+            .mock("POST", "/api/v1/tasks/6Xqhv4cwxgjwG9w8/close")
+            .with_status(204)
+            .create_async()
+            .await;
+
+        let config = test::fixtures::config()
+            .await
+            .with_mock_url(server.url())
+            .mock_select(0);
+        let args = Complete {
+            index: None,
+            project: Some("myproject".to_string()),
+            filter: None,
+            force: false,
+            comment: None,
+        };
+
+        let result = complete(config, &args).await;
+        assert_eq!(
+            result,
+            Ok(format::green_string("Task completed successfully"))
+        );
+        tasks_mock.assert();
+        complete_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn complete_without_flags_requires_next_task() {
+        let config = test::fixtures::config().await;
+        let args = Complete {
+            index: None,
+            project: None,
+            filter: None,
+            force: false,
+            comment: None,
+        };
+
+        let error = complete(config, &args)
+            .await
+            .expect_err("completing without a next task should fail");
+        assert_eq!(error.source, "task_complete");
+    }
+
+    #[tokio::test]
+    async fn complete_with_index_uses_last_list_view() {
+        let mut server = mockito::Server::new_async().await;
+        let tasks_mock = server
+            .mock("GET", "/api/v1/tasks/?project_id=123&limit=200")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::TodayTasks.read().await)
+            .expect(2)
+            .create_async()
+            .await;
+        let complete_mock = server
+            .mock("POST", "/api/v1/tasks/6Xqhv4cwxgjwG9w8/close")
+            .with_status(204)
+            .create_async()
+            .await;
+
+        let config = test::fixtures::config().await.with_mock_url(server.url());
+        let listed = crate::lists::view(
+            &config,
+            Flag::Project(test::fixtures::project()),
+            &tasks::SortOptions::default(),
+            crate::lists::GroupBy::None,
+            None,
+            tasks::DueRange::default(),
+            None,
+            None,
+            None,
+            &crate::lists::ViewOutput::default(),
+        )
+        .await
+        .expect("list view should succeed");
+        assert!(listed.contains("\n1 - "));
+
+        let args = Complete {
+            index: Some(1),
+            project: None,
+            filter: None,
+            force: false,
+            comment: None,
+        };
+        let result = complete(config, &args).await;
+        assert_eq!(
+            result,
+            Ok(format::green_string("Task completed successfully"))
+        );
+        tasks_mock.assert();
+        complete_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn links_lists_markdown_links_of_next_task() {
+        let task = Task {
+            content: "Read [the docs](https://docs.rs)".into(),
+            description: "Also [tod](https://github.com/tod-org/tod) and https://plain.link".into(),
+            ..test::fixtures::today_task().await
+        };
+        let config = test::fixtures::config().await.set_next_task(task);
+        let args = Links { open_first: true };
+
+        let result = links(config, &args).await;
+        assert_eq!(
+            result,
+            Ok(String::from(
+                "Links in Read [the docs](https://docs.rs)\n - the docs: https://docs.rs\n - tod: https://github.com/tod-org/tod"
+            ))
+        );
+
+        let config = test::fixtures::config()
+            .await
+            .set_next_task(test::fixtures::today_task().await);
+        let result = links(config, &args).await;
+        assert_eq!(result, Ok(format::green_string("No links in this task")));
+
+        let error = links(test::fixtures::config().await, &args)
+            .await
+            .expect_err("there is no next task");
+        assert_eq!(error.source, "task_links");
+    }
+
+    async fn recurring_task() -> Task {
+        Task {
+            due: Some(tasks::DateInfo {
+                date: "2024-01-01".into(),
+                is_recurring: true,
+                lang: "en".into(),
+                string: "every monday".into(),
+                timezone: None,
+            }),
+            ..test::fixtures::today_task().await
+        }
+    }
+
+    #[tokio::test]
+    async fn complete_recurring_task_can_be_cancelled() {
+        let config = test::fixtures::config()
+            .await
+            .set_next_task(recurring_task().await)
+            .mock_select(0);
+        let args = Complete {
+            index: None,
+            project: None,
+            filter: None,
+            force: false,
+            comment: None,
+        };
+
+        let result = complete(config, &args).await;
+        assert_eq!(result, Ok("Cancelled".to_string()));
+    }
+
+    #[tokio::test]
+    async fn complete_recurring_task_with_force_skips_confirmation() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/api/v1/tasks/6Xqhv4cwxgjwG9w8/close")
+            .with_status(204)
+            .create_async()
+            .await;
+
+        let config = test::fixtures::config()
+            .await
+            .with_mock_url(server.url())
+            .set_next_task(recurring_task().await);
+        let args = Complete {
+            index: None,
+            project: None,
+            filter: None,
+            force: true,
+            comment: None,
+        };
+
+        let result = complete(config, &args).await;
+        assert_eq!(
+            result,
+            Ok(format::green_string("Task completed successfully"))
+        );
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn complete_with_comment_comments_before_completing() {
+        let mut server = mockito::Server::new_async().await;
+        let comment_mock = server
+            .mock("POST", "/api/v1/comments/")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "task_id": "6Xqhv4cwxgjwG9w8",
+                "content": "Sent the invoice"
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::Comment.read().await)
+            .create_async()
+            .await;
+        let complete_mock = server
+            .mock("POST", "/api/v1/tasks/6Xqhv4cwxgjwG9w8/close")
+            .with_status(204)
+            .create_async()
+            .await;
+
+        let config = test::fixtures::config()
+            .await
+            .with_mock_url(server.url())
+            .set_next_task(test::fixtures::today_task().await);
+        let args = Complete {
+            index: None,
+            project: None,
+            filter: None,
+            force: false,
+            comment: Some("Sent the invoice".to_string()),
+        };
+
+        let result = complete(config, &args).await;
+        assert_eq!(
+            result,
+            Ok(format::green_string(
+                "Comment added and task completed successfully"
+            ))
+        );
+        comment_mock.assert();
+        complete_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn complete_with_failed_comment_leaves_task_open() {
+        let mut server = mockito::Server::new_async().await;
+        let comment_mock = server
+            .mock("POST", "/api/v1/comments/")
+            .with_status(500)
+            .create_async()
+            .await;
+        let complete_mock = server
+            .mock("POST", "/api/v1/tasks/6Xqhv4cwxgjwG9w8/close")
+            .with_status(204)
+            .expect(0)
+            .create_async()
+            .await;
+
+        let config = test::fixtures::config()
+            .await
+            .with_mock_url(server.url())
+            .set_next_task(test::fixtures::today_task().await);
+        let args = Complete {
+            index: None,
+            project: None,
+            filter: None,
+            force: false,
+            comment: Some("Sent the invoice".to_string()),
+        };
+
+        assert!(complete(config, &args).await.is_err());
+        comment_mock.assert();
+        complete_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn snooze_moves_next_task_by_offset_or_to_date() {
+        let mut server = mockito::Server::new_async().await;
+        let offset_mock = server
+            .mock("POST", "/api/v1/tasks/6Xqhv4cwxgjwG9w8")
+            .match_body(mockito::Matcher::PartialJson(
+                serde_json::json!({"due_string": "2025-05-10 05:00"}),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::Task.read().await)
+            .create_async()
+            .await;
+        let date_mock = server
+            .mock("POST", "/api/v1/tasks/6Xqhv4cwxgjwG9w8")
+            .match_body(mockito::Matcher::PartialJson(
+                serde_json::json!({"due_string": "tomorrow"}),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::Task.read().await)
+            .create_async()
+            .await;
+
+        let mut task = test::fixtures::today_task().await;
+        task.due = None;
+        let config = test::fixtures::config()
+            .await
+            .with_mock_url(server.url())
+            .set_next_task(task);
+
+        let args = Snooze {
+            when: vec!["2h".into()],
+            force: false,
+        };
+        let result = snooze(config.clone(), &args).await;
+        assert_eq!(result, Ok("Snoozed 'TEST' to 2025-05-10 05:00".into()));
+
+        let args = Snooze {
+            when: vec!["tomorrow".into()],
+            force: false,
+        };
+        let result = snooze(config, &args).await;
+        assert_eq!(result, Ok("Snoozed 'TEST' to tomorrow".into()));
+        offset_mock.assert();
+        date_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn snooze_recurring_task_requires_force() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/api/v1/tasks/6Xqhv4cwxgjwG9w8")
+            .match_body(mockito::Matcher::PartialJson(
+                serde_json::json!({"due_string": "every monday starting tomorrow"}),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::Task.read().await)
+            .expect(1)
+            .create_async()
+            .await;
+        let config = test::fixtures::config()
+            .await
+            .with_mock_url(server.url())
+            .set_next_task(recurring_task().await);
+
+        let args = Snooze {
+            when: vec!["tomorrow".into()],
+            force: false,
+        };
+        let result = snooze(config.clone(), &args).await;
+        assert_eq!(
+            result.map_err(|e| e.message),
+            Err("'TEST' repeats every monday, snoozing it would restart the recurrence from the new date. Use --force to snooze it anyway.".to_string())
+        );
+
+        let args = Snooze {
+            when: vec!["tomorrow".into()],
+            force: true,
+        };
+        let result = snooze(config, &args).await;
+        assert_eq!(result, Ok("Snoozed 'TEST' to tomorrow".into()));
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn move_task_changes_section_within_project() {
+        let mut server = mockito::Server::new_async().await;
+        let sections_mock = server
+            .mock("GET", "/api/v1/sections?project_id=123&limit=200")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::Sections.read().await)
+            .expect(2)
+            .create_async()
+            .await;
+        let section_mock = server
+            .mock("POST", "/api/v1/tasks/6Xqhv4cwxgjwG9w8/move")
+            .match_body(mockito::Matcher::Json(
+                serde_json::json!({"section_id": "1234"}),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::TodayTask.read().await)
+            .expect(2)
+            .create_async()
+            .await;
+        let no_section_mock = server
+            .mock("POST", "/api/v1/tasks/6Xqhv4cwxgjwG9w8/move")
+            .match_body(mockito::Matcher::Json(
+                serde_json::json!({"project_id": "123"}),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::TodayTask.read().await)
+            .create_async()
+            .await;
+
+        let mut task = test::fixtures::today_task().await;
+        task.project_id = "123".to_string();
+        task.section_id = Some("999".to_string());
+        let config = test::fixtures::config()
+            .await
+            .with_mock_url(server.url())
+            .mock_select(1)
+            .set_next_task(task)
+            .create()
+            .await
+            .expect("expected value or result, got None or Err");
+
+        let args = Move::try_parse_from(["tod", "--section", "bread"])
+            .expect("move arguments should parse");
+        let result = move_task(config.clone(), &args).await;
+        assert_eq!(result, Ok(format::green_string("Moved 'TEST' to 'Bread'")));
+        // The next task is replaced with the one Todoist returns, so later moves see its new section
+        let saved = config.reload().await.expect("config should reload");
+        assert_eq!(saved.next_task(), Some(test::fixtures::today_task().await));
+
+        // Without --section the picker offers "No section" first, then Bread
+        let args = Move::try_parse_from(["tod"]).expect("move arguments should parse");
+        let result = move_task(config.clone(), &args).await;
+        assert_eq!(result, Ok(format::green_string("Moved 'TEST' to 'Bread'")));
+
+        let args =
+            Move::try_parse_from(["tod", "--no-section"]).expect("move arguments should parse");
+        let result = move_task(config, &args).await;
+        assert_eq!(
+            result,
+            Ok(format::green_string("Moved 'TEST' out of its section"))
+        );
+        sections_mock.assert();
+        section_mock.assert();
+        no_section_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn snooze_without_next_task_errors() {
+        let config = test::fixtures::config().await;
+        let args = Snooze {
+            when: vec!["2h".into()],
+            force: false,
+        };
+        let result = snooze(config, &args).await;
+        assert_eq!(result.map_err(|e| e.source), Err("task_snooze".to_string()));
+    }
+
+    #[tokio::test]
+    async fn comment_with_missing_file_errors_before_uploading() {
+        let config = test::fixtures::config()
+            .await
+            .set_next_task(test::fixtures::today_task().await);
+        let args = Comment {
+            content: Some("Receipt".to_string()),
+            file: Some(PathBuf::from("/does/not/exist.pdf")),
+            list: false,
+            limit: 10,
+            page: 1,
+            reverse: false,
+        };
+
+        let error = comment(config, &args)
+            .await
+            .expect_err("missing file should fail");
+        assert_eq!(error.source, "validate_upload");
+    }
+
+    #[tokio::test]
+    async fn comment_list_shows_page_footer() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock(
+                "GET",
+                "/api/v1/comments/?task_id=6Xqhv4cwxgjwG9w8&limit=200",
+            )
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::CommentsAllTypes.read().await)
+            .create_async()
+            .await;
+        let config = test::fixtures::config()
+            .await
+            .with_mock_url(server.url())
+            .set_next_task(test::fixtures::today_task().await);
+        let args = Comment::try_parse_from(["tod", "--list", "--limit", "3", "--page", "2"])
+            .expect("comment arguments should parse");
+
+        let result = comment(config, &args)
+            .await
+            .expect("listing comments should succeed");
+        assert!(result.ends_with("\n\nPage 2 of 3"));
+        assert_eq!(result.matches("Posted ").count(), 3);
+        mock.assert();
+
+        assert!(Comment::try_parse_from(["tod", "--page", "2"]).is_err());
+        assert!(Comment::try_parse_from(["tod", "--list", "--content", "hi"]).is_err());
+    }
+
+    #[tokio::test]
+    async fn create_with_empty_editor_is_aborted() {
+        let config = test::fixtures::config().await;
+        let mut args = create_args();
+        args.editor = true;
+
+        let error = create(config, &args)
+            .await
+            .expect_err("empty editor should not create a task");
+        assert_eq!(error.source, "task_create");
+        assert!(error.message.contains("the task was not created"));
+
+        assert!(Create::try_parse_from(["tod", "--editor", "--content", "hi"]).is_err());
+    }
+
+    #[tokio::test]
+    async fn read_content_file_splits_content_and_description() {
+        let dir = tempfile::tempdir().expect("temp dir should be created");
+        let path = dir.path().join("task.md");
+        tokio::fs::write(&path, "Write the report\r\n\n## Notes\n- include charts\n")
+            .await
+            .expect("file should be written");
+
+        let result = read_content_file(&path).await;
+        assert_eq!(
+            result,
+            Ok((
+                "Write the report".to_string(),
+                "## Notes\n- include charts".to_string()
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn read_content_file_rejects_empty_first_line_and_invalid_utf8() {
+        let dir = tempfile::tempdir().expect("temp dir should be created");
+        let empty = dir.path().join("empty.md");
+        tokio::fs::write(&empty, "\nDescription only")
+            .await
+            .expect("file should be written");
+        let error = read_content_file(&empty)
+            .await
+            .expect_err("empty first line should fail");
+        assert!(error.message.contains("must contain the task content"));
+
+        let binary = dir.path().join("binary.md");
+        tokio::fs::write(&binary, [0xff, 0xfe, 0x00])
+            .await
+            .expect("file should be written");
+        let error = read_content_file(&binary)
+            .await
+            .expect_err("invalid utf-8 should fail");
+        assert!(error.message.contains("is not valid UTF-8"));
+    }
+
+    #[test]
+    fn content_from_file_conflicts_with_content() {
+        let result = Create::try_parse_from(["tod", "--content-from-file", "task.md", "-c", "x"]);
+        assert!(result.is_err());
+
+        let args = Create::try_parse_from(["tod", "--content-from-file", "task.md"])
+            .expect("--content-from-file should be valid");
+        assert!(!no_flags_used(&args));
+    }
+
+    #[tokio::test]
+    async fn next_skip_without_next_task_errors() {
+        let config = test::fixtures::config().await;
+        let args = Next {
+            project: None,
+            filter: Some("today".to_string()),
+            skip: true,
+            reset_skips: false,
+            sort: SortOrder::Value,
+            seed: None,
+            open: false,
+            links: false,
+        };
+
+        let error = next(config, &args)
+            .await
+            .expect_err("skipping without a next task should fail");
+        assert_eq!(error.source, "task_next");
+    }
+
+    #[tokio::test]
+    async fn next_skip_excludes_current_next_task() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api/v1/tasks/filter?query=today&limit=200")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::TodayTasks.read().await)
+            .create_async()
+            .await;
+
+        let task = test::fixtures::today_task().await;
+        let config = test::fixtures::config()
+            .await
+            .with_mock_url(server.url())
+            .set_next_task(task.clone())
+            .create()
+            .await
+            .expect("creating config should succeed");
+        let path = config.path.clone();
+        let args = Next {
+            project: None,
+            filter: Some("today".to_string()),
+            skip: true,
+            reset_skips: false,
+            sort: SortOrder::Value,
+            seed: None,
+            open: false,
+            links: false,
+        };
+
+        let result = next(config, &args).await;
+        assert_eq!(result, Ok(format::green_string("No tasks on list")));
+        mock.assert();
+
+        let saved = Config::load(&path)
+            .await
+            .expect("loading config should succeed");
+        assert_eq!(saved.skipped_tasks(), vec![task.id]);
+        assert_eq!(saved.next_task(), None);
+
+        let args = Next {
+            project: None,
+            filter: Some("today".to_string()),
+            skip: false,
+            reset_skips: true,
+            sort: SortOrder::Value,
+            seed: None,
+            open: false,
+            links: true,
+        };
+        let comments_mock = server
+            .mock(
+                "GET",
+                "/api/v1/comments/?task_id=6Xqhv4cwxgjwG9w8&limit=200",
+            )
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::CommentsAllTypes.read().await)
+            .create_async()
+            .await;
+        let result = next(saved, &args)
+            .await
+            .expect("getting the next task should succeed");
+        assert!(result.contains("TEST"));
+        comments_mock.assert();
+        let saved = Config::load(&path)
+            .await
+            .expect("loading config should succeed");
+        assert!(saved.skipped_tasks().is_empty());
+    }
 }
@@ -1,4 +1,4 @@
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, builder::PossibleValuesParser};
 
 use crate::{config::Config, errors::Error, todoist};
 
@@ -7,15 +7,31 @@ pub enum TestCommands {
     #[clap(alias = "a")]
     /// (a) Hit all API endpoints
     All(All),
+
+    #[clap(alias = "e")]
+    /// (e) Hit a single API endpoint
+    Endpoint(Endpoint),
 }
 
 #[derive(Parser, Debug, Clone)]
 pub struct All {}
 
+#[derive(Parser, Debug, Clone)]
+pub struct Endpoint {
+    #[arg(value_parser = PossibleValuesParser::new(todoist::endpoint_names()))]
+    /// Name of the endpoint to test
+    name: String,
+}
+
 pub async fn all(config: &Config, _args: &All) -> Result<String, Error> {
     todoist::test_all_endpoints(config).await
 }
 
+pub async fn endpoint(config: &Config, args: &Endpoint) -> Result<String, Error> {
+    let Endpoint { name } = args;
+    todoist::test_endpoint(config, name).await
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -24,4 +40,12 @@ mod tests {
     fn all_command_is_constructible() {
         let _ = All {};
     }
+
+    #[test]
+    fn endpoint_name_must_be_a_known_endpoint() {
+        let args = Endpoint::try_parse_from(["tod", "user"]).expect("known endpoint should parse");
+        assert_eq!(args.name, "user");
+
+        assert!(Endpoint::try_parse_from(["tod", "nope"]).is_err());
+    }
 }
@@ -59,6 +59,13 @@ pub struct FileAttachment {
     pub resource_type: String,
 }
 
+impl FileAttachment {
+    pub fn from_json(json: &str) -> Result<FileAttachment, Error> {
+        let attachment: FileAttachment = serde_json::from_str(json)?;
+        Ok(attachment)
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
 pub struct UrlAttachment {
     description: String,
@@ -153,6 +160,35 @@ impl Comment {
         Ok(format!("Posted {formatted_date}{link}\n{content}"))
     }
 
+    /// Splits comments into pages of `limit`, newest first unless `oldest_first` is set.
+    /// Returns the comments on `page`, counting from 1, along with the number of pages
+    pub fn page(
+        mut comments: Vec<Comment>,
+        limit: usize,
+        page: usize,
+        oldest_first: bool,
+    ) -> Result<(Vec<Comment>, usize), Error> {
+        // Todoist returns comments oldest first
+        if !oldest_first {
+            comments.reverse();
+        }
+        let limit = limit.max(1);
+        let total_pages = comments.len().div_ceil(limit).max(1);
+        if page == 0 || page > total_pages {
+            return Err(Error::new(
+                "comment_page",
+                &format!("Page {page} does not exist, there are {total_pages} page(s)"),
+            ));
+        }
+
+        let comments = comments
+            .into_iter()
+            .skip((page - 1) * limit)
+            .take(limit)
+            .collect();
+        Ok((comments, total_pages))
+    }
+
     fn render_link(url: &str, label: &str, config: &Config) -> String {
         if hyperlinks_disabled(config) {
             format!("\nAttachment [{label}]({url})")
@@ -183,6 +219,36 @@ mod tests {
             .collect()
     }
 
+    #[tokio::test]
+    async fn test_page_comments() {
+        let comments = load_comments().await;
+        let newest = comments.last().cloned();
+        let oldest = comments.first().cloned();
+
+        let (page, total_pages) =
+            Comment::page(comments.clone(), 3, 1, false).expect("first page should exist");
+        assert_eq!(total_pages, 3);
+        assert_eq!(page.len(), 3);
+        assert_eq!(page.first().cloned(), newest);
+
+        let (page, _) =
+            Comment::page(comments.clone(), 3, 3, false).expect("last page should exist");
+        assert_eq!(page.len(), 1);
+        assert_eq!(page.first().cloned(), oldest);
+
+        let (page, _) =
+            Comment::page(comments.clone(), 3, 1, true).expect("first page should exist");
+        assert_eq!(page.first().cloned(), oldest);
+
+        let error = Comment::page(comments, 3, 4, false).expect_err("page 4 should not exist");
+        assert_eq!(error.source, "comment_page");
+
+        let (page, total_pages) =
+            Comment::page(Vec::new(), 3, 1, false).expect("empty list has one page");
+        assert!(page.is_empty());
+        assert_eq!(total_pages, 1);
+    }
+
     #[tokio::test]
     async fn test_filters_deleted_comment() {
         let comments = load_comments().await;
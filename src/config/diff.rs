@@ -0,0 +1,204 @@
+use std::path::Path;
+
+use serde_json::{Map, Value};
+
+use crate::{debug, format};
+
+/// Keys whose values are secrets and get redacted before printing
+const SECRET_KEYS: [&str; 2] = ["token", "refresh_token"];
+
+/// Reads the config file as raw JSON so it can be compared after a command rewrites it.
+/// Returns None when the file is missing or can't be parsed.
+pub async fn snapshot(path: &Path) -> Option<Value> {
+    let json = tokio::fs::read_to_string(path).await.ok()?;
+    serde_json::from_str(&json).ok()
+}
+
+/// Renders the changes between two snapshots under a heading, empty when nothing changed
+pub fn render(before: Option<&Value>, after: Option<&Value>) -> String {
+    let empty = Value::Object(Map::new());
+    let changes = changes(before.unwrap_or(&empty), after.unwrap_or(&empty));
+    if changes.is_empty() {
+        return String::new();
+    }
+
+    format!("Config changes:\n{}", changes.join("\n"))
+}
+
+/// Lists top level config values that were added, removed or changed.
+/// Lists of objects with an id or name, such as projects, are compared item by item
+pub fn changes(before: &Value, after: &Value) -> Vec<String> {
+    let empty = Map::new();
+    let before = before.as_object().unwrap_or(&empty);
+    let after = after.as_object().unwrap_or(&empty);
+
+    let mut keys = before.keys().chain(after.keys()).collect::<Vec<&String>>();
+    keys.sort();
+    keys.dedup();
+
+    keys.into_iter()
+        .flat_map(|key| match (before.get(key), after.get(key)) {
+            (None, Some(new)) => vec![added(key, &display_value(key, new))],
+            (Some(old), None) => vec![removed(key, &display_value(key, old))],
+            (Some(old), Some(new)) if old != new => {
+                match (items_by_identity(old), items_by_identity(new)) {
+                    (Some(old), Some(new)) => item_changes(key, &old, &new),
+                    _ => vec![changed(
+                        key,
+                        &display_value(key, old),
+                        &display_value(key, new),
+                    )],
+                }
+            }
+            _ => Vec::new(),
+        })
+        .collect()
+}
+
+/// The items of a list keyed by their id, or name when they have none.
+/// None unless the value is a list and every item has one of them
+fn items_by_identity(value: &Value) -> Option<Vec<(&Value, &Value)>> {
+    value
+        .as_array()?
+        .iter()
+        .map(|item| {
+            let identity = item.get("id").or_else(|| item.get("name"))?;
+            Some((identity, item))
+        })
+        .collect()
+}
+
+/// Items removed, changed and added between two lists, each shown by its name
+fn item_changes(key: &str, before: &[(&Value, &Value)], after: &[(&Value, &Value)]) -> Vec<String> {
+    let removed_items = before
+        .iter()
+        .filter(|(identity, _)| find(after, identity).is_none())
+        .map(|(_, item)| removed(key, &item_name(item)));
+    let changed_items = before.iter().filter_map(|(identity, old)| {
+        let new = find(after, identity).filter(|new| new != old)?;
+        let (old_name, new_name) = (item_name(old), item_name(new));
+        Some(if old_name == new_name {
+            format::yellow_string(&format!("~ {key}: {old_name}"))
+        } else {
+            changed(key, &old_name, &new_name)
+        })
+    });
+    let added_items = after
+        .iter()
+        .filter(|(identity, _)| find(before, identity).is_none())
+        .map(|(_, item)| added(key, &item_name(item)));
+
+    removed_items
+        .chain(changed_items)
+        .chain(added_items)
+        .collect()
+}
+
+fn find<'a>(items: &[(&Value, &'a Value)], identity: &Value) -> Option<&'a Value> {
+    items
+        .iter()
+        .find(|(other, _)| *other == identity)
+        .map(|(_, item)| *item)
+}
+
+fn item_name(item: &Value) -> String {
+    item.get("name")
+        .or_else(|| item.get("id"))
+        .map_or_else(|| item.to_string(), Value::to_string)
+}
+
+fn added(key: &str, value: &str) -> String {
+    format::green_string(&format!("+ {key}: {value}"))
+}
+
+fn removed(key: &str, value: &str) -> String {
+    format::red_string(&format!("- {key}: {value}"))
+}
+
+fn changed(key: &str, old: &str, new: &str) -> String {
+    format::yellow_string(&format!("~ {key}: {old} -> {new}"))
+}
+
+fn display_value(key: &str, value: &Value) -> String {
+    match value {
+        Value::String(text) if SECRET_KEYS.contains(&key) => {
+            format!("\"{}\"", debug::redact_token(text))
+        }
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    #[test]
+    fn test_changes() {
+        let before = json!({"a": 1, "b": 2, "c": 3});
+        let after = json!({"a": 1, "b": 5, "d": null});
+
+        assert_eq!(
+            changes(&before, &after),
+            vec!["~ b: 2 -> 5", "- c: 3", "+ d: null"]
+        );
+    }
+
+    #[test]
+    fn test_changes_compares_projects_by_id() {
+        let home = json!({"id": "1", "name": "Home", "color": "blue"});
+        let before =
+            json!({"projects": [home, {"id": "2", "name": "Work"}, {"id": "3", "name": "Gym"}]});
+        let after = json!({"projects": [
+            {"id": "1", "name": "Home", "color": "red"},
+            {"id": "3", "name": "Fitness"},
+            {"id": "4", "name": "Garden"}
+        ]});
+
+        assert_eq!(
+            changes(&before, &after),
+            vec![
+                "- projects: \"Work\"",
+                "~ projects: \"Home\"",
+                "~ projects: \"Gym\" -> \"Fitness\"",
+                "+ projects: \"Garden\""
+            ]
+        );
+        assert_eq!(
+            changes(&json!({"list": [1, 2]}), &json!({"list": [1]})),
+            vec!["~ list: [1,2] -> [1]"]
+        );
+    }
+
+    #[test]
+    fn test_changes_redacts_tokens() {
+        let before = json!({"token": null});
+        let after = json!({"token": "abcdefghijklmnop"});
+
+        assert_eq!(
+            changes(&before, &after),
+            vec![format!(
+                "~ token: null -> \"{}\"",
+                debug::redact_token("abcdefghijklmnop")
+            )]
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let config = json!({"timezone": "UTC"});
+        let updated = json!({"timezone": "Europe/Berlin"});
+
+        assert_eq!(render(Some(&config), Some(&config)), "");
+        assert_eq!(render(None, None), "");
+        assert_eq!(
+            render(Some(&config), Some(&updated)),
+            "Config changes:\n~ timezone: \"UTC\" -> \"Europe/Berlin\""
+        );
+        assert_eq!(
+            render(None, Some(&updated)),
+            "Config changes:\n+ timezone: \"Europe/Berlin\""
+        );
+    }
+}
@@ -30,8 +30,17 @@ impl Config {
     }
 
     /// Writes the config's current contents to disk as JSON.
+    /// Credentials refreshed during this command are kept, they were saved from another copy of the config
     pub async fn save(&mut self) -> std::result::Result<String, Error> {
+        let refreshed_token = self.internal.refreshed_token.lock().await.clone();
         let config = match Config::load(&self.path).await {
+            Ok(saved) if refreshed_token.is_some() && saved.token == refreshed_token => Config {
+                verbose: saved.verbose,
+                token: saved.token,
+                refresh_token: saved.refresh_token,
+                token_expires_at: saved.token_expires_at,
+                ..self.clone()
+            },
             Ok(Config { verbose, .. }) => Config {
                 verbose,
                 ..self.clone()
@@ -73,6 +82,22 @@ impl Config {
             ..config
         })
     }
+
+    /// File in the temp directory for state kept per config and `key`, such as list numbers.
+    /// Named with an FNV-1a hash, which unlike `DefaultHasher` is the same in every build of tod
+    pub fn temp_path(&self, prefix: &str, key: &str) -> PathBuf {
+        let bytes = self
+            .path
+            .as_os_str()
+            .as_encoded_bytes()
+            .iter()
+            .chain(b"\n")
+            .chain(key.as_bytes());
+        let hash = bytes.fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+        std::env::temp_dir().join(format!("{prefix}-{hash:x}.json"))
+    }
 }
 
 fn config_load_error(error: &serde_json::Error, path: &Path) -> Error {
@@ -89,7 +114,23 @@ fn config_load_error(error: &serde_json::Error, path: &Path) -> Error {
 
     Error::new(source, &message)
 }
+const CONFIG_FILE_NAME: &str = "tod.cfg";
+
 pub async fn generate_path() -> Result<PathBuf, Error> {
+    resolve_config_path_with_source(None)
+        .await
+        .map(|(path, _source)| path)
+}
+
+/// Resolves the config path along with where it came from, in order of precedence:
+/// the --config flag, `TOD_CONFIG`, `$XDG_CONFIG_HOME/tod.cfg`, `$HOME/.config/tod.cfg`,
+/// then the platform config directory, which is always used on Windows
+pub async fn resolve_config_path_with_source(
+    config_path: Option<PathBuf>,
+) -> Result<(PathBuf, &'static str), Error> {
+    if let Some(path) = config_path {
+        return Ok((maybe_expand_home_dir(path)?, "--config"));
+    }
     if cfg!(test) {
         let file = tempfile::Builder::new()
             .prefix("tod-")
@@ -97,11 +138,53 @@ pub async fn generate_path() -> Result<PathBuf, Error> {
             .tempfile()?;
         let path = file.path().to_path_buf();
         drop(file);
-        Ok(path)
-    } else {
-        let config_directory = dirs::config_dir()
-            .ok_or_else(|| Error::new("dirs", "Could not find config directory"))?;
-        Ok(config_directory.join("tod.cfg"))
+        return Ok((path, "a temporary file"));
+    }
+
+    default_config_path(
+        non_empty_env("TOD_CONFIG"),
+        non_empty_env("XDG_CONFIG_HOME"),
+        homedir::my_home()?,
+        dirs::config_dir(),
+        cfg!(windows),
+    )
+}
+
+fn non_empty_env(key: &str) -> Option<String> {
+    std::env::var(key)
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+}
+
+fn default_config_path(
+    tod_config: Option<String>,
+    xdg_config_home: Option<String>,
+    home: Option<PathBuf>,
+    platform_dir: Option<PathBuf>,
+    windows: bool,
+) -> Result<(PathBuf, &'static str), Error> {
+    if let Some(path) = tod_config {
+        return Ok((maybe_expand_home_dir(PathBuf::from(path))?, "TOD_CONFIG"));
+    }
+    if let Some(dir) = xdg_config_home {
+        return Ok((
+            PathBuf::from(dir).join(CONFIG_FILE_NAME),
+            "$XDG_CONFIG_HOME",
+        ));
+    }
+
+    let platform_path = platform_dir.map(|dir| dir.join(CONFIG_FILE_NAME));
+    match (home, platform_path) {
+        (_, Some(platform_path)) if windows => Ok((platform_path, "the platform config directory")),
+        // Macs used to default to ~/Library/Application Support, keep using an existing config there
+        (Some(home), Some(platform_path))
+            if !home.join(".config").join(CONFIG_FILE_NAME).exists() && platform_path.exists() =>
+        {
+            Ok((platform_path, "the platform config directory"))
+        }
+        (Some(home), _) => Ok((home.join(".config").join(CONFIG_FILE_NAME), "$HOME/.config")),
+        (None, Some(platform_path)) => Ok((platform_path, "the platform config directory")),
+        (None, None) => Err(Error::new("dirs", "Could not find config directory")),
     }
 }
 
@@ -211,10 +294,9 @@ where
 
 /// Resolves the config path, either using the provided path or generating a default one.
 pub async fn resolve_config_path(config_path: Option<PathBuf>) -> Result<PathBuf, Error> {
-    match config_path {
-        None => generate_path().await,
-        Some(path) => maybe_expand_home_dir(path),
-    }
+    resolve_config_path_with_source(config_path)
+        .await
+        .map(|(path, _source)| path)
 }
 
 /// Fetches the config from disk; errors out if it doesn't exist
@@ -318,6 +400,47 @@ mod tests {
         assert_eq!(loaded.token, Some("loaded".into()));
     }
 
+    #[test]
+    fn default_config_path_follows_precedence() {
+        let (dir, _) = temp_config_path("unused.cfg");
+        let home = dir.path().join("home");
+        let platform = dir.path().join("platform");
+        let resolve = |tod_config: Option<&str>, xdg: Option<&str>, windows: bool| {
+            default_config_path(
+                tod_config.map(String::from),
+                xdg.map(String::from),
+                Some(home.clone()),
+                Some(platform.clone()),
+                windows,
+            )
+            .expect("path should resolve")
+        };
+
+        assert_eq!(
+            resolve(Some("/tmp/custom.cfg"), Some("/xdg"), false),
+            (PathBuf::from("/tmp/custom.cfg"), "TOD_CONFIG")
+        );
+        assert_eq!(
+            resolve(None, Some("/xdg"), false),
+            (PathBuf::from("/xdg/tod.cfg"), "$XDG_CONFIG_HOME")
+        );
+        assert_eq!(
+            resolve(None, None, false),
+            (home.join(".config/tod.cfg"), "$HOME/.config")
+        );
+        assert_eq!(
+            resolve(None, None, true),
+            (platform.join("tod.cfg"), "the platform config directory")
+        );
+
+        std::fs::create_dir_all(&platform).expect("platform dir should be created");
+        std::fs::write(platform.join("tod.cfg"), "{}").expect("config should be written");
+        assert_eq!(
+            resolve(None, None, false),
+            (platform.join("tod.cfg"), "the platform config directory")
+        );
+    }
+
     #[tokio::test]
     async fn new_should_generate_config() {
         let config = Config::new(
@@ -344,6 +467,17 @@ mod tests {
 
         config.reload().await.expect("Failed to reload config");
     }
+
+    #[tokio::test]
+    async fn temp_path_is_named_with_a_stable_hash() {
+        let config = test::fixtures::config()
+            .await
+            .with_path(PathBuf::from("/tmp/tod.cfg"));
+        assert_eq!(
+            config.temp_path("tod-process", "filter:today"),
+            std::env::temp_dir().join("tod-process-a1227fafe8747efe.json")
+        );
+    }
     #[test]
     fn test_maybe_expand_home_dir() {
         // No tilde, so path should remain unchanged
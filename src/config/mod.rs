@@ -1,30 +1,47 @@
 use crate::cargo::Version;
+pub mod diff;
 mod file;
 mod projects;
 mod timezone;
+use crate::debug::LogLevel;
 use crate::errors::Error;
 use crate::format::maybe_format_url;
 use crate::input::page_size;
 use crate::legacy;
+use crate::oauth::AccessToken;
 use crate::projects::Project;
 use crate::tasks::Task;
-use crate::time::{SystemTimeProvider, TimeProviderEnum};
+use crate::tasks::priority::PrioritySymbols;
+use crate::time::{SystemTimeProvider, TimeProvider, TimeProviderEnum};
 use crate::{VERSION, cargo, format, input, time};
+use chrono::Weekday;
 use regex::Regex;
 use serde::de::Error as DeError;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::Arc;
 use terminal_size::{Height, Width, terminal_size};
+use tokio::sync::Mutex;
 use tokio::sync::mpsc::UnboundedSender;
 
 const MAX_COMMENT_LENGTH: u32 = 500;
 pub const DEFAULT_TIMEOUT_SECONDS: u64 = 30;
+const DEFAULT_MAX_CONCURRENCY: usize = 4;
+/// Bump when the config schema changes so that `tod config migrate` rewrites old files
+pub const CONFIG_VERSION: u32 = 1;
+/// Languages Todoist can parse natural language due dates in
+pub const LANGUAGES: [&str; 17] = [
+    "da", "de", "en", "es", "fi", "fr", "it", "ja", "ko", "nb", "nl", "pl", "pt", "ru", "sv", "tw",
+    "zh",
+];
 const TODOIST_INTEGRATIONS_URL: &str = "https://todoist.com/prefs/integrations";
 pub use file::config_open;
 pub use file::config_reset;
 pub use file::generate_path;
 pub use file::get_config;
 pub use file::resolve_config_path;
+pub use file::resolve_config_path_with_source;
 pub use legacy::LegacySortValue;
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -52,8 +69,14 @@ where
 #[derive(Clone, Serialize, Deserialize, Debug)]
 #[serde(default, deny_unknown_fields)]
 pub struct Config {
+    /// Schema version of the config file, missing in files written before versioning
+    pub config_version: Option<u32>,
     /// The Todoist Api token
     pub token: Option<String>,
+    /// The OAuth refresh token, used to get a new token when it expires
+    pub refresh_token: Option<String>,
+    /// When the OAuth token expires, as a unix timestamp in seconds
+    pub token_expires_at: Option<i64>,
     /// List of Todoist projects and their project numbers
     #[serde(rename = "projectsv1")]
     projects: Option<Vec<Project>>,
@@ -64,16 +87,22 @@ pub struct Config {
     /// The next task, for use with complete
     #[serde(rename = "next_taskv1")]
     next_task: Option<Task>,
+    /// IDs of tasks passed over with `task next --skip`, excluded until reset
+    skipped_tasks: Option<Vec<String>>,
     /// Whether to trigger terminal bell on success
     #[serde(default)]
     pub bell_on_success: bool,
     /// Whether to trigger terminal bell on error
     #[serde(default = "bell_on_failure_default")]
     pub bell_on_failure: bool,
+    /// ID of the project that quick add puts tasks into when no project is given
+    pub default_project: Option<String>,
     /// A command to to run on task creation
     pub task_create_command: Option<String>,
     /// A command to run on task completion
     pub task_complete_command: Option<String>,
+    /// URL that completed tasks are posted to as JSON
+    pub completion_webhook: Option<String>,
     /// A command to run on task comment creation
     pub task_comment_command: Option<String>,
     /// Regex to exclude tasks
@@ -101,8 +130,30 @@ pub struct Config {
     pub verbose: Option<bool>,
     /// Don't ask for sections
     pub no_sections: Option<bool>,
+    /// Write task content and descriptions in $EDITOR instead of a one line prompt
+    pub use_editor: Option<bool>,
     /// Goes straight to natural language input in datetime selection
     pub natural_language_only: Option<bool>,
+    /// Language code that natural language due dates are written in, i.e. "es". Defaults to English
+    pub language: Option<String>,
+    /// First day of the week for smart date suggestions, "monday" or "sunday". Defaults to Monday
+    pub week_start: Option<String>,
+    /// Typing filters long selection lists such as projects, sections and labels
+    pub fuzzy_select: Option<bool>,
+    /// Print dates as RFC 3339 timestamps instead of human readable strings
+    pub iso_dates: Option<bool>,
+    /// Only print ASCII in lists, dropping emoji and other symbols from task content
+    pub ascii: Option<bool>,
+    /// Symbols shown before task content in lists, one per priority
+    pub priority_symbols: Option<PrioritySymbols>,
+    /// Bulk changes to more than this many tasks always ask first, even with --force
+    pub confirm_threshold: Option<usize>,
+    /// Most API requests to run at once in bulk commands, 1 runs them one after another
+    pub max_concurrency: Option<usize>,
+    /// Request timeouts in seconds for particular commands, i.e. "test all", overriding `timeout`
+    pub timeouts: Option<HashMap<String, u64>>,
+    /// Project names keyed by label, used by `project empty --strategy by-label`
+    pub label_projects: Option<HashMap<String, String>>,
     /// Ordered list of fields used when sorting by value.
     pub sort_order: Option<Vec<SortRule>>,
     /// Legacy numeric sort configuration. Deserialized for migration only.
@@ -129,11 +180,26 @@ fn bell_on_failure_default() -> bool {
 pub struct Args {
     pub verbose: bool,
     pub timeout: Option<u64>,
+    pub log_file: Option<PathBuf>,
+    /// Hide progress output for bulk operations
+    pub quiet: bool,
+    /// Print dates as RFC 3339 timestamps
+    pub iso_dates: bool,
+    /// Only print ASCII priority symbols
+    pub ascii: bool,
+    /// Name of the command being run, i.e. "task create", used to look up `timeouts`
+    pub command: Option<String>,
+    /// Most API requests to run at once, overriding `max_concurrency` in config
+    pub max_concurrency: Option<usize>,
+    /// Most detailed level of log output, from `--log-level`
+    pub log_level: LogLevel,
 }
 
 #[derive(Default, Clone, Debug)]
 pub struct Internal {
     pub tx: Option<UnboundedSender<Error>>,
+    /// Token obtained from an OAuth refresh during this run, shared between clones of config
+    pub refreshed_token: Arc<Mutex<Option<String>>>,
 }
 
 #[derive(Copy, Clone, Serialize, Deserialize, Eq, PartialEq, Debug)]
@@ -285,7 +351,7 @@ impl<'de> Deserialize<'de> for SortRule {
 }
 
 impl Config {
-    fn with_default_sort_order(self) -> Config {
+    pub(crate) fn with_default_sort_order(self) -> Config {
         if self.sort_order.is_some() {
             self
         } else if let Some(sort_order) =
@@ -335,6 +401,87 @@ impl Config {
         }
     }
 
+    /// Whether typing filters selection lists, defaults to true
+    pub fn fuzzy_select(&self) -> bool {
+        self.fuzzy_select.unwrap_or(true)
+    }
+
+    /// Whether content and descriptions are written in $EDITOR, off unless set in config
+    pub fn use_editor(&self) -> bool {
+        self.use_editor.unwrap_or(false)
+    }
+
+    /// The configured language for natural language due dates, None when it is not set
+    pub fn language(&self) -> Result<Option<&str>, Error> {
+        match self.language.as_deref() {
+            None => Ok(None),
+            Some(language) if LANGUAGES.contains(&language) => Ok(Some(language)),
+            Some(language) => Err(Error::new(
+                "language",
+                &format!(
+                    "Unknown language '{language}' in config, use one of: {}",
+                    LANGUAGES.join(", ")
+                ),
+            )),
+        }
+    }
+
+    /// Drops a deleted label from label_projects, returning whether anything changed
+    pub fn remove_label(&mut self, name: &str) -> bool {
+        self.label_projects
+            .as_mut()
+            .is_some_and(|label_projects| label_projects.remove(name).is_some())
+    }
+
+    /// Moves a renamed label's label_projects entry to its new name, returning whether anything changed
+    pub fn rename_label(&mut self, from: &str, to: &str) -> bool {
+        let Some(label_projects) = self.label_projects.as_mut() else {
+            return false;
+        };
+        let Some(project) = label_projects.remove(from) else {
+            return false;
+        };
+        label_projects.insert(to.to_string(), project);
+        true
+    }
+
+    /// The first day of the week, Monday unless week_start in config is set to Sunday
+    pub fn week_start(&self) -> Result<Weekday, Error> {
+        match self.week_start.as_deref().map(str::to_lowercase).as_deref() {
+            None | Some("monday") => Ok(Weekday::Mon),
+            Some("sunday") => Ok(Weekday::Sun),
+            Some(week_start) => Err(Error::new(
+                "week_start",
+                &format!("Unknown week_start '{week_start}' in config, use monday or sunday"),
+            )),
+        }
+    }
+
+    /// Whether dates are printed as RFC 3339 timestamps, from either --iso-dates or config
+    pub fn iso_dates(&self) -> bool {
+        self.args.iso_dates || self.iso_dates.unwrap_or(false)
+    }
+
+    /// Whether only ASCII symbols should be printed, from --ascii, the config or the NO_COLOR environment variable
+    pub fn ascii(&self) -> bool {
+        self.ascii_content() || std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty())
+    }
+
+    /// Whether task content should be shown as ASCII only, from --ascii or the config.
+    /// NO_COLOR doesn't change content, as it only asks for plain styling.
+    pub fn ascii_content(&self) -> bool {
+        self.args.ascii || self.ascii.unwrap_or(false)
+    }
+
+    /// Most API requests to run at once, from --max-concurrency or the config, defaults to 4 and is never below 1
+    pub fn max_concurrency(&self) -> usize {
+        self.args
+            .max_concurrency
+            .or(self.max_concurrency)
+            .unwrap_or(DEFAULT_MAX_CONCURRENCY)
+            .max(1)
+    }
+
     /// Fetches a sender for the error channel
     /// Use this to end errors from an async process
     pub fn tx(self) -> UnboundedSender<Error> {
@@ -368,10 +515,7 @@ impl Config {
                             format::cyan_string("cargo install tod --force"),
                             format::cyan_string("brew update && brew upgrade tod")
                         );
-                        let _ = self.tx().send(Error {
-                            message,
-                            source: "Crates.io".into(),
-                        });
+                        let _ = self.tx().send(Error::new("Crates.io", &message));
                     }
                     Ok(Version::Latest) => (),
                     Err(err) => {
@@ -415,10 +559,15 @@ impl Config {
     #[allow(clippy::unused_async)]
     pub async fn new(tx: Option<UnboundedSender<Error>>, path: PathBuf) -> Result<Config, Error> {
         Ok(Config {
+            config_version: Some(CONFIG_VERSION),
             path,
             token: None,
+            refresh_token: None,
+            token_expires_at: None,
             next_id: None,
             next_task: None,
+            skipped_tasks: None,
+            default_project: None,
             last_version_check: None,
             timeout: None,
             bell_on_success: false,
@@ -432,21 +581,43 @@ impl Config {
             mock_url: None,
             no_sections: None,
             natural_language_only: None,
+            use_editor: None,
+            language: None,
+            week_start: None,
+            fuzzy_select: None,
+            iso_dates: None,
+            ascii: None,
+            priority_symbols: None,
+            confirm_threshold: None,
+            max_concurrency: None,
+            timeouts: None,
+            label_projects: None,
             mock_string: None,
             mock_select: None,
             max_comment_length: None,
             comment_exclude_regex: None,
             task_exclude_regex: None,
             verbose: None,
-            internal: Internal { tx },
+            internal: Internal {
+                tx,
+                ..Internal::default()
+            },
             args: Args {
                 verbose: false,
                 timeout: None,
+                log_file: None,
+                quiet: false,
+                iso_dates: false,
+                ascii: false,
+                command: None,
+                max_concurrency: None,
+                log_level: LogLevel::Warn,
             },
             time_provider: TimeProviderEnum::System(SystemTimeProvider),
             task_comment_command: None,
             task_create_command: None,
             task_complete_command: None,
+            completion_webhook: None,
             projects: Some(Vec::new()),
         })
     }
@@ -478,11 +649,62 @@ impl Config {
         self.next_task.clone()
     }
 
+    /// Adds a task ID to the skip set so that `task next` passes over it
+    pub fn skip_task(&self, task_id: &str) -> Config {
+        let mut skipped_tasks = self.skipped_tasks();
+        if !skipped_tasks.iter().any(|id| id == task_id) {
+            skipped_tasks.push(task_id.to_string());
+        }
+
+        Config {
+            skipped_tasks: Some(skipped_tasks),
+            ..self.clone()
+        }
+    }
+
+    pub fn clear_skipped_tasks(self) -> Config {
+        Config {
+            skipped_tasks: None,
+            ..self
+        }
+    }
+
+    pub fn skipped_tasks(&self) -> Vec<String> {
+        self.skipped_tasks.clone().unwrap_or_default()
+    }
+
     pub async fn set_token(&mut self, access_token: String) -> Result<String, Error> {
         self.token = Some(access_token);
         self.save().await
     }
 
+    /// Saves an OAuth token along with its refresh token and expiry, when provided
+    pub async fn set_access_token(&mut self, access_token: &AccessToken) -> Result<String, Error> {
+        self.token = Some(access_token.access_token.clone());
+        if let Some(refresh_token) = &access_token.refresh_token {
+            self.refresh_token = Some(refresh_token.clone());
+        }
+        self.token_expires_at = access_token
+            .expires_in
+            .map(|expires_in| self.time_provider.now(chrono_tz::UTC).timestamp() + expires_in);
+        self.save().await
+    }
+
+    /// Whether the OAuth token is past its expiry, false when the expiry is unknown
+    pub fn token_expired(&self) -> bool {
+        self.token_expires_at.is_some_and(|expires_at| {
+            expires_at <= self.time_provider.now(chrono_tz::UTC).timestamp()
+        })
+    }
+
+    /// Removes stored credentials and saves, leaving the rest of the config intact
+    pub async fn clear_token(&mut self) -> Result<String, Error> {
+        self.token = None;
+        self.refresh_token = None;
+        self.token_expires_at = None;
+        self.save().await
+    }
+
     pub async fn set_developer_token(mut self, key: &str) -> Result<Config, Error> {
         let trimmed_key = key.trim();
         if trimmed_key.is_empty() {
@@ -492,6 +714,9 @@ impl Config {
             ));
         }
 
+        // Developer tokens don't expire, so any OAuth refresh state no longer applies
+        self.refresh_token = None;
+        self.token_expires_at = None;
         self.set_token(trimmed_key.to_string()).await?;
         self.maybe_set_timezone().await
     }
@@ -503,6 +728,9 @@ impl Config {
             bell_on_success,
             comment_exclude_regex,
             disable_links,
+            fuzzy_select,
+            iso_dates,
+            ascii,
             max_comment_length,
             natural_language_only,
             no_sections,
@@ -517,8 +745,20 @@ impl Config {
             sort_order: _,
 
             // We don't want user to set the ones below
+            config_version: _,
+            language: _,
+            week_start: _,
+            priority_symbols: _,
+            confirm_threshold: _,
+            max_concurrency: _,
+            use_editor: _,
+            timeouts: _,
+            label_projects: _,
+            refresh_token: _,
+            token_expires_at: _,
             args: _,
             completed: _,
+            default_project: _,
             internal: _,
             last_version_check: _,
             mock_select,
@@ -527,9 +767,11 @@ impl Config {
             next_id: _,
             next_task: _,
             path: _,
+            skipped_tasks: _,
             projects: _,
             task_comment_command: _,
             task_complete_command: _,
+            completion_webhook: _,
             task_create_command: _,
             time_provider: _,
             timezone: _,
@@ -573,6 +815,30 @@ impl Config {
         let default_value = natural_language_only.unwrap_or(false);
         let natural_language_only = Some(input::bool(desc, default_value, mock_select)?);
 
+        // --- fuzzy_select
+        let desc = "
+            fuzzy_select
+            Type to filter long lists when selecting projects, sections, labels and tasks
+        ";
+        let default_value = fuzzy_select.unwrap_or(true);
+        let fuzzy_select = Some(input::bool(desc, default_value, mock_select)?);
+
+        // --- iso_dates
+        let desc = "
+            iso_dates
+            Print dates as RFC 3339 timestamps instead of human readable strings
+        ";
+        let default_value = iso_dates.unwrap_or(false);
+        let iso_dates = Some(input::bool(desc, default_value, mock_select)?);
+
+        // --- ascii
+        let desc = "
+            ascii
+            Only print ASCII in lists, dropping emoji and other symbols from task content
+        ";
+        let default_value = ascii.unwrap_or(false);
+        let ascii = Some(input::bool(desc, default_value, mock_select)?);
+
         // --- disable_links
         let desc = "
             disable_links
@@ -673,6 +939,9 @@ impl Config {
             verbose,
             no_sections,
             natural_language_only,
+            fuzzy_select,
+            iso_dates,
+            ascii,
             ..self.clone()
         };
 
@@ -683,16 +952,22 @@ impl Config {
 impl Default for Config {
     fn default() -> Self {
         Config {
+            config_version: None,
             token: None,
+            refresh_token: None,
+            token_expires_at: None,
             path: PathBuf::new(),
             next_id: None,
             next_task: None,
+            skipped_tasks: None,
+            default_project: None,
             last_version_check: None,
             timeout: None,
             bell_on_success: false,
             bell_on_failure: true,
             task_create_command: None,
             task_complete_command: None,
+            completion_webhook: None,
             task_comment_command: None,
             task_exclude_regex: None,
             comment_exclude_regex: None,
@@ -705,14 +980,32 @@ impl Default for Config {
             mock_url: None,
             no_sections: None,
             natural_language_only: None,
+            use_editor: None,
+            language: None,
+            week_start: None,
+            fuzzy_select: None,
+            iso_dates: None,
+            ascii: None,
+            priority_symbols: None,
+            confirm_threshold: None,
+            max_concurrency: None,
+            timeouts: None,
+            label_projects: None,
             mock_string: None,
             mock_select: None,
             max_comment_length: None,
             verbose: None,
-            internal: Internal { tx: None },
+            internal: Internal::default(),
             args: Args {
                 verbose: false,
                 timeout: None,
+                log_file: None,
+                quiet: false,
+                iso_dates: false,
+                ascii: false,
+                command: None,
+                max_concurrency: None,
+                log_level: LogLevel::Warn,
             },
             time_provider: TimeProviderEnum::System(SystemTimeProvider),
             projects: Some(Vec::new()),
@@ -732,23 +1025,36 @@ mod tests {
     impl Config {
         pub fn default_test() -> Self {
             Config {
+                config_version: Some(CONFIG_VERSION),
                 token: Some("default-token".to_string()),
+                refresh_token: None,
+                token_expires_at: None,
                 path: PathBuf::from("/tmp/test.cfg"),
                 time_provider: TimeProviderEnum::Fixed(FixedTimeProvider),
                 args: Args {
                     verbose: false,
                     timeout: None,
+                    log_file: None,
+                    quiet: false,
+                    iso_dates: false,
+                    ascii: false,
+                    command: None,
+                    max_concurrency: None,
+                    log_level: LogLevel::Warn,
                 },
-                internal: Internal { tx: None },
+                internal: Internal::default(),
                 sort_order: Some(SortRule::default_order()),
                 sort_value: None,
                 projects: Some(vec![]),
                 next_id: None,
                 next_task: None,
+                skipped_tasks: None,
+                default_project: None,
                 bell_on_success: false,
                 bell_on_failure: true,
                 task_create_command: None,
                 task_complete_command: None,
+                completion_webhook: None,
                 task_comment_command: None,
                 task_exclude_regex: None,
                 comment_exclude_regex: None,
@@ -765,6 +1071,17 @@ mod tests {
                 verbose: None,
                 no_sections: None,
                 natural_language_only: None,
+                use_editor: None,
+                language: None,
+                week_start: None,
+                fuzzy_select: None,
+                iso_dates: None,
+                ascii: None,
+                priority_symbols: None,
+                confirm_threshold: None,
+                max_concurrency: None,
+                timeouts: None,
+                label_projects: None,
             }
         }
         // Mock the url used for fetching projects and tasks
@@ -827,6 +1144,41 @@ mod tests {
         assert_eq!(config.next_task, None);
     }
 
+    #[test]
+    fn language_is_validated() {
+        let config = Config::default_test();
+        assert_eq!(config.language(), Ok(None));
+
+        let config = Config {
+            language: Some("es".to_string()),
+            ..Config::default_test()
+        };
+        assert_eq!(config.language(), Ok(Some("es")));
+
+        let config = Config {
+            language: Some("xx".to_string()),
+            ..Config::default_test()
+        };
+        let error = config.language().expect_err("unknown language should fail");
+        assert_eq!(error.source, "language");
+        assert!(error.message.contains("da, de, en"));
+    }
+
+    #[tokio::test]
+    async fn skip_and_clear_skipped_tasks_should_work() {
+        let config = test::fixtures::config().await;
+        assert!(config.skipped_tasks().is_empty());
+
+        let config = config.skip_task("1").skip_task("2").skip_task("1");
+        assert_eq!(
+            config.skipped_tasks(),
+            vec!["1".to_string(), "2".to_string()]
+        );
+
+        let config = config.clear_skipped_tasks();
+        assert_eq!(config.skipped_tasks, None);
+    }
+
     #[tokio::test]
     async fn add_project_should_work() {
         let mut config = test::fixtures::config().await;
@@ -887,6 +1239,13 @@ mod tests {
         let args = Args {
             verbose: true,
             timeout: Some(42),
+            log_file: None,
+            quiet: false,
+            iso_dates: false,
+            ascii: false,
+            command: None,
+            max_concurrency: None,
+            log_level: LogLevel::Warn,
         };
         let args_debug = format!("{args:?}");
         assert!(args_debug.contains("Args"));
@@ -894,7 +1253,10 @@ mod tests {
         assert!(args_debug.contains("timeout"));
 
         let (tx, _rx) = unbounded_channel::<Error>();
-        let internal = Internal { tx: Some(tx) };
+        let internal = Internal {
+            tx: Some(tx),
+            ..Internal::default()
+        };
         let internal_debug = format!("{internal:?}");
         assert!(internal_debug.contains("Internal"));
 
@@ -908,11 +1270,18 @@ mod tests {
         let args = Args {
             verbose: true,
             timeout: Some(10),
+            log_file: None,
+            quiet: false,
+            iso_dates: false,
+            ascii: false,
+            command: None,
+            max_concurrency: None,
+            log_level: LogLevel::Warn,
         };
         let args_clone = args.clone();
         assert_eq!(args, args_clone);
 
-        let internal = Internal { tx: None };
+        let internal = Internal::default();
         let internal_clone = internal.clone();
         assert_eq!(internal.tx.is_none(), internal_clone.tx.is_none());
 
@@ -924,14 +1293,28 @@ mod tests {
             args,
             Args {
                 verbose: true,
-                timeout: Some(10)
+                timeout: Some(10),
+                log_file: None,
+                quiet: false,
+                iso_dates: false,
+                ascii: false,
+                command: None,
+                max_concurrency: None,
+                log_level: LogLevel::Warn,
             }
         );
         assert_ne!(
             args,
             Args {
                 verbose: false,
-                timeout: Some(5)
+                timeout: Some(5),
+                log_file: None,
+                quiet: false,
+                iso_dates: false,
+                ascii: false,
+                command: None,
+                max_concurrency: None,
+                log_level: LogLevel::Warn,
             }
         );
 
@@ -1019,6 +1402,21 @@ mod tests {
         assert_eq!(config.max_comment_length(), 1234);
     }
 
+    #[test]
+    fn max_concurrency_should_prefer_args_then_config() {
+        let mut config = Config::default_test();
+        assert_eq!(config.max_concurrency(), 4);
+
+        config.max_concurrency = Some(2);
+        assert_eq!(config.max_concurrency(), 2);
+
+        config.args.max_concurrency = Some(8);
+        assert_eq!(config.max_concurrency(), 8);
+
+        config.args.max_concurrency = Some(0);
+        assert_eq!(config.max_concurrency(), 1);
+    }
+
     #[test]
     fn max_comment_length_should_fallback_when_not_set() {
         let config = Config {
@@ -1034,6 +1432,37 @@ mod tests {
         assert!(result <= MAX_COMMENT_LENGTH);
     }
 
+    #[test]
+    fn fuzzy_select_defaults_to_true() {
+        let config = Config::default_test();
+        assert!(config.fuzzy_select());
+
+        let config = Config {
+            fuzzy_select: Some(false),
+            ..Config::default_test()
+        };
+        assert!(!config.fuzzy_select());
+    }
+
+    #[test]
+    fn token_expired_compares_expiry_with_now() {
+        let config = Config::default_test();
+        assert!(!config.token_expired());
+
+        let now = config.time_provider.now(chrono_tz::UTC).timestamp();
+        let config = Config {
+            token_expires_at: Some(now - 1),
+            ..Config::default_test()
+        };
+        assert!(config.token_expired());
+
+        let config = Config {
+            token_expires_at: Some(now + 3600),
+            ..Config::default_test()
+        };
+        assert!(!config.token_expired());
+    }
+
     #[tokio::test]
     async fn check_for_latest_version_should_skip_if_checked_today() {
         let today =
@@ -46,4 +46,35 @@ impl Config {
 
         self.projects = Some(projects);
     }
+
+    /// The project quick add should use, None when no default project is set
+    pub async fn default_project(self: &Config) -> Result<Option<Project>, Error> {
+        let Some(id) = &self.default_project else {
+            return Ok(None);
+        };
+
+        let project = self
+            .projects()
+            .await?
+            .into_iter()
+            .find(|p| &p.id == id)
+            .ok_or_else(|| {
+                let command = format::blue_string("tod config set-default-project");
+                Error::new(
+                    "default_project",
+                    &format!("Default project is no longer in config, run {command} to change it"),
+                )
+            })?;
+
+        Ok(Some(project))
+    }
+
+    /// Saves the project quick add should use, or clears it when None
+    pub async fn set_default_project(
+        self: &mut Config,
+        project: Option<&Project>,
+    ) -> Result<String, Error> {
+        self.default_project = project.map(|p| p.id.clone());
+        self.save().await
+    }
 }
@@ -1,3 +1,5 @@
+use chrono_tz::Tz;
+
 use crate::{config::Config, errors::Error, todoist};
 
 impl Config {
@@ -11,10 +13,9 @@ impl Config {
 
     // Get timezone from config, or API if necessary
     pub fn get_timezone(&self) -> Result<String, Error> {
-        self.timezone.clone().ok_or_else(|| Error {
-            message: "Must set timezone".to_string(),
-            source: "get_timezone".to_string(),
-        })
+        self.timezone
+            .clone()
+            .ok_or_else(|| Error::new("get_timezone", "Must set timezone"))
     }
 
     pub async fn maybe_set_timezone(self) -> Result<Config, Error> {
@@ -33,4 +34,18 @@ impl Config {
 
         Ok(config)
     }
+
+    /// Set timezone to an IANA name and save to disk, rejecting unknown names before writing
+    pub async fn set_timezone_to(self, timezone: &str) -> Result<Config, Error> {
+        if timezone.parse::<Tz>().is_err() {
+            let message = format!(
+                "Unknown timezone '{timezone}', run \"tod config set-timezone --list\" to see valid names"
+            );
+            return Err(Error::new("config set-timezone", &message));
+        }
+        let mut config = self.with_timezone(timezone);
+        config.save().await?;
+
+        Ok(config)
+    }
 }
@@ -1,24 +1,142 @@
+use crate::errors::Error;
+use crate::time::TimeProvider;
 use crate::{config::Config, format};
+use std::fmt::Display;
+use std::io::Write;
+use std::path::Path;
+use std::time::Duration;
 const TOKEN_PREFIX_LENGTH: usize = 4;
+/// Number of characters in a Todoist developer API token
+pub const TOKEN_LENGTH: usize = 40;
+/// Query parameters containing any of these are redacted in logged urls
+const SENSITIVE_PARAMS: [&str; 3] = ["token", "code", "secret"];
 
-// Print a debug statement if in verbose mode
-pub fn maybe_print(config: &Config, text: &str) {
+/// How much tod logs, each level includes the ones before it
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Error,
+    #[default]
+    Warn,
+    Info,
+    Debug,
+}
+
+impl Display for LogLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            LogLevel::Error => "ERROR",
+            LogLevel::Warn => "WARN",
+            LogLevel::Info => "INFO",
+            LogLevel::Debug => "DEBUG",
+        };
+        write!(f, "{name}")
+    }
+}
+
+// The most detailed level logged. Verbose logs debug and a log file always gets API requests
+fn max_level(config: &Config) -> LogLevel {
+    let mut level = config.args.log_level;
     if config.verbose.unwrap_or_default() || config.args.verbose {
-        print(text);
+        level = level.max(LogLevel::Debug);
+    }
+    if config.args.log_file.is_some() {
+        level = level.max(LogLevel::Info);
+    }
+    level
+}
+
+pub fn enabled(config: &Config, level: LogLevel) -> bool {
+    level <= max_level(config)
+}
+
+// Log a statement at level, writing it to the log file instead when one is set
+pub fn log(config: &Config, level: LogLevel, text: &str) {
+    if !enabled(config, level) {
+        return;
+    }
+    let text = format!("{level} {text}");
+    match &config.args.log_file {
+        Some(path) if write_log(config, path, &text).is_ok() => (),
+        _ => print_level(level, &text),
     }
 }
 
+// Print a debug statement if in verbose mode or at --log-level debug
+pub fn maybe_print(config: &Config, text: &str) {
+    log(config, LogLevel::Debug, text);
+}
+
+// Log a summary of an API request at info level, to the log file when set.
+// The request has already been sent, so a log file that can't be written falls back to printing
+pub fn log_request(
+    config: &Config,
+    method: &str,
+    url: &str,
+    status: Option<u16>,
+    duration: Duration,
+    token: Option<&str>,
+) {
+    if !enabled(config, LogLevel::Info) {
+        return;
+    }
+    let status = status.map_or_else(|| String::from("no response"), |code| code.to_string());
+    let authorization = token
+        .map(|token| format!(" authorization: Bearer {}", redact_token(token)))
+        .unwrap_or_default();
+    let text = format!(
+        "{method} {} {status} {}ms{authorization}",
+        redact_url(url),
+        duration.as_millis()
+    );
+    log(config, LogLevel::Info, &text);
+}
+
+// Append a timestamped line to the log file
+fn write_log(config: &Config, path: &Path, text: &str) -> Result<(), Error> {
+    let timestamp = config.time_provider.now(chrono_tz::UTC).to_rfc3339();
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    writeln!(file, "[{timestamp}] {text}")?;
+    Ok(())
+}
+
+// Redact the values of query parameters that may hold credentials
+fn redact_url(url: &str) -> String {
+    let Some((path, query)) = url.split_once('?') else {
+        return url.to_string();
+    };
+    let query = query
+        .split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((key, value)) if SENSITIVE_PARAMS.iter().any(|param| key.contains(param)) => {
+                format!("{key}={}", redact_token(value))
+            }
+            _ => pair.to_string(),
+        })
+        .collect::<Vec<String>>()
+        .join("&");
+
+    format!("{path}?{query}")
+}
+
 // Print config with token redacted when in verbose mode.
 pub fn maybe_print_redacted_config(config: &Config) {
-    if config.verbose.unwrap_or_default() || config.args.verbose {
+    if enabled(config, LogLevel::Debug) {
         let token = config.token.as_ref().map(|token| redact_token(token));
+        let refresh_token = config
+            .refresh_token
+            .as_ref()
+            .map(|token| redact_token(token));
         let mut redacted_config = config.clone();
         redacted_config.token = token;
+        redacted_config.refresh_token = refresh_token;
         print(&format!("{redacted_config:#?}"));
     }
 }
 
-fn redact_token(token: &str) -> String {
+pub fn redact_token(token: &str) -> String {
     let visible: String = token.chars().take(TOKEN_PREFIX_LENGTH).collect();
     let redacted_length = token.chars().count().saturating_sub(TOKEN_PREFIX_LENGTH);
     format!("{visible}{}", "x".repeat(redacted_length))
@@ -26,10 +144,18 @@ fn redact_token(token: &str) -> String {
 
 // Print a debug statement
 pub fn print(text: &str) {
-    let text = format!("=== DEBUG ===\n{text}\n===");
+    print_level(LogLevel::Debug, text);
+}
+
+// Print a statement with its level, errors and warnings go to stderr
+fn print_level(level: LogLevel, text: &str) {
+    let text = format!("=== {level} ===\n{text}\n===");
     let text = format::debug_string(&text);
 
-    println!("{text}");
+    match level {
+        LogLevel::Error | LogLevel::Warn => eprintln!("{text}"),
+        LogLevel::Info | LogLevel::Debug => println!("{text}"),
+    }
 }
 
 #[cfg(test)]
@@ -43,6 +169,60 @@ mod tests {
         assert_eq!(redacted, "abcdxxxx");
     }
 
+    #[test]
+    fn redact_url_hides_sensitive_query_values() {
+        let redacted = redact_url("https://todoist.com/oauth?code=abcd1234&state=visible");
+        assert_eq!(
+            redacted,
+            "https://todoist.com/oauth?code=abcdxxxx&state=visible"
+        );
+        assert_eq!(redact_url("/api/v1/tasks/"), "/api/v1/tasks/");
+    }
+
+    #[test]
+    fn log_request_writes_redacted_summary_to_log_file() {
+        let dir = tempfile::tempdir().expect("temp dir should be created");
+        let path = dir.path().join("tod.log");
+        let mut config = Config::default_test();
+        config.args.log_file = Some(path.clone());
+
+        log_request(
+            &config,
+            "GET",
+            "https://api.todoist.com/api/v1/user?token=abcd1234",
+            Some(200),
+            Duration::from_millis(12),
+            Some("efgh5678"),
+        );
+
+        let log = std::fs::read_to_string(path).expect("log should be readable");
+        assert!(log.starts_with('['));
+        assert!(log.contains(
+            "] INFO GET https://api.todoist.com/api/v1/user?token=abcdxxxx 200 12ms authorization: Bearer efghxxxx\n"
+        ));
+    }
+
+    #[test]
+    fn log_request_survives_an_unwritable_log_file() {
+        let dir = tempfile::tempdir().expect("temp dir should be created");
+        let path = dir.path().join("missing").join("tod.log");
+        let mut config = Config::default_test();
+        // The directory doesn't exist so the file can't be created, and the summary is printed instead
+        config.args.log_file = Some(path.clone());
+
+        log_request(
+            &config,
+            "GET",
+            "/api/v1/user",
+            None,
+            Duration::from_millis(3),
+            None,
+        );
+        assert!(!path.exists());
+        let mut entries = std::fs::read_dir(dir.path()).expect("temp dir should be readable");
+        assert!(entries.next().is_none());
+    }
+
     #[test]
     fn redact_token_handles_short_tokens() {
         let redacted = redact_token("abc");
@@ -57,4 +237,34 @@ mod tests {
 
         maybe_print_redacted_config(&config);
     }
+
+    #[test]
+    fn log_level_gates_what_is_logged() {
+        let mut config = Config::default_test();
+        assert!(enabled(&config, LogLevel::Warn));
+        assert!(!enabled(&config, LogLevel::Info));
+
+        config.args.log_level = LogLevel::Info;
+        assert!(enabled(&config, LogLevel::Info));
+        assert!(!enabled(&config, LogLevel::Debug));
+
+        config.args.log_level = LogLevel::Error;
+        config.args.verbose = true;
+        assert!(enabled(&config, LogLevel::Debug));
+    }
+
+    #[test]
+    fn log_writes_level_to_log_file() {
+        let dir = tempfile::tempdir().expect("temp dir should be created");
+        let path = dir.path().join("tod.log");
+        let mut config = Config::default_test();
+        config.args.log_file = Some(path.clone());
+
+        log(&config, LogLevel::Debug, "hidden");
+        log(&config, LogLevel::Warn, "shown");
+
+        let log = std::fs::read_to_string(path).expect("log should be readable");
+        assert!(log.contains("] WARN shown\n"));
+        assert!(!log.contains("hidden"));
+    }
 }
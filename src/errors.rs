@@ -12,11 +12,45 @@ use tokio::{sync::oneshot::error::RecvError, task::JoinError};
 pub struct Error {
     pub message: String,
     pub source: String,
+    #[serde(default)]
+    pub kind: ErrorKind,
+}
+
+/// Broad category of an error, each one exits with its own code so that scripts can tell them apart
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub enum ErrorKind {
+    #[default]
+    General,
+    /// Missing, expired or rejected credentials
+    Auth,
+    /// Todoist could not be reached or the connection failed
+    Network,
+    /// A project, task or file that was asked for does not exist
+    NotFound,
+    /// Todoist responded with an error
+    Api,
+    /// The user cancelled a prompt with Esc or Ctrl-C, or interrupted tod with Ctrl-C
+    UserCancelled,
+}
+
+impl ErrorKind {
+    pub fn exit_code(self) -> u8 {
+        match self {
+            ErrorKind::General => 1,
+            ErrorKind::Auth => 3,
+            ErrorKind::Network => 4,
+            ErrorKind::NotFound => 5,
+            ErrorKind::Api => 6,
+            ErrorKind::UserCancelled => 130,
+        }
+    }
 }
 
 impl Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let Error { source, message } = self;
+        let Error {
+            source, message, ..
+        } = self;
         write!(
             f,
             "Error from {}:\n{}",
@@ -31,6 +65,7 @@ impl From<std::io::Error> for Error {
         Self {
             source: "io".into(),
             message: format!("{value}"),
+            kind: ErrorKind::General,
         }
     }
 }
@@ -40,6 +75,7 @@ impl From<regex::Error> for Error {
         Self {
             source: "regex".into(),
             message: format!("{value}"),
+            kind: ErrorKind::General,
         }
     }
 }
@@ -49,6 +85,7 @@ impl From<RecvError> for Error {
         Self {
             source: String::from("RecvError"),
             message: "Sender dropped without sending".to_string(),
+            kind: ErrorKind::General,
         }
     }
 }
@@ -58,6 +95,7 @@ impl From<TryFromIntError> for Error {
         Self {
             source: "TryFromIntError".into(),
             message: format!("{value}"),
+            kind: ErrorKind::General,
         }
     }
 }
@@ -67,6 +105,7 @@ impl From<JoinError> for Error {
         Self {
             source: "Join on future".into(),
             message: format!("{value}"),
+            kind: ErrorKind::General,
         }
     }
 }
@@ -76,6 +115,7 @@ impl From<chrono::LocalResult<chrono::DateTime<chrono_tz::Tz>>> for Error {
         Self {
             source: "chrono".into(),
             message: format!("{value:?}"),
+            kind: ErrorKind::General,
         }
     }
 }
@@ -85,6 +125,7 @@ impl From<tokio::sync::mpsc::error::SendError<Error>> for Error {
         Self {
             source: "tokio mpsc".into(),
             message: format!("{value}"),
+            kind: ErrorKind::General,
         }
     }
 }
@@ -94,6 +135,7 @@ impl From<chrono_tz::ParseError> for Error {
         Self {
             source: "chrono_tz".into(),
             message: format!("{value}"),
+            kind: ErrorKind::General,
         }
     }
 }
@@ -103,6 +145,7 @@ impl From<ParseIntError> for Error {
         Self {
             source: "ParseIntError".into(),
             message: format!("{value}"),
+            kind: ErrorKind::General,
         }
     }
 }
@@ -112,6 +155,7 @@ impl From<chrono::ParseError> for Error {
         Self {
             source: "chrono".into(),
             message: format!("{value}"),
+            kind: ErrorKind::General,
         }
     }
 }
@@ -121,6 +165,7 @@ impl From<GetHomeError> for Error {
         Self {
             source: "homedir".into(),
             message: format!("{value}"),
+            kind: ErrorKind::General,
         }
     }
 }
@@ -130,6 +175,7 @@ impl From<serde_json::Error> for Error {
         Self {
             source: "serde_json".into(),
             message: format!("{value}"),
+            kind: ErrorKind::General,
         }
     }
 }
@@ -139,6 +185,7 @@ impl From<reqwest::Error> for Error {
         Self {
             source: "reqwest".into(),
             message: format!("{value}"),
+            kind: ErrorKind::Network,
         }
     }
 }
@@ -148,6 +195,11 @@ impl From<inquire::InquireError> for Error {
         Self {
             source: "inquire".into(),
             message: format!("{value}"),
+            kind: match value {
+                inquire::InquireError::OperationCanceled
+                | inquire::InquireError::OperationInterrupted => ErrorKind::UserCancelled,
+                _ => ErrorKind::General,
+            },
         }
     }
 }
@@ -157,8 +209,13 @@ impl Error {
         Error {
             source: source.into(),
             message: message.into(),
+            kind: ErrorKind::General,
         }
     }
+
+    pub fn with_kind(self, kind: ErrorKind) -> Error {
+        Error { kind, ..self }
+    }
 }
 
 #[cfg(test)]
@@ -171,6 +228,7 @@ mod tests {
         let error = Error {
             message: "there".to_string(),
             source: "hello".to_string(),
+            kind: ErrorKind::General,
         };
         assert_eq!(error.to_string(), String::from("Error from hello:\nthere"));
     }
@@ -214,6 +272,44 @@ mod tests {
         assert!(!e.message.is_empty());
     }
 
+    #[test]
+    fn test_from_inquire_error_sets_kind() {
+        let cancelled: Error = inquire::InquireError::OperationCanceled.into();
+        assert_eq!(cancelled.kind, ErrorKind::UserCancelled);
+
+        let interrupted: Error = inquire::InquireError::OperationInterrupted.into();
+        assert_eq!(interrupted.kind, ErrorKind::UserCancelled);
+
+        let other: Error = inquire::InquireError::NotTTY.into();
+        assert_eq!(other.kind, ErrorKind::General);
+    }
+
+    #[test]
+    fn test_exit_codes_are_distinct() {
+        let kinds = [
+            ErrorKind::General,
+            ErrorKind::Auth,
+            ErrorKind::Network,
+            ErrorKind::NotFound,
+            ErrorKind::Api,
+            ErrorKind::UserCancelled,
+        ];
+        let mut codes = kinds.map(ErrorKind::exit_code).to_vec();
+        codes.sort_unstable();
+        codes.dedup();
+        assert_eq!(codes.len(), kinds.len());
+        assert!(!codes.contains(&0));
+        // clap uses 2 for usage errors
+        assert!(!codes.contains(&2));
+    }
+
+    #[test]
+    fn test_with_kind() {
+        let e = Error::new("src", "msg").with_kind(ErrorKind::Auth);
+        assert_eq!(e.kind, ErrorKind::Auth);
+        assert_eq!(e.source, "src");
+    }
+
     #[test]
     fn test_error_clone_and_eq() {
         let e = Error::new("src", "msg");
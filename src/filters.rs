@@ -1,77 +1,90 @@
 use futures::{StreamExt, TryStreamExt, future, stream};
 
 use crate::{
-    SortOrder,
     config::Config,
     errors::Error,
     format,
     input::{self},
     projects::TaskFilter,
-    tasks::{self, FormatType, Task},
+    tasks::{self, FormatType, SortOptions, Task},
     todoist,
 };
 
-pub async fn edit_task(config: &Config, filter: String) -> Result<String, Error> {
+pub async fn edit_task(config: &Config, filter: String, editor: bool) -> Result<String, Error> {
     let tasks = todoist::all_tasks_by_filters(config, &filter)
         .await?
         .into_iter()
         .flat_map(|(_, tasks)| tasks)
         .collect::<Vec<Task>>();
 
-    let task = input::select(input::TASK, tasks, config.mock_select)?;
+    let task = input::fuzzy_select(
+        input::TASK,
+        tasks,
+        config.fuzzy_select(),
+        config.mock_select,
+    )?;
 
-    let options = tasks::edit_task_attributes();
-
-    let selections = input::multi_select(input::ATTRIBUTES, options, config.mock_select)?;
-
-    if selections.is_empty() {
-        return Err(Error {
-            message: "Nothing selected".to_string(),
-            source: "edit_task".to_string(),
-        });
-    }
-
-    let mut handles = Vec::new();
-    for attribute in selections {
-        // Stops the inputs from rolling over each other in terminal
-        println!();
-        if let Some(handle) = tasks::update_task(config, &task, &attribute).await? {
-            handles.push(handle);
-        }
-    }
-
-    future::join_all(handles).await;
-    Ok("Finished editing task".into())
+    tasks::edit(config, &task, editor).await
 }
 
-/// Get the next task by priority and save its id to config
-pub async fn next_task(config: &Config, filter: &str) -> Result<String, Error> {
-    match fetch_next_task(config, filter).await {
+/// Get the next task by priority and save its id to config, opening it in the browser when `open` is set.
+/// Lists the links in its comments beneath it when `links` is set
+pub async fn next_task(
+    config: &Config,
+    filter: &str,
+    sort: &SortOptions,
+    open: bool,
+    links: bool,
+) -> Result<String, Error> {
+    match fetch_next_task(config, filter, sort).await {
         Ok(Some((task, remaining))) => {
             let comments = todoist::all_comments(config, &task.id, None).await?;
+            let links = if links {
+                tasks::format_comment_links(&comments)
+            } else {
+                String::new()
+            };
             let task_string = task.fmt(comments, config, FormatType::Single, true).await?;
-            config.set_next_task(task).save().await?;
-            Ok(format!("{task_string}\n{remaining} task(s) remaining"))
+            config.set_next_task(task.clone()).save().await?;
+            // The task is already saved as next, so a browser that fails to open only gets reported
+            let open_failure = match tasks::maybe_open(config, &task, open) {
+                Ok(()) => String::new(),
+                Err(e) => format!("\n{}", format::red_string(&e.message)),
+            };
+            Ok(format!(
+                "{task_string}{links}{open_failure}\n{remaining} task(s) remaining"
+            ))
         }
         Ok(None) => Ok(format::green_string("No tasks on list")),
         Err(e) => Err(e),
     }
 }
 
-async fn fetch_next_task(config: &Config, filter: &str) -> Result<Option<(Task, usize)>, Error> {
+async fn fetch_next_task(
+    config: &Config,
+    filter: &str,
+    sort: &SortOptions,
+) -> Result<Option<(Task, usize)>, Error> {
     let tasks = todoist::all_tasks_by_filters(config, filter)
         .await?
         .into_iter()
         .flat_map(|(_, tasks)| tasks)
         .collect::<Vec<Task>>();
 
-    let tasks = tasks::sort_by_value(tasks, config);
+    let tasks = tasks::filter_skipped(tasks, config);
+    let tasks = tasks::sort(tasks, config, *sort);
 
     Ok(tasks.first().map(|task| (task.to_owned(), tasks.len())))
 }
 
-/// Put dates on all tasks without dates
-pub async fn schedule(config: &Config, filter: &str, sort: &SortOrder) -> Result<String, Error> {
+/// Put dates on all tasks without dates, stopping at the first failed update when `strict` is set
+pub async fn schedule(
+    config: &Config,
+    filter: &str,
+    sort: &SortOptions,
+    smart: bool,
+    strict: bool,
+) -> Result<String, Error> {
     let tasks = todoist::all_tasks_by_filters(config, filter)
         .await?
         .into_iter()
@@ -85,8 +98,12 @@ pub async fn schedule(config: &Config, filter: &str, sort: &SortOrder) -> Result
             "No tasks to schedule in '{filter}'"
         )))
     } else {
-        let handles = stream::iter(tasks)
-            .then(|task| tasks::spawn_schedule_task(config.clone(), task))
+        let total = tasks.len();
+        let handles = stream::iter(tasks.into_iter().enumerate())
+            .then(|(index, task)| {
+                tasks::print_progress(config, index + 1, total, "scheduling", &task);
+                tasks::spawn_schedule_task(config.clone(), task, smart, strict)
+            })
             .try_collect::<Vec<_>>()
             .await?
             .into_iter()
@@ -99,8 +116,13 @@ pub async fn schedule(config: &Config, filter: &str, sort: &SortOrder) -> Result
         )))
     }
 }
-/// Put deadlines on all non-recurring tasks without deadlines
-pub async fn deadline(config: &Config, filter: &str, sort: &SortOrder) -> Result<String, Error> {
+/// Put deadlines on all non-recurring tasks without deadlines, stopping at the first failed update when `strict` is set
+pub async fn deadline(
+    config: &Config,
+    filter: &str,
+    sort: &SortOptions,
+    strict: bool,
+) -> Result<String, Error> {
     let tasks = todoist::all_tasks_by_filters(config, filter)
         .await?
         .into_iter()
@@ -118,8 +140,12 @@ pub async fn deadline(config: &Config, filter: &str, sort: &SortOrder) -> Result
             "No tasks to deadline in '{filter}'"
         )))
     } else {
-        let handles = stream::iter(filtered_tasks)
-            .then(|task| tasks::spawn_deadline_task(config.clone(), task))
+        let total = filtered_tasks.len();
+        let handles = stream::iter(filtered_tasks.into_iter().enumerate())
+            .then(|(index, task)| {
+                tasks::print_progress(config, index + 1, total, "deadlining", &task);
+                tasks::spawn_deadline_task(config.clone(), task, strict)
+            })
             .try_collect::<Vec<_>>()
             .await?
             .into_iter()
@@ -156,7 +182,7 @@ mod tests {
             .with_mock_url(server.url())
             .mock_select(0);
 
-        let result = edit_task(&config, "today".into());
+        let result = edit_task(&config, "today".into(), false);
         assert_eq!(result.await, Ok("Finished editing task".to_string()));
         mock.assert();
     }
@@ -197,9 +223,15 @@ mod tests {
             .expect("expected value or result, got None or Err");
 
         let filter = String::from("today");
-        let task = next_task(&config_with_timezone, &filter)
-            .await
-            .expect("expected value or result, got None or Err");
+        let task = next_task(
+            &config_with_timezone,
+            &filter,
+            &SortOptions::default(),
+            false,
+            false,
+        )
+        .await
+        .expect("expected value or result, got None or Err");
 
         assert!(task.contains("TEST"));
         assert!(task.contains("for 15 min"));
@@ -244,8 +276,8 @@ mod tests {
             .with_mock_string("tod");
 
         let filter = String::from("today");
-        let sort = &SortOrder::Value;
-        let result = schedule(&config, &filter, sort);
+        let sort = &SortOptions::default();
+        let result = schedule(&config, &filter, sort, false, false);
         assert_eq!(
             result.await,
             Ok("Successfully scheduled tasks in 'today'".to_string())
@@ -254,7 +286,7 @@ mod tests {
         let config = config.mock_select(2);
 
         let filter = String::from("today");
-        let result = schedule(&config, &filter, sort);
+        let result = schedule(&config, &filter, sort, false, false);
         assert_eq!(
             result.await,
             Ok("Successfully scheduled tasks in 'today'".to_string())
@@ -265,6 +297,43 @@ mod tests {
         mock3.expect(2);
     }
 
+    #[tokio::test]
+    async fn test_schedule_smart() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api/v1/tasks/filter?query=today&limit=200")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::TodayTasks.read().await)
+            .create_async()
+            .await;
+        let update_mock = server
+            .mock("POST", "/api/v1/tasks/6Xqhv4cwxgjwG9w8")
+            .match_body(mockito::Matcher::PartialJson(
+                serde_json::json!({"due_string": "2025-05-11"}),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::TodayTask.read().await)
+            .expect(1)
+            .create_async()
+            .await;
+
+        // Fixed time is Saturday 2025-05-10, so Tomorrow is the second quick pick
+        let config = test::fixtures::config()
+            .await
+            .with_mock_url(server.url())
+            .mock_select(1);
+
+        let result = schedule(&config, "today", &SortOptions::default(), true, false).await;
+        assert_eq!(
+            result,
+            Ok("Successfully scheduled tasks in 'today'".to_string())
+        );
+        mock.assert();
+        update_mock.assert();
+    }
+
     #[tokio::test]
     async fn test_deadline() {
         let mut server = mockito::Server::new_async().await;
@@ -302,8 +371,8 @@ mod tests {
             .with_mock_string("tod");
 
         let filter = String::from("today");
-        let sort = &SortOrder::Value;
-        let result = deadline(&config, &filter, sort);
+        let sort = &SortOptions::default();
+        let result = deadline(&config, &filter, sort, false);
         assert_eq!(
             result.await,
             Ok("Successfully deadlined tasks in 'today'".to_string())
@@ -312,7 +381,7 @@ mod tests {
         let config = config.mock_select(2);
 
         let filter = String::from("today");
-        let result = deadline(&config, &filter, sort);
+        let result = deadline(&config, &filter, sort, false);
         assert_eq!(
             result.await,
             Ok("Successfully deadlined tasks in 'today'".to_string())
@@ -36,6 +36,10 @@ pub fn yellow_string(str: &str) -> String {
     apply_color(str, |s| s.yellow())
 }
 
+pub fn strikethrough_string(str: &str) -> String {
+    apply_color(str, |s| s.strikethrough())
+}
+
 pub fn debug_string(str: &str) -> String {
     apply_color(str, |s| s.bright_blue().on_yellow())
 }
@@ -44,6 +48,77 @@ pub fn normal_string(str: &str) -> String {
     String::from(str).normal().to_string()
 }
 
+/// Colors text with the closest terminal color to a Todoist color name such as "berry_red" or "sky_blue",
+/// greys and unknown colors are left as normal text
+pub fn todoist_color_string(str: &str, color: &str) -> String {
+    match color {
+        "berry_red" | "red" | "salmon" => red_string(str),
+        "orange" | "yellow" | "taupe" => yellow_string(str),
+        "olive_green" | "lime_green" | "green" | "mint_green" => green_string(str),
+        "teal" | "sky_blue" | "light_blue" => cyan_string(str),
+        "blue" => blue_string(str),
+        "grape" | "violet" | "lavender" | "magenta" => purple_string(str),
+        _ => normal_string(str),
+    }
+}
+
+/// Replaces common accented letters and punctuation with ASCII look-alikes and drops any other non-ASCII characters,
+/// such as emoji, along with the spaces left around them
+pub fn ascii_only(text: &str) -> String {
+    if text.is_ascii() {
+        return text.to_string();
+    }
+
+    let mut ascii = String::with_capacity(text.len());
+    for c in text.chars() {
+        match transliterate(c) {
+            Some(replacement) => ascii.push_str(replacement),
+            None if c.is_ascii() => ascii.push(c),
+            None => ascii.push(' '),
+        }
+    }
+    ascii
+        .split(' ')
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+fn transliterate(c: char) -> Option<&'static str> {
+    let replacement = match c {
+        'À'..='Å' => "A",
+        'à'..='å' => "a",
+        'Æ' => "AE",
+        'æ' => "ae",
+        'Ç' => "C",
+        'ç' => "c",
+        'È'..='Ë' => "E",
+        'è'..='ë' => "e",
+        'Ì'..='Ï' => "I",
+        'ì'..='ï' => "i",
+        'Ñ' => "N",
+        'ñ' => "n",
+        'Ò'..='Ö' | 'Ø' => "O",
+        'ò'..='ö' | 'ø' => "o",
+        'Œ' => "OE",
+        'œ' => "oe",
+        'Ù'..='Ü' => "U",
+        'ù'..='ü' => "u",
+        'Ý' | 'Ÿ' => "Y",
+        'ý' | 'ÿ' => "y",
+        'ß' => "ss",
+        '‘' | '’' | '‚' | '′' => "'",
+        '“' | '”' | '„' | '″' | '«' | '»' => "\"",
+        '‐' | '‑' | '‒' | '–' | '—' | '−' => "-",
+        '…' => "...",
+        '•' => "*",
+        '×' => "x",
+        '\u{a0}' => " ",
+        _ => return None,
+    };
+    Some(replacement)
+}
+
 pub fn hyperlinks_disabled(config: &Config) -> bool {
     config.disable_links || !supports_hyperlinks::on(Stream::Stdout)
 }
@@ -119,6 +194,15 @@ pub fn maybe_format_url(url: &str, config: &Config) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn ascii_only_transliterates_and_drops_symbols() {
+        assert_eq!(ascii_only("Plain text  stays"), "Plain text  stays");
+        assert_eq!(ascii_only("Crème brûlée – naïve"), "Creme brulee - naive");
+        assert_eq!(ascii_only("Ship it 🚀 today"), "Ship it today");
+        assert_eq!(ascii_only("👨‍👩‍👧 Family ❤️"), "Family");
+        assert_eq!(ascii_only("会議"), "");
+    }
+
     #[test]
     fn test_blue_string() {
         assert_eq!(blue_string("TEST"), "TEST");
@@ -139,6 +223,13 @@ mod tests {
         assert_eq!(red_string("ERR"), "ERR");
     }
 
+    #[test]
+    fn test_todoist_color_string() {
+        assert_eq!(todoist_color_string("work", "berry_red"), "work");
+        assert_eq!(todoist_color_string("work", "charcoal"), "work");
+        assert_eq!(todoist_color_string("work", ""), "work");
+    }
+
     #[test]
     fn test_cyan_string() {
         assert_eq!(cyan_string("INFO"), "INFO");
@@ -0,0 +1,222 @@
+//! Short numbers for the tasks shown by `list view`, so that a task can be picked with `tod task complete 3`
+//! Numbers are kept in the temp directory for each config, one list per project or filter,
+//! and stop working as soon as the tasks in that project or filter change
+use crate::cleanup;
+use crate::config::Config;
+use crate::errors::{Error, ErrorKind};
+use crate::lists::Flag;
+use crate::projects::Project;
+use crate::tasks::Task;
+use crate::todoist;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct IndexFile {
+    /// Key of the most recently listed project or filter
+    last: Option<String>,
+    lists: HashMap<String, IndexedList>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexedList {
+    project: Option<Project>,
+    filter: Option<String>,
+    /// Task IDs in the order they were numbered, the number is the position plus one
+    task_ids: Vec<String>,
+    /// Every task ID in the project or filter when it was listed, used to tell when the list has changed
+    snapshot: Vec<String>,
+}
+
+impl IndexedList {
+    fn flag(&self) -> Option<Flag> {
+        match (&self.project, &self.filter) {
+            (Some(project), _) => Some(Flag::Project(project.clone())),
+            (None, Some(filter)) => Some(Flag::Filter(filter.clone())),
+            (None, None) => None,
+        }
+    }
+}
+
+/// Remembers the numbers given to tasks in a project or filter
+/// `snapshot` is every task fetched, before any filtering by priority or due date
+pub async fn save(
+    config: &Config,
+    flag: &Flag,
+    task_ids: Vec<String>,
+    mut snapshot: Vec<String>,
+) -> Result<(), Error> {
+    let path = index_path(config);
+    let mut file = load(&path).await;
+    let key = flag_key(flag);
+    let (project, filter) = match flag {
+        Flag::Project(project) => (Some(project.clone()), None),
+        Flag::Filter(filter) => (None, Some(filter.clone())),
+    };
+    snapshot.sort();
+    snapshot.dedup();
+    file.lists.insert(
+        key.clone(),
+        IndexedList {
+            project,
+            filter,
+            task_ids,
+            snapshot,
+        },
+    );
+    file.last = Some(key);
+
+    cleanup::write_file(&path, &serde_json::to_string(&file)?).await
+}
+
+/// Finds the task with a number from the last `list view` of a project or filter, or of whatever was listed last
+/// Errors when the tasks have changed since they were numbered, as the number may now point at another task
+pub async fn resolve(config: &Config, flag: Option<&Flag>, index: u32) -> Result<Task, Error> {
+    let file = load(&index_path(config)).await;
+    let key = match flag {
+        Some(flag) => Some(flag_key(flag)),
+        None => file.last.clone(),
+    };
+    let not_listed = || {
+        let scope = flag.map(|flag| format!(" for {flag}")).unwrap_or_default();
+        Error::new(
+            "task_index",
+            &format!("No numbered tasks{scope}, run tod list view first"),
+        )
+        .with_kind(ErrorKind::NotFound)
+    };
+    let list = key
+        .and_then(|key| file.lists.get(&key).cloned())
+        .ok_or_else(not_listed)?;
+    let flag = list.flag().ok_or_else(not_listed)?;
+
+    let task_id = usize::try_from(index)
+        .ok()
+        .and_then(|index| index.checked_sub(1))
+        .and_then(|position| list.task_ids.get(position))
+        .ok_or_else(|| {
+            Error::new(
+                "task_index",
+                &format!(
+                    "There is no task {index} in {flag}, the last list view numbered 1 to {}",
+                    list.task_ids.len()
+                ),
+            )
+            .with_kind(ErrorKind::NotFound)
+        })?;
+
+    let tasks = match &flag {
+        Flag::Project(project) => todoist::all_tasks_by_project(config, project, None).await?,
+        Flag::Filter(filter) => todoist::all_tasks_by_filters(config, filter)
+            .await?
+            .into_iter()
+            .flat_map(|(_, tasks)| tasks)
+            .collect(),
+    };
+    let mut current = tasks
+        .iter()
+        .map(|task| task.id.clone())
+        .collect::<Vec<String>>();
+    current.sort();
+    current.dedup();
+    if current != list.snapshot {
+        return Err(Error::new(
+            "task_index",
+            &format!(
+                "The tasks in {flag} have changed since they were numbered, run tod list view again"
+            ),
+        ));
+    }
+
+    tasks
+        .into_iter()
+        .find(|task| &task.id == task_id)
+        .ok_or_else(|| {
+            Error::new(
+                "task_index",
+                &format!("Task {index} is no longer in {flag}"),
+            )
+            .with_kind(ErrorKind::NotFound)
+        })
+}
+
+async fn load(path: &Path) -> IndexFile {
+    match fs::read_to_string(path).await {
+        Ok(json) => serde_json::from_str(&json).unwrap_or_default(),
+        Err(_) => IndexFile::default(),
+    }
+}
+
+fn flag_key(flag: &Flag) -> String {
+    match flag {
+        Flag::Project(project) => format!("project:{}", project.id),
+        Flag::Filter(filter) => format!("filter:{filter}"),
+    }
+}
+
+fn index_path(config: &Config) -> PathBuf {
+    config.temp_path("tod-index", "")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test;
+    use crate::test::responses::ResponseFromFile;
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test]
+    async fn resolve_should_find_numbered_task_until_list_changes() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api/v1/tasks/?project_id=123&limit=200")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::TodayTasks.read().await)
+            .expect(2)
+            .create_async()
+            .await;
+        let config = test::fixtures::config()
+            .await
+            .create()
+            .await
+            .expect("expected value or result, got None or Err")
+            .with_mock_url(server.url());
+        let flag = Flag::Project(test::fixtures::project());
+        let task_id = test::fixtures::today_task().await.id;
+
+        let error = resolve(&config, None, 1)
+            .await
+            .expect_err("nothing has been listed yet");
+        assert_eq!(error.kind, ErrorKind::NotFound);
+
+        save(&config, &flag, vec![task_id.clone()], vec![task_id.clone()])
+            .await
+            .expect("saving should succeed");
+        let task = resolve(&config, None, 1)
+            .await
+            .expect("task 1 should resolve");
+        assert_eq!(task.id, task_id);
+
+        let error = resolve(&config, Some(&flag), 2)
+            .await
+            .expect_err("only one task was numbered");
+        assert!(error.message.contains("no task 2"));
+
+        save(
+            &config,
+            &flag,
+            vec![task_id.clone()],
+            vec![task_id, "completed".to_string()],
+        )
+        .await
+        .expect("saving should succeed");
+        let error = resolve(&config, Some(&flag), 1)
+            .await
+            .expect_err("the list has changed");
+        assert!(error.message.contains("have changed"));
+        mock.assert_async().await;
+    }
+}
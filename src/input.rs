@@ -1,6 +1,11 @@
-use crate::errors::Error;
+use crate::errors::{Error, ErrorKind};
+use crate::time;
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::terminal;
+use inquire::validator::Validation;
 use inquire::{Confirm, CustomType, DateSelect, MultiSelect, Select, Text};
 use std::fmt::Display;
+use std::io::{IsTerminal, Read};
 use terminal_size::{Height, Width, terminal_size};
 
 // These constants are used throughout the app
@@ -14,17 +19,24 @@ pub const PATH: &str = "Set path";
 pub const DATE: &str = "Set a due date";
 pub const TIME: &str = "Set time, i.e. 3pm or 1500";
 pub const DATE_AND_TIME: &str = "Set a date and time in natural language";
-pub const DURATION: &str = "Set duration in minutes";
+pub const DURATION: &str = "Set duration, i.e. 45, 45 min or 1h30m";
 
 // Select
 pub const ATTRIBUTES: &str = "Select attributes";
 pub const PROJECT: &str = "Select a project";
+pub const PROJECTS: &str = "Select projects";
 pub const LABELS: &str = "Select labels";
 pub const SECTION: &str = "Select section";
+pub const ASSIGNEE: &str = "Select assignee";
 pub const PRIORITY: &str = "Select priority";
+pub const PRIORITY_KEYS: &str = "Press 1-4 to set P1-P4, or any other key for the menu";
 pub const OPTION: &str = "Select an option";
 pub const SELECT_DATE: &str = "Select a date";
 pub const TASK: &str = "Select a task";
+pub const TIMEZONE: &str = "Select a timezone";
+
+// Help
+const FUZZY_HELP: &str = "type to filter, arrows to move, enter to select";
 
 // Options
 pub const NAT_LANG: &str = "Natural Language";
@@ -38,6 +50,14 @@ pub const DELETE: &str = "Delete";
 pub const CANCEL: &str = "Cancel";
 pub const QUIT: &str = "Quit";
 pub const SCHEDULE: &str = "Schedule";
+pub const MERGE: &str = "Merge";
+pub const EMPTY: &str = "Empty";
+pub const RENAME: &str = "Rename";
+pub const CLEAR: &str = "Clear";
+pub const RESUME: &str = "Resume";
+pub const START_FRESH: &str = "Start fresh";
+pub const CUSTOM: &str = "Custom";
+pub const UNASSIGNED: &str = "Unassigned";
 
 pub enum DateTimeInput {
     Skip,
@@ -84,7 +104,10 @@ pub fn datetime(
                     "none" | "n" => Ok(DateTimeInput::None),
                     "complete" | "c" => Ok(DateTimeInput::Complete),
                     "skip" | "s" => Ok(DateTimeInput::Skip),
-                    _ => Ok(DateTimeInput::Text(entry)),
+                    _ => {
+                        time::validate_due(&entry)?;
+                        Ok(DateTimeInput::Text(entry))
+                    }
                 }
             } else {
                 let entry = string(
@@ -94,7 +117,10 @@ pub fn datetime(
 
                 match entry.as_str() {
                     "none" | "n" => Ok(DateTimeInput::None),
-                    _ => Ok(DateTimeInput::Text(entry)),
+                    _ => {
+                        time::validate_due(&entry)?;
+                        Ok(DateTimeInput::Text(entry))
+                    }
                 }
             }
         }
@@ -106,14 +132,12 @@ pub fn datetime(
         NO_DATE => Ok(DateTimeInput::None),
         "Complete" => Ok(DateTimeInput::Complete),
         SKIP => Ok(DateTimeInput::Skip),
-        _ => Err(Error {
-            message: "Unrecognized input".into(),
-            source: "Datetime Input".into(),
-        }),
+        _ => Err(Error::new("Datetime Input", "Unrecognized input")),
     }
 }
 
 pub fn date() -> Result<String, Error> {
+    ensure_interactive(DATE)?;
     let string = DateSelect::new("Select Date")
         .with_help_message(
             "arrows to move, []{} move months and years, enter to select, esc to cancel",
@@ -134,12 +158,86 @@ pub fn string(desc: &str, mock_string: Option<String>) -> Result<String, Error>
             panic!("Must set mock_string in config")
         }
     } else {
+        ensure_interactive(desc)?;
         Text::new(desc).prompt().map_err(Error::from)
     }
 }
 
+/// Read a single keypress without waiting for Enter, None when the key is not a character
+pub fn key(desc: &str, mock_string: Option<String>) -> Result<Option<char>, Error> {
+    if cfg!(test) {
+        return Ok(mock_string.and_then(|string| string.chars().next()));
+    }
+
+    ensure_interactive(desc)?;
+    println!("{desc}");
+    terminal::enable_raw_mode()?;
+    let pressed = read_key();
+    terminal::disable_raw_mode()?;
+
+    match pressed? {
+        KeyCode::Char(c) => Ok(Some(c)),
+        _ => Ok(None),
+    }
+}
+
+fn read_key() -> Result<KeyCode, Error> {
+    loop {
+        if let Event::Key(key_event) = event::read()?
+            && key_event.kind == KeyEventKind::Press
+        {
+            if key_event.modifiers.contains(KeyModifiers::CONTROL)
+                && key_event.code == KeyCode::Char('c')
+            {
+                return Err(Error::new("input", "Operation was interrupted")
+                    .with_kind(ErrorKind::UserCancelled));
+            }
+            return Ok(key_event.code);
+        }
+    }
+}
+
+/// Get a duration in minutes from user, re-prompting until the input can be parsed
+pub fn duration(desc: &str, mock_string: Option<String>) -> Result<u32, Error> {
+    if cfg!(test) {
+        let string = string(desc, mock_string)?;
+        return time::duration_to_minutes(&string);
+    }
+
+    ensure_interactive(desc)?;
+    let string = Text::new(desc)
+        .with_validator(|input: &str| match time::duration_to_minutes(input) {
+            Ok(_) => Ok(Validation::Valid),
+            Err(e) => Ok(Validation::Invalid(e.message.into())),
+        })
+        .prompt()
+        .map_err(Error::from)?;
+
+    time::duration_to_minutes(&string)
+}
+
+/// Get a duration in minutes from user with the default filled in, so Enter accepts it
+pub fn duration_with_default(desc: &str, default_minutes: u32) -> Result<u32, Error> {
+    if cfg!(test) {
+        return Ok(default_minutes);
+    }
+
+    ensure_interactive(desc)?;
+    let string = Text::new(desc)
+        .with_initial_value(&default_minutes.to_string())
+        .with_validator(|input: &str| match time::duration_to_minutes(input) {
+            Ok(_) => Ok(Validation::Valid),
+            Err(e) => Ok(Validation::Invalid(e.message.into())),
+        })
+        .prompt()
+        .map_err(Error::from)?;
+
+    time::duration_to_minutes(&string)
+}
+
 /// Get confirmation from user
 pub fn confirm(desc: &str) -> Result<bool, Error> {
+    ensure_interactive(desc)?;
     Confirm::new(desc)
         .with_default(false)
         .prompt()
@@ -152,6 +250,7 @@ pub fn string_with_default(desc: &str, default_message: &str) -> Result<String,
         return Ok(default_message.into());
     }
 
+    ensure_interactive(desc)?;
     Text::new(desc)
         .with_initial_value(default_message)
         .prompt()
@@ -163,6 +262,7 @@ pub fn number_with_default(desc: &str, default_message: usize) -> Result<usize,
     if cfg!(test) {
         return Ok(default_message);
     }
+    ensure_interactive(desc)?;
     CustomType::<usize>::new(desc)
         .with_error_message("Please type a valid number")
         .with_starting_input(&default_message.to_string())
@@ -202,6 +302,7 @@ pub fn select_with_cursor_index<T: Display>(
             panic!("Must set mock_select in config")
         }
     } else {
+        ensure_interactive(desc)?;
         Select::new(desc, options)
             .with_page_size(page_size() / 2) //Fixing bug with page size
             .with_starting_cursor(cursor_index)
@@ -210,6 +311,48 @@ pub fn select_with_cursor_index<T: Display>(
     }
 }
 
+/// Select an input from a potentially long list, typing narrows the options when `fuzzy` is enabled
+pub fn fuzzy_select<T: Display>(
+    desc: &str,
+    options: Vec<T>,
+    fuzzy: bool,
+    mock_select: Option<usize>,
+) -> Result<T, Error> {
+    if cfg!(test) {
+        return select(desc, options, mock_select);
+    }
+
+    ensure_interactive(desc)?;
+    let prompt = Select::new(desc, options).with_page_size(page_size() / 2); //Fixing bug with page size
+    let result = if fuzzy {
+        prompt.with_help_message(FUZZY_HELP).prompt()
+    } else {
+        prompt.without_filtering().prompt()
+    };
+    result.map_err(Error::from)
+}
+
+/// Select inputs from a potentially long list, typing narrows the options when `fuzzy` is enabled
+pub fn fuzzy_multi_select<T: Display>(
+    desc: &str,
+    options: Vec<T>,
+    fuzzy: bool,
+    mock_select: Option<usize>,
+) -> Result<Vec<T>, Error> {
+    if cfg!(test) {
+        return multi_select(desc, options, mock_select);
+    }
+
+    ensure_interactive(desc)?;
+    let prompt = MultiSelect::new(desc, options).with_page_size(page_size() / 2); //Fixing bug with page size
+    let result = if fuzzy {
+        prompt.with_help_message(FUZZY_HELP).prompt()
+    } else {
+        prompt.without_filtering().prompt()
+    };
+    result.map_err(Error::from)
+}
+
 /// Select an input from a list
 pub fn multi_select<T: Display>(
     desc: &str,
@@ -227,6 +370,7 @@ pub fn multi_select<T: Display>(
             panic!("Must set mock_select in config")
         }
     } else {
+        ensure_interactive(desc)?;
         MultiSelect::new(desc, options)
             .with_page_size(page_size() / 2) //Fixing bug with page size
             .prompt()
@@ -234,6 +378,50 @@ pub fn multi_select<T: Display>(
     }
 }
 
+/// Prompts need a terminal to read from, without one (i.e. in cron) they would hang or fail with an unclear error
+fn ensure_interactive(desc: &str) -> Result<(), Error> {
+    if std::io::stdin().is_terminal() {
+        Ok(())
+    } else {
+        Err(non_interactive_error(desc))
+    }
+}
+
+fn non_interactive_error(desc: &str) -> Error {
+    let hint = match desc {
+        CONTENT => "pass --content instead",
+        DESCRIPTION => "pass --description instead",
+        NAME => "pass --name instead",
+        FILTER => "pass --filter instead",
+        PATH => "pass --path instead",
+        DATE | SELECT_DATE => "pass --due instead",
+        PROJECT | PROJECTS => "pass --project instead",
+        LABELS => "pass --label instead",
+        SECTION => "pass --section or --no-section instead",
+        ASSIGNEE => "pass --assignee instead",
+        PRIORITY | PRIORITY_KEYS => "pass --priority instead",
+        ATTRIBUTES => "pass the values as flags instead, such as --content",
+        _ => "pass the value with a flag instead, see --help",
+    };
+    Error::new(
+        "input",
+        &format!("Can't ask '{desc}' without an interactive terminal, {hint}"),
+    )
+}
+
+/// Reads everything piped into stdin on a thread of its own, so that Ctrl-C can still interrupt tod while it waits.
+/// A blocking read would stall the runtime, and tokio's stdin holds up shutdown until the read returns
+pub async fn read_stdin() -> Result<Vec<u8>, Error> {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    std::thread::spawn(move || {
+        let mut bytes = Vec::new();
+        let read = std::io::stdin().read_to_end(&mut bytes).map(|_| bytes);
+        // Nothing is waiting for the result once tod has been interrupted
+        tx.send(read).ok();
+    });
+    Ok(rx.await??)
+}
+
 /// Gets the desired number of visible options for select menu and adjusts size
 pub fn page_size() -> usize {
     match terminal_size() {
@@ -249,6 +437,26 @@ mod tests {
     use super::*;
     use pretty_assertions::assert_eq;
 
+    #[test]
+    fn non_interactive_error_names_the_flag() {
+        let error = non_interactive_error(CONTENT);
+        assert_eq!(error.source, "input");
+        assert_eq!(
+            error.message,
+            "Can't ask 'Set content' without an interactive terminal, pass --content instead"
+        );
+        assert!(
+            non_interactive_error(SECTION)
+                .message
+                .ends_with("pass --section or --no-section instead")
+        );
+        assert!(
+            non_interactive_error("Anything else")
+                .message
+                .ends_with("see --help")
+        );
+    }
+
     #[test]
     fn can_select() {
         let result = select("type", vec!["there", "are", "words"], Some(0));
@@ -259,4 +467,34 @@ mod tests {
         let expected = Ok("are");
         assert_eq!(result, expected);
     }
+
+    #[test]
+    fn fuzzy_select_uses_mock_select() {
+        let result = fuzzy_select("type", vec!["there", "are", "words"], true, Some(2));
+        assert_eq!(result, Ok("words"));
+
+        let result = fuzzy_multi_select("type", vec!["there", "are", "words"], false, Some(1));
+        assert_eq!(result, Ok(vec!["are"]));
+    }
+
+    #[test]
+    fn duration_parses_mock_string() {
+        assert_eq!(duration(DURATION, Some("1h30m".to_string())), Ok(90));
+        assert!(duration(DURATION, Some("later".to_string())).is_err());
+    }
+
+    #[test]
+    fn datetime_validates_natural_language_entry() {
+        let result = datetime(None, Some("tomorrow".to_string()), Some(true), false, false);
+        assert!(matches!(result, Ok(DateTimeInput::Text(text)) if text == "tomorrow"));
+
+        let result = datetime(
+            None,
+            Some("2024-13-01".to_string()),
+            Some(true),
+            false,
+            true,
+        );
+        assert!(result.is_err_and(|error| error.source == "validate_due"));
+    }
 }
@@ -1,9 +1,13 @@
 use std::fmt::Display;
 
-use crate::{config::Config, errors::Error, todoist};
+use crate::{
+    config::Config,
+    errors::{Error, ErrorKind},
+    format, todoist,
+};
 use serde::Deserialize;
 
-#[derive(Deserialize, Debug, PartialEq, Eq)]
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct Label {
     pub id: String,
     pub name: String,
@@ -24,9 +28,22 @@ impl LabelResponse {
         Ok(response)
     }
 }
+impl Label {
+    /// A label known only by name, such as one passed with --label, which is shown without color
+    pub fn from_name(name: &str) -> Label {
+        Label {
+            id: String::new(),
+            name: name.to_string(),
+            color: String::new(),
+            order: None,
+            is_favorite: false,
+        }
+    }
+}
+
 impl Display for Label {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let name = self.name.clone();
+        let name = format::todoist_color_string(&self.name, &self.color);
         write!(f, "{name}")
     }
 }
@@ -34,6 +51,26 @@ pub async fn get_labels(config: &Config, spinner: bool) -> Result<Vec<Label>, Er
     todoist::all_labels(config, spinner, None).await
 }
 
+/// Finds a label by name, erroring with the available labels when there isn't one
+pub fn find_label(labels: &[Label], name: &str) -> Result<Label, Error> {
+    if let Some(label) = labels.iter().find(|label| label.name == name) {
+        return Ok(label.clone());
+    }
+    let available = labels
+        .iter()
+        .map(|label| label.name.as_str())
+        .collect::<Vec<&str>>();
+    let message = if available.is_empty() {
+        format!("Label '{name}' does not exist, there are no labels in Todoist")
+    } else {
+        format!(
+            "Label '{name}' does not exist, available labels are: {}",
+            available.join(", ")
+        )
+    };
+    Err(Error::new("find_label", &message).with_kind(ErrorKind::NotFound))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -51,6 +88,24 @@ mod tests {
         assert_eq!(label.to_string(), "work");
     }
 
+    #[test]
+    fn test_find_label_lists_available_labels() {
+        let labels = vec![Label::from_name("home"), Label::from_name("work")];
+        assert_eq!(find_label(&labels, "work"), Ok(Label::from_name("work")));
+
+        let error = find_label(&labels, "errands").expect_err("errands does not exist");
+        assert_eq!(error.kind, ErrorKind::NotFound);
+        assert!(error.message.ends_with("available labels are: home, work"));
+    }
+
+    #[test]
+    fn test_label_from_name_has_no_color() {
+        let label = Label::from_name("errands");
+        assert_eq!(label.name, "errands");
+        assert_eq!(label.color, "");
+        assert_eq!(label.to_string(), "errands");
+    }
+
     #[test]
     fn test_from_json_response_valid() {
         let json = r#"{"results":[{"id":"1","name":"work","color":"red","order":1,"is_favorite":false}],"next_cursor":null}"#;
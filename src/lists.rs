@@ -1,17 +1,30 @@
 use crate::{
     comments::Comment,
     config::Config,
+    debug::{self, LogLevel},
     errors::Error,
-    format,
+    format, id, input,
+    labels::Label,
+    process_state::ProcessState,
     projects::Project,
-    tasks::{self, FormatType, SortOrder, Task, priority::Priority},
-    todoist,
+    sections::{self, Section},
+    tasks::{
+        self, DueRange, FormatType, SortOptions, Task, TaskColumn, TimeboxDefaults,
+        priority::Priority,
+    },
+    time, todoist,
 };
+use chrono::NaiveDate;
 use futures::{StreamExt, TryStreamExt, future, stream};
-use std::collections::HashSet;
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Display;
 use tokio::{fs, io::AsyncReadExt, task::JoinError};
 
+const NON_RECURRING: &str = "non-recurring";
+const NO_SECTION: &str = "(No section)";
+const NO_LABEL: &str = "(No label)";
+
 #[derive(Clone)]
 pub enum Flag {
     Project(Project),
@@ -27,16 +40,119 @@ impl Display for Flag {
     }
 }
 
-/// Get a list of all tasks
-pub async fn view(config: &mut Config, flag: Flag, sort: &SortOrder) -> Result<String, Error> {
-    let list_of_tasks = match &flag {
+#[derive(clap::ValueEnum, Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum GroupBy {
+    /// A single list of tasks
+    #[default]
+    None,
+    /// Group tasks under their section
+    Section,
+    /// Group tasks by priority, highest first
+    Priority,
+    /// Group tasks under each of their labels
+    Label,
+}
+
+impl Display for GroupBy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GroupBy::None => write!(f, "none"),
+            GroupBy::Section => write!(f, "section"),
+            GroupBy::Priority => write!(f, "priority"),
+            GroupBy::Label => write!(f, "label"),
+        }
+    }
+}
+
+/// How `list view` prints each task
+#[derive(Debug, Clone, Default)]
+pub struct ViewOutput {
+    /// Parts of each task shown on one line and their order, from `--columns`. The whole task when None
+    pub columns: Option<Vec<TaskColumn>>,
+    /// Show how many days overdue tasks are, from `--show-age`
+    pub show_age: bool,
+    /// Print only the number of matching tasks, from `--count-only`
+    pub count_only: bool,
+}
+
+impl ViewOutput {
+    async fn format_task(&self, task: &Task, config: &Config) -> Result<String, Error> {
+        match &self.columns {
+            Some(columns) => task.fmt_columns(config, columns, self.show_age).await,
+            None => {
+                let format = FormatType::List {
+                    show_age: self.show_age,
+                };
+                task.fmt(Vec::new(), config, format, true).await
+            }
+        }
+    }
+}
+
+/// Completed tasks shown in `list view`, from `--include-completed`
+#[derive(Debug, Copy, Clone)]
+pub struct IncludeCompleted {
+    /// Tasks completed from the start of this date, from `--since`
+    pub since: NaiveDate,
+    /// Days of completed tasks fetched per request, from `--batch-size`. One request when None
+    pub batch_days: Option<u32>,
+}
+
+/// Lists tasks, along with the completed tasks when `completed` is given
+#[allow(clippy::too_many_arguments)]
+pub async fn view(
+    config: &Config,
+    flag: Flag,
+    sort: &SortOptions,
+    group_by: GroupBy,
+    min_priority: Option<Priority>,
+    due_range: DueRange,
+    recurring: Option<bool>,
+    completed: Option<IncludeCompleted>,
+    content: Option<&Regex>,
+    output: &ViewOutput,
+) -> Result<String, Error> {
+    let mut list_of_tasks = match &flag {
         Flag::Project(project) => vec![(
             project.name.clone(),
             todoist::all_tasks_by_project(config, project, None).await?,
         )],
         Flag::Filter(filter) => todoist::all_tasks_by_filters(config, filter).await?,
     };
+    let sections = match (&flag, group_by) {
+        (Flag::Project(project), GroupBy::Section) => {
+            todoist::all_sections_by_project(config, project, None).await?
+        }
+        (Flag::Filter(_), GroupBy::Section) => sections::all_sections(config).await?,
+        (_, _) => Vec::new(),
+    };
 
+    let snapshot = list_of_tasks
+        .iter()
+        .flat_map(|(_, tasks)| tasks.iter().map(|task| task.id.clone()))
+        .collect::<Vec<String>>();
+    if let Some(IncludeCompleted { since, batch_days }) = completed {
+        let since = time::start_of_day(since, config)?;
+        let until = time::datetime_now(config)?;
+        let completed = list_of_tasks.iter().map(|(query, _)| {
+            let (project, filter) = match &flag {
+                Flag::Project(project) => (Some(project), None),
+                Flag::Filter(_) => (None, Some(query.as_str())),
+            };
+            todoist::all_completed_tasks(config, project, filter, since, until, batch_days)
+        });
+        let completed = stream::iter(completed)
+            .buffered(config.max_concurrency())
+            .try_collect::<Vec<_>>()
+            .await?;
+        for ((_, tasks), completed) in list_of_tasks.iter_mut().zip(completed) {
+            tasks.extend(completed);
+        }
+    }
+    // Numbers are given in the order tasks are shown, a task shown twice keeps its first number
+    let mut numbered_ids: Vec<String> = Vec::new();
+    // A task in more than one filter is only counted once
+    let mut counted_ids: HashSet<String> = HashSet::new();
     let mut buffer = String::new();
 
     for (query, tasks) in list_of_tasks {
@@ -44,16 +160,242 @@ pub async fn view(config: &mut Config, flag: Flag, sort: &SortOrder) -> Result<S
         buffer.push('\n');
         buffer.push_str(&format::green_string(&title));
         buffer.push('\n');
-        for task in tasks::sort(tasks, config, *sort) {
-            let comments = Vec::new();
-            let text = task.fmt(comments, config, FormatType::List, true).await?;
-            buffer.push('\n');
-            buffer.push_str(&text);
+        let tasks = tasks::filter_min_priority(tasks, min_priority);
+        let tasks = tasks::filter_due_range(tasks, config, due_range);
+        let tasks = tasks::filter_recurring(tasks, recurring);
+        let tasks = tasks::filter_content(tasks, content);
+        if output.count_only {
+            counted_ids.extend(tasks.into_iter().map(|task| task.id));
+            continue;
         }
+        for (heading, tasks) in group_tasks(tasks, group_by, &sections) {
+            if let Some(heading) = heading {
+                buffer.push_str("\n\n");
+                buffer.push_str(&heading);
+            }
+            for task in tasks::sort(tasks, config, *sort) {
+                let text = output.format_task(&task, config).await?;
+                buffer.push('\n');
+                // Completed tasks can't be completed or edited again, so they are not numbered
+                if task.checked {
+                    buffer.push_str(&text);
+                    continue;
+                }
+                let number = match numbered_ids
+                    .iter()
+                    .position(|numbered| numbered == &task.id)
+                {
+                    Some(position) => position + 1,
+                    None => {
+                        numbered_ids.push(task.id.clone());
+                        numbered_ids.len()
+                    }
+                };
+                buffer.push_str(&format::cyan_string(&number.to_string()));
+                buffer.push(' ');
+                buffer.push_str(&text);
+            }
+        }
+    }
+    if output.count_only {
+        return Ok(counted_ids.len().to_string());
+    }
+    // The list is still worth showing when its numbers can't be saved, they just can't be used afterwards
+    if let Err(e) = id::save(config, &flag, numbered_ids, snapshot).await {
+        debug::maybe_print(config, &format!("Could not save task numbers: {e}"));
     }
     Ok(buffer)
 }
 
+/// Splits tasks into headed groups, empty groups are left out
+fn group_tasks(
+    tasks: Vec<Task>,
+    group_by: GroupBy,
+    sections: &[Section],
+) -> Vec<(Option<String>, Vec<Task>)> {
+    let groups: Vec<(String, Vec<Task>)> = match group_by {
+        GroupBy::None => return vec![(None, tasks)],
+        GroupBy::Section => {
+            let mut sections = sections.iter().collect::<Vec<&Section>>();
+            sections.sort_by_key(|section| section.section_order);
+
+            let mut groups = sections
+                .iter()
+                .map(|section| {
+                    let section_tasks = tasks
+                        .iter()
+                        .filter(|task| task.section_id.as_ref() == Some(&section.id))
+                        .cloned()
+                        .collect::<Vec<Task>>();
+                    (section.name.clone(), section_tasks)
+                })
+                .collect::<Vec<(String, Vec<Task>)>>();
+            let no_section = tasks
+                .into_iter()
+                .filter(|task| {
+                    !task
+                        .section_id
+                        .as_ref()
+                        .is_some_and(|id| sections.iter().any(|section| &section.id == id))
+                })
+                .collect::<Vec<Task>>();
+            groups.push((NO_SECTION.to_string(), no_section));
+            groups
+        }
+        GroupBy::Priority => {
+            let mut priorities = tasks::priority::all_priorities();
+            priorities.reverse();
+            priorities
+                .into_iter()
+                .map(|priority| {
+                    let priority_tasks = tasks
+                        .iter()
+                        .filter(|task| task.priority == priority)
+                        .cloned()
+                        .collect::<Vec<Task>>();
+                    (priority.to_string(), priority_tasks)
+                })
+                .collect()
+        }
+        GroupBy::Label => {
+            let mut labels = tasks
+                .iter()
+                .flat_map(|task| task.labels.clone())
+                .collect::<Vec<String>>();
+            labels.sort();
+            labels.dedup();
+
+            let mut groups = labels
+                .into_iter()
+                .map(|label| {
+                    let label_tasks = tasks
+                        .iter()
+                        .filter(|task| task.labels.contains(&label))
+                        .cloned()
+                        .collect::<Vec<Task>>();
+                    (label, label_tasks)
+                })
+                .collect::<Vec<(String, Vec<Task>)>>();
+            let no_label = tasks
+                .into_iter()
+                .filter(|task| task.labels.is_empty())
+                .collect::<Vec<Task>>();
+            groups.push((NO_LABEL.to_string(), no_label));
+            groups
+        }
+    };
+
+    groups
+        .into_iter()
+        .filter(|(_, tasks)| !tasks.is_empty())
+        .map(|(heading, tasks)| (Some(heading), tasks))
+        .collect()
+}
+
+/// Get a merged list of tasks from several projects, with each task prefixed by its project name
+#[allow(clippy::too_many_arguments)]
+pub async fn view_projects(
+    config: &Config,
+    projects: &[Project],
+    sort: &SortOptions,
+    min_priority: Option<Priority>,
+    due_range: DueRange,
+    recurring: Option<bool>,
+    content: Option<&Regex>,
+    output: &ViewOutput,
+) -> Result<String, Error> {
+    let project_tasks = stream::iter(projects)
+        .map(|project| async move {
+            let tasks = todoist::all_tasks_by_project(config, project, None).await?;
+            Ok::<(&str, Vec<Task>), Error>((project.name.as_str(), tasks))
+        })
+        .buffered(config.max_concurrency())
+        .try_collect::<Vec<_>>()
+        .await?;
+
+    let mut project_names: HashMap<String, &str> = HashMap::new();
+    let mut merged_tasks = Vec::new();
+    for (project_name, tasks) in project_tasks {
+        for task in tasks {
+            if !project_names.contains_key(&task.id) {
+                project_names.insert(task.id.clone(), project_name);
+                merged_tasks.push(task);
+            }
+        }
+    }
+
+    let query = projects
+        .iter()
+        .map(|project| project.name.as_str())
+        .collect::<Vec<&str>>()
+        .join(", ");
+    let mut buffer = String::new();
+    buffer.push('\n');
+    buffer.push_str(&format::green_string(&format!("Tasks for {query}")));
+    buffer.push('\n');
+    let merged_tasks = tasks::filter_min_priority(merged_tasks, min_priority);
+    let merged_tasks = tasks::filter_due_range(merged_tasks, config, due_range);
+    let merged_tasks = tasks::filter_recurring(merged_tasks, recurring);
+    let merged_tasks = tasks::filter_content(merged_tasks, content);
+    if output.count_only {
+        return Ok(merged_tasks.len().to_string());
+    }
+    for task in tasks::sort(merged_tasks, config, *sort) {
+        let text = output.format_task(&task, config).await?;
+        let project_name = project_names.get(&task.id).copied().unwrap_or_default();
+        buffer.push_str("\n[");
+        buffer.push_str(project_name);
+        buffer.push_str("] ");
+        buffer.push_str(&text);
+    }
+    Ok(buffer)
+}
+
+/// Get a count of tasks grouped by their recurrence pattern
+pub async fn recurrence_summary(config: &Config, flag: Flag) -> Result<String, Error> {
+    let list_of_tasks = match &flag {
+        Flag::Project(project) => vec![(
+            project.name.clone(),
+            todoist::all_tasks_by_project(config, project, None).await?,
+        )],
+        Flag::Filter(filter) => todoist::all_tasks_by_filters(config, filter).await?,
+    };
+
+    let mut buffer = String::new();
+
+    for (query, tasks) in list_of_tasks {
+        let title = format!("Recurring tasks for {query}");
+        buffer.push('\n');
+        buffer.push_str(&format::green_string(&title));
+        buffer.push_str("\n\n");
+        buffer.push_str(&summarize_recurrence(&tasks));
+        buffer.push('\n');
+    }
+    Ok(buffer)
+}
+
+/// Formats counts of tasks per recurrence pattern, most common first
+fn summarize_recurrence(tasks: &[Task]) -> String {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for task in tasks {
+        let pattern = task
+            .recurrence()
+            .map_or_else(|| NON_RECURRING.to_string(), |r| r.trim().to_lowercase());
+        *counts.entry(pattern).or_default() += 1;
+    }
+
+    let mut counts = counts.into_iter().collect::<Vec<(String, usize)>>();
+    counts.sort_by(|(a_name, a_count), (b_name, b_count)| {
+        b_count.cmp(a_count).then_with(|| a_name.cmp(b_name))
+    });
+
+    counts
+        .into_iter()
+        .map(|(pattern, count)| format!("{pattern}: {count}"))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
 pub async fn fetch_tasks_by_flag<F, P>(
     config: &Config,
     flag: &Flag,
@@ -82,10 +424,20 @@ where
 }
 
 /// Prioritize all unprioritized tasks
-pub async fn prioritize(config: &Config, flag: Flag, sort: &SortOrder) -> Result<String, Error> {
+pub async fn prioritize(
+    config: &Config,
+    flag: Flag,
+    sort: &SortOptions,
+    min_priority: Option<Priority>,
+    count_only: bool,
+) -> Result<String, Error> {
     let project_filter = |task: &Task| task.priority == Priority::None;
     let filter_filter = |_task: &Task| true;
     let tasks = fetch_tasks_by_flag(config, &flag, project_filter, filter_filter).await?;
+    let tasks = tasks::filter_min_priority(tasks, min_priority);
+    if count_only {
+        return Ok(tasks.len().to_string());
+    }
 
     let empty_text = format!("No tasks for {flag}");
     let success = format!("Successfully prioritized {flag}");
@@ -95,11 +447,13 @@ pub async fn prioritize(config: &Config, flag: Flag, sort: &SortOrder) -> Result
     }
 
     let tasks = tasks::sort(tasks, config, *sort);
+    let total = tasks.len();
 
-    let handles = stream::iter(tasks)
-        .then(|task| async {
+    let handles = stream::iter(tasks.into_iter().enumerate())
+        .then(|(index, task)| async move {
             println!();
-            tasks::set_priority(config, task, true).await
+            tasks::print_progress(config, index + 1, total, "prioritizing", &task);
+            tasks::set_priority(config, task, true, true).await
         })
         .try_collect::<Vec<_>>()
         .await?;
@@ -108,7 +462,7 @@ pub async fn prioritize(config: &Config, flag: Flag, sort: &SortOrder) -> Result
 }
 
 /// Add reminders to all tasks that do not have them
-pub async fn remind(config: &Config, flag: Flag, sort: &SortOrder) -> Result<String, Error> {
+pub async fn remind(config: &Config, flag: Flag, sort: &SortOptions) -> Result<String, Error> {
     let reminder_task_ids = todoist::all_reminders(config, None)
         .await?
         .into_iter()
@@ -124,10 +478,12 @@ pub async fn remind(config: &Config, flag: Flag, sort: &SortOrder) -> Result<Str
     }
 
     let tasks = tasks::sort(tasks, config, *sort);
+    let total = tasks.len();
 
-    let handles = stream::iter(tasks)
-        .then(|task| async {
+    let handles = stream::iter(tasks.into_iter().enumerate())
+        .then(|(index, task)| async move {
             println!();
+            tasks::print_progress(config, index + 1, total, "reminding", &task);
             tasks::create_reminder(config, task).await
         })
         .try_collect::<Vec<_>>()
@@ -141,7 +497,12 @@ pub async fn remind(config: &Config, flag: Flag, sort: &SortOrder) -> Result<Str
 }
 
 /// Gives tasks durations
-pub async fn timebox(config: &Config, flag: Flag, sort: &SortOrder) -> Result<String, Error> {
+pub async fn timebox(
+    config: &Config,
+    flag: Flag,
+    sort: &SortOptions,
+    defaults: TimeboxDefaults,
+) -> Result<String, Error> {
     let project_filter = |task: &Task| task.duration.is_none();
     let filter_filter = |_task: &Task| true;
     let tasks = fetch_tasks_by_flag(config, &flag, project_filter, filter_filter).await?;
@@ -158,7 +519,15 @@ pub async fn timebox(config: &Config, flag: Flag, sort: &SortOrder) -> Result<St
     let mut handles = Vec::new();
     for task in tasks {
         println!();
-        match tasks::timebox_task(&config.reload().await?, task, &mut task_count, false).await? {
+        match tasks::timebox_task(
+            &config.reload().await?,
+            task,
+            &mut task_count,
+            false,
+            defaults,
+        )
+        .await?
+        {
             Some(handle) => handles.push(handle),
             None => return Ok(format::green_string("Exited")),
         }
@@ -167,8 +536,114 @@ pub async fn timebox(config: &Config, flag: Flag, sort: &SortOrder) -> Result<St
     Ok(format::green_string(&success))
 }
 
+/// Remove the deadlines from the given tasks, stopping at the first failure when `strict` is set
+pub async fn clear_deadlines(
+    config: &Config,
+    flag: &Flag,
+    tasks: Vec<Task>,
+    strict: bool,
+) -> Result<String, Error> {
+    if tasks.is_empty() {
+        let empty_text = format!("No tasks with deadlines for {flag}");
+        return Ok(format::green_string(&empty_text));
+    }
+
+    let failures = update_each(
+        config,
+        &tasks,
+        "clearing deadline",
+        strict,
+        |task| async move {
+            todoist::clear_task_deadline(config, &task.id, false)
+                .await
+                .map(|_| ())
+        },
+    )
+    .await?;
+    let cleared = tasks.len() - failures.len();
+    let success = format!("Cleared deadlines from {cleared} task(s) in {flag}");
+    bulk_result("list_deadline", success, "clear", &failures)
+}
+
+/// Sets the due date of every task to the same date, without prompting for each one.
+/// Stops at the first failure when `strict` is set
+pub async fn schedule_all_to(
+    config: &Config,
+    flag: &Flag,
+    tasks: Vec<Task>,
+    date: &str,
+    strict: bool,
+) -> Result<String, Error> {
+    if tasks.is_empty() {
+        let empty_text = format!("No tasks to schedule in {flag}");
+        return Ok(format::green_string(&empty_text));
+    }
+
+    let failures = update_each(config, &tasks, "scheduling", strict, |task| async move {
+        todoist::update_task_due_natural_language(config, task, date.to_string(), None, false)
+            .await
+            .map(|_| ())
+    })
+    .await?;
+    let scheduled = tasks.len() - failures.len();
+    let success = format!("Scheduled {scheduled} task(s) in {flag} for {date}");
+    bulk_result("list_schedule", success, "schedule", &failures)
+}
+
+/// Runs `update` on each task one after another and returns the failures once every task is done.
+/// With `strict` the first failure stops the command instead
+async fn update_each<'a, F, Fut>(
+    config: &Config,
+    tasks: &'a [Task],
+    action: &str,
+    strict: bool,
+    update: F,
+) -> Result<Vec<String>, Error>
+where
+    F: Fn(&'a Task) -> Fut,
+    Fut: Future<Output = Result<(), Error>>,
+{
+    let total = tasks.len();
+    let mut failures = Vec::new();
+    for (index, task) in tasks.iter().enumerate() {
+        tasks::print_progress(config, index + 1, total, action, task);
+        if let Err(e) = update(task).await {
+            if strict {
+                return Err(tasks::stopped_by_strict(task, &e));
+            }
+            failures.push(format!("'{}': {}", task.content, e.message));
+        }
+    }
+    Ok(failures)
+}
+
+/// The success message, or an error that lists the tasks `update_each` failed on
+fn bulk_result(
+    source: &str,
+    success: String,
+    action: &str,
+    failures: &[String],
+) -> Result<String, Error> {
+    if failures.is_empty() {
+        return Ok(format::green_string(&success));
+    }
+    let mut message = success;
+    message.push_str(&format!("\nFailed to {action} {} task(s):", failures.len()));
+    for failure in failures {
+        message.push_str("\n - ");
+        message.push_str(failure);
+    }
+    Err(Error::new(source, &message))
+}
+
 /// Get next tasks and give an interactive prompt for completing them one by one
-pub async fn process(config: &Config, flag: Flag, sort: &SortOrder) -> Result<String, Error> {
+pub async fn process(
+    config: &Config,
+    flag: Flag,
+    sort: &SortOptions,
+    min_priority: Option<Priority>,
+    count_only: bool,
+) -> Result<String, Error> {
     let project_filter = |task: &Task| {
         task.is_today(config).unwrap_or_default()
             || task.has_no_date()
@@ -182,6 +657,10 @@ pub async fn process(config: &Config, flag: Flag, sort: &SortOrder) -> Result<St
         Flag::Filter(..) => true,
     };
     let tasks = tasks::reject_parent_tasks(tasks, config).await;
+    let tasks = tasks::filter_min_priority(tasks, min_priority);
+    if count_only {
+        return Ok(tasks.len().to_string());
+    }
 
     let empty_text = format!("No tasks for {flag}");
     let success = format!("Successfully processed {flag}");
@@ -191,22 +670,59 @@ pub async fn process(config: &Config, flag: Flag, sort: &SortOrder) -> Result<St
     }
 
     let tasks = tasks::sort(tasks, config, *sort);
+    let mut state = ProcessState::load(config, &flag).await;
+    let tasks = resume_or_start_fresh(tasks, &mut state, config).await?;
     let mut task_count = i32::try_from(tasks.len())?;
     let tasks_with_comments = fetch_comments_for_tasks(tasks, config).await;
     let mut handles = Vec::new();
     for task_with_comments in tasks_with_comments {
+        let task_id = task_with_comments
+            .as_ref()
+            .ok()
+            .map(|(task, _)| task.id.clone());
         match process_task_with_comments(task_with_comments, config, &mut task_count, with_project)
             .await?
         {
-            ProcessTaskOutcome::Handle(handle) => handles.push(handle),
+            ProcessTaskOutcome::Handle(handle) => {
+                handles.push(handle);
+                if let Some(task_id) = task_id {
+                    state.record(&task_id).await?;
+                }
+            }
             ProcessTaskOutcome::Exit => return Ok(format::green_string("Exited")),
             ProcessTaskOutcome::Skip => {}
         }
     }
     future::join_all(handles).await;
+    state.clear().await?;
     Ok(format::green_string(&success))
 }
 
+/// When an earlier session was interrupted, offers to skip the tasks it already handled
+async fn resume_or_start_fresh(
+    tasks: Vec<Task>,
+    state: &mut ProcessState,
+    config: &Config,
+) -> Result<Vec<Task>, Error> {
+    let processed = tasks.iter().filter(|task| state.contains(&task.id)).count();
+    if processed == 0 {
+        state.clear().await?;
+        return Ok(tasks);
+    }
+
+    let desc = format!("Already processed {processed} of these tasks in an earlier session");
+    let options = vec![input::RESUME, input::START_FRESH];
+    if input::select(&desc, options, config.mock_select)? == input::RESUME {
+        Ok(tasks
+            .into_iter()
+            .filter(|task| !state.contains(&task.id))
+            .collect())
+    } else {
+        state.clear().await?;
+        Ok(tasks)
+    }
+}
+
 enum ProcessTaskOutcome {
     Handle(tokio::task::JoinHandle<()>),
     Exit,
@@ -221,12 +737,22 @@ async fn process_task_with_comments(
 ) -> Result<ProcessTaskOutcome, Error> {
     let (task, comments, with_project) = match task_with_comments {
         Ok((task, Ok(comments))) => (task, comments, with_project),
-        Ok((task, Err(Error { message, source }))) => {
-            println!("Could not fetch comments from {source}: {message}");
+        Ok((
+            task,
+            Err(Error {
+                message, source, ..
+            }),
+        )) => {
+            let text = format!("Could not fetch comments from {source}: {message}");
+            debug::log(config, LogLevel::Warn, &text);
             (task, Vec::new(), false)
         }
-        Err(JoinError { .. }) => {
-            println!("JoinError");
+        Err(e) => {
+            debug::log(
+                config,
+                LogLevel::Error,
+                &format!("Fetching comments failed: {e}"),
+            );
             return Ok(ProcessTaskOutcome::Skip);
         }
     };
@@ -250,25 +776,26 @@ async fn fetch_comments_for_tasks(
     tasks: Vec<Task>,
     config: &Config,
 ) -> Vec<Result<(Task, Result<Vec<Comment>, Error>), JoinError>> {
-    let handles = tasks
-        .into_iter()
+    stream::iter(tasks)
         .map(|task| {
             let config = config.clone();
+            // Only spawned once buffered pulls it in, so at most max_concurrency fetches run at a time
             tokio::spawn(async move {
                 let comments = todoist::all_comments(&config, &task.id, None).await;
                 (task, comments)
             })
         })
-        .collect::<Vec<_>>();
-    future::join_all(handles).await
+        .buffered(config.max_concurrency())
+        .collect()
+        .await
 }
 
 /// Puts labels on tasks
 pub async fn label(
     config: &Config,
     flag: Flag,
-    labels: &[String],
-    sort: &SortOrder,
+    labels: &[Label],
+    sort: &SortOptions,
 ) -> Result<String, Error> {
     let filter = |_task: &Task| true;
     let tasks = fetch_tasks_by_flag(config, &flag, filter, filter).await?;
@@ -281,9 +808,11 @@ pub async fn label(
     }
 
     let tasks = tasks::sort(tasks, config, *sort);
-    let handles = stream::iter(tasks)
-        .then(|task| async {
+    let total = tasks.len();
+    let handles = stream::iter(tasks.into_iter().enumerate())
+        .then(|(index, task)| async move {
             println!();
+            tasks::print_progress(config, index + 1, total, "labeling", &task);
             tasks::label_task(config, task, labels).await
         })
         .try_collect::<Vec<_>>()
@@ -292,6 +821,33 @@ pub async fn label(
     Ok(format::green_string(&success))
 }
 
+/// Adds one label to every task that does not already have it, without prompting.
+/// Stops at the first failure when `strict` is set
+pub async fn label_all(
+    config: &Config,
+    flag: Flag,
+    label: &str,
+    strict: bool,
+) -> Result<String, Error> {
+    let missing_label = |task: &Task| !task.labels.iter().any(|name| name == label);
+    let tasks = fetch_tasks_by_flag(config, &flag, missing_label, missing_label).await?;
+
+    if tasks.is_empty() {
+        let empty_text = format!("No tasks without '{label}' for {flag}");
+        return Ok(format::green_string(&empty_text));
+    }
+
+    let failures = update_each(config, &tasks, "labeling", strict, |task| async move {
+        todoist::add_task_label(config, task, label.to_string(), false)
+            .await
+            .map(|_| ())
+    })
+    .await?;
+    let labeled = tasks.len() - failures.len();
+    let success = format!("Added '{label}' to {labeled} task(s) in {flag}");
+    bulk_result("list_label", success, "label", &failures)
+}
+
 pub async fn import(config: &Config, file_path: &str) -> Result<String, Error> {
     let mut lines = String::new();
     fs::File::open(file_path)
@@ -305,7 +861,7 @@ pub async fn import(config: &Config, file_path: &str) -> Result<String, Error> {
         .filter(|s| !s.is_empty())
         .collect();
     for line in lines {
-        todoist::quick_create_task(config, &line, None).await?;
+        todoist::quick_create_task(config, &line, None, None).await?;
     }
 
     Ok("✓".into())
@@ -344,6 +900,110 @@ mod tests {
         mock.assert();
     }
 
+    #[tokio::test]
+    async fn test_group_tasks() {
+        let task = test::fixtures::today_task().await;
+        let in_section = Task {
+            id: "1".into(),
+            section_id: Some(test::fixtures::section().id),
+            priority: Priority::High,
+            labels: Vec::new(),
+            ..task.clone()
+        };
+        let without_section = Task {
+            id: "2".into(),
+            ..task.clone()
+        };
+        let tasks = vec![in_section.clone(), without_section.clone()];
+        let sections = vec![test::fixtures::section()];
+
+        let names = |groups: Vec<(Option<String>, Vec<Task>)>| {
+            groups
+                .into_iter()
+                .map(|(heading, tasks)| {
+                    let ids = tasks.into_iter().map(|t| t.id).collect::<Vec<String>>();
+                    (heading, ids)
+                })
+                .collect::<Vec<(Option<String>, Vec<String>)>>()
+        };
+
+        assert_eq!(
+            names(group_tasks(tasks.clone(), GroupBy::None, &sections)),
+            vec![(None, vec!["1".to_string(), "2".to_string()])]
+        );
+        assert_eq!(
+            names(group_tasks(tasks.clone(), GroupBy::Section, &sections)),
+            vec![
+                (Some("Bread".to_string()), vec!["1".to_string()]),
+                (Some(NO_SECTION.to_string()), vec!["2".to_string()]),
+            ]
+        );
+        assert_eq!(
+            names(group_tasks(tasks.clone(), GroupBy::Priority, &sections)),
+            vec![
+                (Some(Priority::High.to_string()), vec!["1".to_string()]),
+                (Some(Priority::Medium.to_string()), vec!["2".to_string()]),
+            ]
+        );
+        assert_eq!(
+            names(group_tasks(tasks, GroupBy::Label, &sections)),
+            vec![
+                (Some("computer".to_string()), vec!["2".to_string()]),
+                (Some(NO_LABEL.to_string()), vec!["1".to_string()]),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_summarize_recurrence() {
+        let task = test::fixtures::today_task().await;
+        let recurring = |id: &str, string: &str| Task {
+            id: id.into(),
+            due: Some(tasks::DateInfo {
+                date: "2024-01-01".into(),
+                is_recurring: true,
+                lang: "en".into(),
+                string: string.into(),
+                timezone: None,
+            }),
+            ..task.clone()
+        };
+        let tasks = vec![
+            recurring("1", "every monday"),
+            recurring("2", "Daily"),
+            recurring("3", "daily"),
+            recurring("4", "every weekday"),
+            task.clone(),
+        ];
+
+        assert_eq!(
+            summarize_recurrence(&tasks),
+            "daily: 2, every monday: 1, every weekday: 1, non-recurring: 1"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_recurrence_summary() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api/v1/tasks/filter?query=today&limit=200")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::TodayTasks.read().await)
+            .create_async()
+            .await;
+
+        let config = test::fixtures::config().await.with_mock_url(server.url());
+
+        let summary = recurrence_summary(&config, Flag::Filter("today".to_string()))
+            .await
+            .expect("summary should be created");
+
+        assert!(summary.contains("Recurring tasks for today"));
+        assert!(summary.contains("non-recurring: "));
+        mock.assert();
+    }
+
     #[tokio::test]
     async fn test_prioritize() {
         let mut server = mockito::Server::new_async().await;
@@ -368,12 +1028,156 @@ mod tests {
             .mock_select(1);
 
         let filter = String::from("today");
-        let sort = &SortOrder::Value;
-        let result = prioritize(&config, Flag::Filter(filter), sort).await;
+        let sort = &SortOptions::default();
+        let result = prioritize(&config, Flag::Filter(filter), sort, None, false).await;
         assert_eq!(result, Ok(String::from("Successfully prioritized 'today'")));
         mock.assert();
         mock2.assert();
     }
+
+    #[tokio::test]
+    async fn test_label_all() {
+        let mut server = mockito::Server::new_async().await;
+        let tasks_mock = server
+            .mock("GET", "/api/v1/tasks/filter?query=today&limit=200")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::TodayTasks.read().await)
+            .expect(2)
+            .create_async()
+            .await;
+        let label_mock = server
+            .mock("POST", "/api/v1/tasks/6Xqhv4cwxgjwG9w8")
+            .match_body(mockito::Matcher::PartialJson(
+                serde_json::json!({"labels": ["computer", "errands"]}),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::TodayTask.read().await)
+            .create_async()
+            .await;
+
+        let config = test::fixtures::config().await.with_mock_url(server.url());
+        let flag = Flag::Filter(String::from("today"));
+
+        let result = label_all(&config, flag.clone(), "errands", false).await;
+        assert_eq!(
+            result,
+            Ok(String::from("Added 'errands' to 1 task(s) in 'today'"))
+        );
+        label_mock.assert();
+
+        let result = label_all(&config, flag, "computer", false).await;
+        assert_eq!(
+            result,
+            Ok(String::from("No tasks without 'computer' for 'today'"))
+        );
+        tasks_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_clear_deadlines() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/api/v1/tasks/6Xqhv4cwxgjwG9w8")
+            .match_body(mockito::Matcher::PartialJson(
+                serde_json::json!({"deadline_date": null}),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::TodayTask.read().await)
+            .create_async()
+            .await;
+
+        let config = test::fixtures::config().await.with_mock_url(server.url());
+        let flag = Flag::Filter(String::from("today"));
+
+        let result = clear_deadlines(&config, &flag, Vec::new(), false).await;
+        assert_eq!(
+            result,
+            Ok(String::from("No tasks with deadlines for 'today'"))
+        );
+
+        let tasks = vec![test::fixtures::today_task().await];
+        let result = clear_deadlines(&config, &flag, tasks, false).await;
+        assert_eq!(
+            result,
+            Ok(String::from("Cleared deadlines from 1 task(s) in 'today'"))
+        );
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_schedule_all_to() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/api/v1/tasks/6Xqhv4cwxgjwG9w8")
+            .match_body(mockito::Matcher::PartialJson(
+                serde_json::json!({"due_string": "friday"}),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::TodayTask.read().await)
+            .create_async()
+            .await;
+
+        let config = test::fixtures::config().await.with_mock_url(server.url());
+        let flag = Flag::Filter(String::from("today"));
+
+        let result = schedule_all_to(&config, &flag, Vec::new(), "friday", false).await;
+        assert_eq!(result, Ok(String::from("No tasks to schedule in 'today'")));
+
+        let tasks = vec![test::fixtures::today_task().await];
+        let result = schedule_all_to(&config, &flag, tasks, "friday", false).await;
+        assert_eq!(
+            result,
+            Ok(String::from("Scheduled 1 task(s) in 'today' for friday"))
+        );
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn schedule_all_to_reports_failures_unless_strict() {
+        let mut server = mockito::Server::new_async().await;
+        let failed_mock = server
+            .mock("POST", "/api/v1/tasks/6Xqhv4cwxgjwG9w8")
+            .with_status(500)
+            .expect(2)
+            .create_async()
+            .await;
+        let second_mock = server
+            .mock("POST", "/api/v1/tasks/second")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::TodayTask.read().await)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let config = test::fixtures::config().await.with_mock_url(server.url());
+        let flag = Flag::Filter(String::from("today"));
+        let first = test::fixtures::today_task().await;
+        let second = Task {
+            id: "second".to_string(),
+            ..first.clone()
+        };
+        let tasks = vec![first, second];
+
+        let error = schedule_all_to(&config, &flag, tasks.clone(), "friday", false)
+            .await
+            .expect_err("the failed update should be reported");
+        assert_eq!(error.source, "list_schedule");
+        assert!(error.message.starts_with("Scheduled 1 task(s) in 'today'"));
+        assert!(error.message.contains("Failed to schedule 1 task(s):"));
+
+        let error = schedule_all_to(&config, &flag, tasks, "friday", true)
+            .await
+            .expect_err("the failed update should stop the command");
+        assert_eq!(error.source, "strict");
+        failed_mock.assert();
+        second_mock.assert();
+    }
+
     #[tokio::test]
     async fn test_timebox() {
         let mut server = mockito::Server::new_async().await;
@@ -421,8 +1225,14 @@ mod tests {
             .first()
             .expect("Expected at least one project in binding")
             .to_owned();
-        let sort = &SortOrder::Value;
-        let result = timebox(&config, Flag::Project(project), sort).await;
+        let sort = &SortOptions::default();
+        let result = timebox(
+            &config,
+            Flag::Project(project),
+            sort,
+            TimeboxDefaults::default(),
+        )
+        .await;
         assert_matches!(result, Ok(x) if x.contains("Successfully timeboxed"));
 
         let config = config.mock_select(2);
@@ -435,7 +1245,13 @@ mod tests {
             .first()
             .expect("Expected at least one project in binding")
             .to_owned();
-        let result = timebox(&config, Flag::Project(project), sort).await;
+        let result = timebox(
+            &config,
+            Flag::Project(project),
+            sort,
+            TimeboxDefaults::default(),
+        )
+        .await;
         assert_matches!(result, Ok(x) if x.contains("Successfully timeboxed"));
 
         let config = config.mock_select(3);
@@ -448,16 +1264,68 @@ mod tests {
             .first()
             .expect("Expected at least one project in binding")
             .to_owned();
-        let result = timebox(&config, Flag::Project(project.clone()), sort).await;
+        let result = timebox(
+            &config,
+            Flag::Project(project.clone()),
+            sort,
+            TimeboxDefaults::default(),
+        )
+        .await;
         assert_matches!(result, Ok(x) if x.contains("Successfully timeboxed"));
 
-        let result = timebox(&config, Flag::Project(project), sort).await;
+        let result = timebox(
+            &config,
+            Flag::Project(project),
+            sort,
+            TimeboxDefaults::default(),
+        )
+        .await;
         assert_matches!(result, Ok(x) if x.contains("Successfully timeboxed"));
         mock.expect(2);
         mock2.expect(2);
         mock4.expect(1);
     }
 
+    #[tokio::test]
+    async fn test_timebox_with_defaults_only_sets_duration() {
+        let mut server = mockito::Server::new_async().await;
+        let tasks_mock = server
+            .mock("GET", "/api/v1/tasks/filter?query=today&limit=200")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::TodayTasksWithoutDuration.read().await)
+            .create_async()
+            .await;
+        let duration_mock = server
+            .mock("POST", "/api/v1/tasks/6Xqhv4cwxgjwG9w8")
+            .match_body(mockito::Matcher::Json(
+                serde_json::json!({"duration": 30, "duration_unit": "minute"}),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::TodayTask.read().await)
+            .create_async()
+            .await;
+        let config = test::fixtures::config()
+            .await
+            .with_mock_url(server.url())
+            .mock_select(0)
+            .create()
+            .await
+            .expect("config should be created");
+
+        let defaults = TimeboxDefaults {
+            duration: Some(30),
+            skip_date: true,
+        };
+        let filter = Flag::Filter("today".to_string());
+        let result = timebox(&config, filter, &SortOptions::default(), defaults).await;
+
+        assert_eq!(result, Ok("Successfully timeboxed 'today'".to_string()));
+        tasks_mock.assert();
+        duration_mock.assert();
+    }
+
     #[tokio::test]
     async fn test_timebox_returns_exited_when_quit_is_selected() {
         let mut server = mockito::Server::new_async().await;
@@ -479,7 +1347,8 @@ mod tests {
         let result = timebox(
             &config,
             Flag::Filter("today".to_string()),
-            &SortOrder::Value,
+            &SortOptions::default(),
+            TimeboxDefaults::default(),
         )
         .await;
 
@@ -487,6 +1356,25 @@ mod tests {
         tasks_mock.assert();
     }
 
+    #[tokio::test]
+    async fn test_prioritize_count_only() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api/v1/tasks/filter?query=today&limit=200")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::TodayTasks.read().await)
+            .create_async()
+            .await;
+
+        let config = test::fixtures::config().await.with_mock_url(server.url());
+
+        let filter = Flag::Filter(String::from("today"));
+        let result = prioritize(&config, filter, &SortOptions::default(), None, true).await;
+        assert_eq!(result, Ok(String::from("1")));
+        mock.assert();
+    }
+
     #[tokio::test]
     async fn test_prioritize_tasks_with_no_tasks() {
         let mut server = mockito::Server::new_async().await;
@@ -508,9 +1396,9 @@ mod tests {
             .first()
             .expect("Expected at least one project in binding")
             .to_owned();
-        let sort = &SortOrder::Value;
+        let sort = &SortOptions::default();
 
-        let result = prioritize(&config, Flag::Project(project), sort).await;
+        let result = prioritize(&config, Flag::Project(project), sort, None, false).await;
         assert_eq!(
             result,
             Ok(String::from(
@@ -544,7 +1432,7 @@ mod tests {
             remind(
                 &config,
                 Flag::Filter("today".to_string()),
-                &SortOrder::Value,
+                &SortOptions::default(),
             )
             .await,
             Ok("No tasks for 'today'".to_string())
@@ -553,7 +1441,8 @@ mod tests {
             timebox(
                 &config,
                 Flag::Filter("today".to_string()),
-                &SortOrder::Value,
+                &SortOptions::default(),
+                TimeboxDefaults::default(),
             )
             .await,
             Ok("No tasks for 'today'".to_string())
@@ -562,7 +1451,9 @@ mod tests {
             process(
                 &config,
                 Flag::Filter("today".to_string()),
-                &SortOrder::Value,
+                &SortOptions::default(),
+                None,
+                false,
             )
             .await,
             Ok("No tasks for 'today'".to_string())
@@ -607,9 +1498,9 @@ mod tests {
             .await
             .expect("expected value or result, got None or Err");
         let filter = String::from("today");
-        let sort = &SortOrder::Value;
+        let sort = &SortOptions::default();
 
-        let result = process(&config, Flag::Filter(filter), sort).await;
+        let result = process(&config, Flag::Filter(filter), sort, None, false).await;
         assert_eq!(result, Ok("Successfully processed 'today'".to_string()));
         mock.assert();
         mock2.assert();
@@ -662,9 +1553,9 @@ mod tests {
             .first()
             .expect("Expected at least one project in binding")
             .to_owned();
-        let sort = &SortOrder::Value;
+        let sort = &SortOptions::default();
 
-        let result = process(&config, Flag::Project(project), sort).await;
+        let result = process(&config, Flag::Project(project), sort, None, false).await;
         assert_eq!(
             result,
             Ok(
@@ -677,6 +1568,44 @@ mod tests {
         mock3.assert();
     }
 
+    #[tokio::test]
+    async fn test_process_resumes_and_skips_processed_tasks() {
+        let mut server = mockito::Server::new_async().await;
+        let tasks_mock = server
+            .mock("GET", "/api/v1/tasks/filter?query=today&limit=200")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::TodayTasks.read().await)
+            .create_async()
+            .await;
+        let close_mock = server
+            .mock("POST", "/api/v1/tasks/6Xqhv4cwxgjwG9w8/close")
+            .expect(0)
+            .create_async()
+            .await;
+        let config = test::fixtures::config()
+            .await
+            .with_mock_url(server.url())
+            .mock_select(0)
+            .create()
+            .await
+            .expect("config should be created");
+        let flag = Flag::Filter("today".to_string());
+        let mut state = ProcessState::load(&config, &flag).await;
+        state
+            .record("6Xqhv4cwxgjwG9w8")
+            .await
+            .expect("recording should succeed");
+
+        let result = process(&config, flag.clone(), &SortOptions::default(), None, false).await;
+
+        assert_eq!(result, Ok("Successfully processed 'today'".to_string()));
+        tasks_mock.assert();
+        close_mock.assert();
+        let state = ProcessState::load(&config, &flag).await;
+        assert!(state.task_ids.is_empty());
+    }
+
     #[tokio::test]
     async fn test_process_returns_exited_when_quit_is_selected() {
         let mut server = mockito::Server::new_async().await;
@@ -708,7 +1637,9 @@ mod tests {
         let result = process(
             &config,
             Flag::Filter("today".to_string()),
-            &SortOrder::Value,
+            &SortOptions::default(),
+            None,
+            false,
         )
         .await;
 
@@ -749,7 +1680,9 @@ mod tests {
         let skipped = process(
             &config,
             Flag::Filter("today".to_string()),
-            &SortOrder::Value,
+            &SortOptions::default(),
+            None,
+            false,
         )
         .await;
         assert_eq!(skipped, Ok("Successfully processed 'today'".to_string()));
@@ -762,7 +1695,9 @@ mod tests {
         let exited = process(
             &quit_config,
             Flag::Filter("today".to_string()),
-            &SortOrder::Value,
+            &SortOptions::default(),
+            None,
+            false,
         )
         .await;
         assert_eq!(exited, Ok("Exited".to_string()));
@@ -821,8 +1756,8 @@ mod tests {
             .expect("expected value or result, got None or Err");
 
         let filter = String::from("today");
-        let labels = vec![String::from("thing")];
-        let sort = &SortOrder::Value;
+        let labels = vec![Label::from_name("thing")];
+        let sort = &SortOptions::default();
 
         assert_eq!(
             label(&config_with_timezone, Flag::Filter(filter), &labels, sort).await,
@@ -882,7 +1817,7 @@ mod tests {
             .with_mock_string("tomorrow");
 
         let filter = String::from("today");
-        let sort = &SortOrder::Value;
+        let sort = &SortOptions::default();
 
         let result = remind(&config, Flag::Filter(filter), sort).await;
         assert_eq!(result, Ok(String::from("Successfully reminded 'today'")));
@@ -948,7 +1883,7 @@ mod tests {
             .first()
             .expect("Expected at least one project in binding")
             .to_owned();
-        let sort = &SortOrder::Value;
+        let sort = &SortOptions::default();
 
         let result = remind(&config, Flag::Project(project), sort).await;
         assert_eq!(
@@ -1001,7 +1936,7 @@ mod tests {
             .first()
             .expect("Expected at least one project in binding")
             .to_owned();
-        let sort = &SortOrder::Value;
+        let sort = &SortOptions::default();
 
         let result = remind(&config, Flag::Project(project), sort).await;
         assert_eq!(
@@ -1028,20 +1963,73 @@ mod tests {
 
         let config = test::fixtures::config().await.with_mock_url(server.url());
 
-        let mut config_with_timezone = config
+        let config_with_timezone = config
             .with_timezone("US/Pacific")
             .with_mock_url(server.url());
         let filter = String::from("today");
-        let sort = &SortOrder::Value;
-
-        let tasks = view(&mut config_with_timezone, Flag::Filter(filter), sort)
-            .await
-            .expect("expected value or result, got None or Err");
+        let sort = &SortOptions::default();
+
+        let tasks = view(
+            &config_with_timezone,
+            Flag::Filter(filter),
+            sort,
+            GroupBy::None,
+            None,
+            DueRange::default(),
+            None,
+            None,
+            None,
+            &ViewOutput::default(),
+        )
+        .await
+        .expect("expected value or result, got None or Err");
 
         assert!(tasks.contains("Tasks for today"));
         mock.assert();
     }
 
+    #[tokio::test]
+    async fn test_view_count_only_counts_each_task_once() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api/v1/tasks/filter?query=today&limit=200")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::TodayTasks.read().await)
+            .expect(4)
+            .create_async()
+            .await;
+
+        let config = test::fixtures::config().await.with_mock_url(server.url());
+        let output = ViewOutput {
+            count_only: true,
+            ..ViewOutput::default()
+        };
+        let count = |min_priority| {
+            let config = config.clone();
+            let output = output.clone();
+            async move {
+                view(
+                    &config,
+                    Flag::Filter(String::from("today,today")),
+                    &SortOptions::default(),
+                    GroupBy::None,
+                    min_priority,
+                    DueRange::default(),
+                    None,
+                    None,
+                    None,
+                    &output,
+                )
+                .await
+            }
+        };
+
+        assert_eq!(count(None).await, Ok(String::from("1")));
+        assert_eq!(count(Some(Priority::High)).await, Ok(String::from("0")));
+        mock.assert();
+    }
+
     #[tokio::test]
     async fn test_view_with_project() {
         let mut server = mockito::Server::new_async().await;
@@ -1055,7 +2043,7 @@ mod tests {
 
         let config = test::fixtures::config().await.with_mock_url(server.url());
 
-        let mut config_with_timezone = config
+        let config_with_timezone = config
             .with_timezone("US/Pacific")
             .with_mock_url(server.url());
 
@@ -1067,14 +2055,159 @@ mod tests {
             .first()
             .expect("Expected at least one project in binding")
             .clone();
-        let sort = &SortOrder::Value;
+        let sort = &SortOptions::default();
+
+        let tasks = view(
+            &config_with_timezone,
+            Flag::Project(project),
+            sort,
+            GroupBy::None,
+            None,
+            DueRange::default(),
+            None,
+            None,
+            None,
+            &ViewOutput::default(),
+        )
+        .await
+        .expect("expected value or result, got None or Err");
+
+        assert!(tasks.contains("Tasks for"));
+        assert!(tasks.contains("\n1 - ! TEST\n"));
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_view_with_columns() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api/v1/tasks/?project_id=123&limit=200")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::TodayTasks.read().await)
+            .create_async()
+            .await;
 
-        let tasks = view(&mut config_with_timezone, Flag::Project(project), sort)
+        let config = test::fixtures::config().await.with_mock_url(server.url());
+        let output = ViewOutput {
+            columns: Some(vec![TaskColumn::Content, TaskColumn::Priority]),
+            ..ViewOutput::default()
+        };
+
+        let tasks = view(
+            &config,
+            Flag::Project(test::fixtures::project()),
+            &SortOptions::default(),
+            GroupBy::None,
+            None,
+            DueRange::default(),
+            None,
+            None,
+            None,
+            &output,
+        )
+        .await
+        .expect("view should render");
+
+        assert!(tasks.ends_with("\n1 - TEST !"));
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_view_with_completed_tasks() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api/v1/tasks/?project_id=123&limit=200")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::TodayTasks.read().await)
+            .create_async()
+            .await;
+        let completed_mock = server
+            .mock(
+                "GET",
+                "/api/v1/tasks/completed/by_completion_date?since=2025-05-10T07%3A00%3A00Z&until=2025-05-10T10%3A00%3A00Z&limit=200&project_id=123",
+            )
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::CompletedTasks.read().await)
+            .create_async()
+            .await;
+        let config = test::fixtures::config().await.with_mock_url(server.url());
+        let today = time::naive_date_today(&config).expect("today should be known");
+
+        let tasks = view(
+            &config,
+            Flag::Project(test::fixtures::project()),
+            &SortOptions::default(),
+            GroupBy::None,
+            None,
+            DueRange::default(),
+            None,
+            Some(IncludeCompleted {
+                since: today,
+                batch_days: None,
+            }),
+            None,
+            &ViewOutput::default(),
+        )
+        .await
+        .expect("view should render");
+
+        assert!(tasks.contains("\n1 - ! TEST\n"));
+        assert!(tasks.contains("\n- [x] DONE\n"));
+        mock.assert();
+        completed_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_view_projects_merges_and_deduplicates() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api/v1/tasks/?project_id=123&limit=200")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::TodayTasks.read().await)
+            .create_async()
+            .await;
+        let mock2 = server
+            .mock("GET", "/api/v1/tasks/?project_id=456&limit=200")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::TodayTasks.read().await)
+            .create_async()
+            .await;
+
+        let config = test::fixtures::config()
             .await
-            .expect("expected value or result, got None or Err");
+            .with_timezone("US/Pacific")
+            .with_mock_url(server.url());
+        let projects = vec![
+            test::fixtures::project(),
+            Project {
+                id: "456".to_string(),
+                name: "other".to_string(),
+                ..test::fixtures::project()
+            },
+        ];
+
+        let tasks = view_projects(
+            &config,
+            &projects,
+            &SortOptions::default(),
+            None,
+            DueRange::default(),
+            None,
+            None,
+            &ViewOutput::default(),
+        )
+        .await
+        .expect("expected value or result, got None or Err");
 
-        assert!(tasks.contains("Tasks for"));
-        assert!(tasks.contains("- TEST\n"));
+        assert!(tasks.contains("Tasks for myproject, other"));
+        assert_eq!(tasks.matches("- ! TEST\n").count(), 1);
+        assert!(tasks.contains("[myproject] - ! TEST\n"));
         mock.assert();
+        mock2.assert();
     }
 }
@@ -7,17 +7,17 @@ extern crate matches;
 
 extern crate clap;
 
-use clap::Parser;
 use commands::Cli;
-use errors::Error;
+use errors::{Error, ErrorKind};
 use std::{
     io::{self, Write},
+    path::Path,
     process::ExitCode,
 };
-use tasks::SortOrder;
 use tokio::sync::mpsc::{UnboundedSender, unbounded_channel};
 
 mod cargo;
+mod cleanup;
 mod commands;
 mod comments;
 mod config;
@@ -25,17 +25,20 @@ mod debug;
 mod errors;
 mod filters;
 mod format;
+mod id;
 mod input;
 mod labels;
 mod legacy;
 mod lists;
 mod oauth;
+mod process_state;
 mod projects;
 mod regexes;
 mod reminders;
 mod sections;
 mod shell;
 mod tasks;
+mod templates;
 mod test;
 mod test_time;
 mod time;
@@ -54,14 +57,26 @@ struct CommandResult {
 
 #[tokio::main]
 async fn main() -> ExitCode {
-    let cli = Cli::parse();
+    let cli = Cli::parse_with_name();
+    let output = cli.output.clone();
+    if output.is_some() {
+        // Color codes would end up in the file
+        colored::control::set_override(false);
+    }
 
     // Channel for sending errors from async processes
     let (tx, mut rx) = unbounded_channel::<Error>();
 
-    let result = run_command(cli, tx).await;
+    // Commands that handle Ctrl-C themselves, such as list view --watch, get to finish
+    let result = cleanup::until_interrupted(run_command(cli, tx), tokio::signal::ctrl_c())
+        .await
+        .unwrap_or_else(|| CommandResult {
+            result: Err(Error::new("ctrl-c", "Interrupted").with_kind(ErrorKind::UserCancelled)),
+            bell_success: false,
+            bell_failure: false,
+        });
 
-    let mut exit_code = output_result(result);
+    let mut exit_code = output_result(result, output.as_deref());
 
     while let Ok(error) = rx.try_recv() {
         if error.source.as_str() == "shell command" {
@@ -73,10 +88,18 @@ async fn main() -> ExitCode {
     ExitCode::from(exit_code)
 }
 
-fn output_result(result: CommandResult) -> u8 {
+fn output_result(result: CommandResult, output: Option<&Path>) -> u8 {
     match result.result {
         Ok(text) => {
-            println!("{text}");
+            match output {
+                Some(path) => {
+                    if let Err(e) = write_output(path, &text) {
+                        eprintln!("\n\n{e}");
+                        return e.kind.exit_code();
+                    }
+                }
+                None => println!("{text}"),
+            }
             if result.bell_success {
                 terminal_bell();
             }
@@ -87,11 +110,21 @@ fn output_result(result: CommandResult) -> u8 {
             if result.bell_failure {
                 terminal_bell();
             }
-            1
+            e.kind.exit_code()
         }
     }
 }
 
+/// Writes the result of a command to the file given with --output
+fn write_output(path: &Path, text: &str) -> Result<(), Error> {
+    std::fs::write(path, format!("{text}\n")).map_err(|e| {
+        Error::new(
+            "output",
+            &format!("Could not write to {}: {e}", path.display()),
+        )
+    })
+}
+
 async fn run_command(cli: Cli, tx: UnboundedSender<Error>) -> CommandResult {
     commands::select_command(cli, tx)
         .await
@@ -107,9 +140,21 @@ fn terminal_bell() {
     io::stdout().flush().expect("failed to flush stdout");
 }
 
+#[test]
+fn write_output_writes_result_with_trailing_newline() {
+    let dir = tempfile::tempdir().expect("temp dir should be created");
+    let path = dir.path().join("result.txt");
+    write_output(&path, "✓").expect("output should be written");
+    let contents = std::fs::read_to_string(&path).expect("output file should exist");
+    assert_eq!(contents, "✓\n");
+
+    let missing = dir.path().join("missing").join("result.txt");
+    assert!(write_output(&missing, "✓").is_err());
+}
+
 #[test]
 fn verify_cmd() {
-    use clap::CommandFactory;
+    use clap::{CommandFactory, Parser};
     // Mostly checks that it is not going to throw an exception because of conflicting short arguments
     Cli::try_parse().err();
     Cli::command().debug_assert();
@@ -2,8 +2,8 @@ use futures::lock::Mutex;
 use serde::Deserialize;
 use uuid::Uuid;
 
-use crate::errors::Error;
-use crate::format::{green_string, maybe_format_url};
+use crate::errors::{Error, ErrorKind};
+use crate::format::{blue_string, green_string, maybe_format_url};
 use crate::todoist::OAUTH_URL;
 use crate::{config::Config, todoist};
 
@@ -31,6 +31,10 @@ struct Params {
 #[derive(Deserialize, Debug, PartialEq, Eq)]
 pub struct AccessToken {
     pub access_token: String,
+    // only returned when the token can be refreshed
+    pub refresh_token: Option<String>,
+    // seconds until the access token expires
+    pub expires_in: Option<i64>,
 }
 impl AccessToken {
     pub fn from_json(json: &str) -> Result<AccessToken, Error> {
@@ -48,7 +52,7 @@ pub async fn login(config: &mut Config, test_tx: Option<Sender<()>>) -> Result<S
         .code
         .ok_or_else(|| Error::new("params", "no code provided"))?;
     let access_token = todoist::get_access_token(config, &code).await?;
-    let result = config.set_token(access_token).await?;
+    let result = config.set_access_token(&access_token).await?;
     let current = std::mem::take(config);
     *config = current.maybe_set_timezone().await?;
 
@@ -60,6 +64,48 @@ pub async fn login(config: &mut Config, test_tx: Option<Sender<()>>) -> Result<S
     Ok(result)
 }
 
+/// Exchange the refresh token for a new access token and save it to config.
+/// Returns the new access token, or the one already refreshed by another request during this run.
+pub async fn refresh(config: &Config, expired_token: &str) -> Result<String, Error> {
+    let access_token = {
+        let mut refreshed_token = config.internal.refreshed_token.lock().await;
+        if let Some(token) = refreshed_token
+            .as_ref()
+            .filter(|token| *token != expired_token)
+        {
+            return Ok(token.clone());
+        }
+
+        let refresh_token = config
+            .refresh_token
+            .clone()
+            .ok_or_else(|| refresh_error("No refresh token saved"))?;
+        let access_token = todoist::refresh_access_token(config, &refresh_token)
+            .await
+            .map_err(|e| refresh_error(&e.message))?;
+        *refreshed_token = Some(access_token.access_token.clone());
+        access_token
+    };
+
+    // Saved once the lock is released, `Config::save` checks the refreshed token
+    let mut updated_config = config.clone();
+    updated_config.set_access_token(&access_token).await?;
+
+    Ok(access_token.access_token)
+}
+
+fn refresh_error(reason: &str) -> Error {
+    let command = blue_string("tod auth login");
+    Error::new(
+        "oauth refresh",
+        &format!(
+            "Could not refresh the Todoist access token, please run {command}
+{reason}"
+        ),
+    )
+    .with_kind(ErrorKind::Auth)
+}
+
 fn print_oauth_url(config: &Config) -> String {
     let csrf_token = new_uuid();
 
@@ -160,6 +206,8 @@ mod tests {
         assert_de_tokens(
             &AccessToken {
                 access_token: "token-value".to_string(),
+                refresh_token: None,
+                expires_in: None,
             },
             &[
                 Token::Struct {
@@ -173,6 +221,88 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn refresh_saves_new_token() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/oauth/access_token")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"access_token": "new-token", "refresh_token": "new-refresh", "expires_in": 3600}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let temp_dir = tempfile::tempdir().expect("temp dir should be created");
+        let path = temp_dir.path().join("oauth-refresh.cfg");
+        let mut config = Config::default_test()
+            .with_path(path.clone())
+            .with_mock_url(server.url());
+        config.refresh_token = Some("old-refresh".to_string());
+        let config = config.create().await.expect("config should be created");
+
+        let token = refresh(&config, "default-token").await;
+        assert_eq!(token, Ok("new-token".to_string()));
+
+        // A second caller with the same expired token reuses the refreshed token
+        let token = refresh(&config, "default-token").await;
+        assert_eq!(token, Ok("new-token".to_string()));
+
+        let saved = Config::load(&path)
+            .await
+            .expect("config should be readable");
+        assert_eq!(saved.token, Some("new-token".to_string()));
+        assert_eq!(saved.refresh_token, Some("new-refresh".to_string()));
+        assert!(saved.token_expires_at.is_some());
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn save_after_refresh_keeps_the_new_token() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/oauth/access_token")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"access_token": "new-token", "refresh_token": "new-refresh", "expires_in": 3600}"#)
+            .create_async()
+            .await;
+
+        let temp_dir = tempfile::tempdir().expect("temp dir should be created");
+        let path = temp_dir.path().join("oauth-refresh-save.cfg");
+        let mut config = Config::default_test()
+            .with_path(path.clone())
+            .with_mock_url(server.url());
+        config.refresh_token = Some("old-refresh".to_string());
+        config.token_expires_at = Some(0);
+        let mut config = config.create().await.expect("config should be created");
+
+        refresh(&config, "default-token")
+            .await
+            .expect("refresh should succeed");
+        // Commands such as task next save the copy of the config they loaded before the refresh
+        config.save().await.expect("config should save");
+
+        let saved = Config::load(&path)
+            .await
+            .expect("config should be readable");
+        assert_eq!(saved.token, Some("new-token".to_string()));
+        assert_eq!(saved.refresh_token, Some("new-refresh".to_string()));
+        assert_ne!(saved.token_expires_at, Some(0));
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn refresh_without_refresh_token_asks_to_login() {
+        let config = Config::default_test();
+
+        let error = refresh(&config, "default-token")
+            .await
+            .expect_err("refresh should fail without a refresh token");
+        assert_eq!(error.source, "oauth refresh");
+        assert!(error.message.contains("tod auth login"));
+    }
+
     #[tokio::test]
     async fn login_test() {
         let mut server = mockito::Server::new_async().await;
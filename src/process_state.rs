@@ -0,0 +1,91 @@
+//! Remembers which tasks `list process` has handled, so that an interrupted session can be resumed
+//! State is kept in the temp directory, one file per config and project or filter
+use crate::cleanup;
+use crate::config::Config;
+use crate::errors::Error;
+use crate::lists::Flag;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tokio::fs;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ProcessState {
+    /// IDs of the tasks already handled in this session
+    pub task_ids: Vec<String>,
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+impl ProcessState {
+    /// Loads the saved session for a project or filter, empty when there is none or it can't be read
+    pub async fn load(config: &Config, flag: &Flag) -> ProcessState {
+        let path = state_path(config, flag);
+        let task_ids = match fs::read_to_string(&path).await {
+            Ok(json) => serde_json::from_str::<ProcessState>(&json)
+                .map(|state| state.task_ids)
+                .unwrap_or_default(),
+            Err(_) => Vec::new(),
+        };
+
+        ProcessState { task_ids, path }
+    }
+
+    pub fn contains(&self, task_id: &str) -> bool {
+        self.task_ids.iter().any(|id| id == task_id)
+    }
+
+    /// Adds a handled task and writes the state straight away so that it survives Ctrl-C
+    pub async fn record(&mut self, task_id: &str) -> Result<(), Error> {
+        self.task_ids.push(task_id.to_string());
+        let json = serde_json::to_string(&self)?;
+        cleanup::write_file(&self.path, &json).await
+    }
+
+    /// Forgets the session by removing the state file
+    pub async fn clear(&mut self) -> Result<(), Error> {
+        self.task_ids.clear();
+        match fs::remove_file(&self.path).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+fn state_path(config: &Config, flag: &Flag) -> PathBuf {
+    let key = match flag {
+        Flag::Project(project) => format!("project:{}", project.id),
+        Flag::Filter(filter) => format!("filter:{filter}"),
+    };
+    config.temp_path("tod-process", &key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test;
+
+    #[tokio::test]
+    async fn record_load_and_clear_should_work() {
+        let config = test::fixtures::config().await;
+        let flag = Flag::Filter("today".to_string());
+
+        let mut state = ProcessState::load(&config, &flag).await;
+        assert!(state.task_ids.is_empty());
+
+        state.record("1").await.expect("recording should succeed");
+        state.record("2").await.expect("recording should succeed");
+
+        let mut state = ProcessState::load(&config, &flag).await;
+        assert!(state.contains("1"));
+        assert!(state.contains("2"));
+        assert!(!state.contains("3"));
+
+        let other = ProcessState::load(&config, &Flag::Filter("overdue".to_string())).await;
+        assert!(other.task_ids.is_empty());
+
+        state.clear().await.expect("clearing should succeed");
+        let state = ProcessState::load(&config, &flag).await;
+        assert!(state.task_ids.is_empty());
+    }
+}
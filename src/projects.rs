@@ -1,17 +1,47 @@
 use futures::{StreamExt, TryStreamExt, future, stream};
 use pad::PadStr;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::fmt::Display;
 use tokio::task::JoinHandle;
 
 use crate::config::Config;
-use crate::errors::Error;
+use crate::errors::{Error, ErrorKind};
 use crate::sections::Section;
-use crate::tasks::{FormatType, Task};
-use crate::{SortOrder, format, input, sections, tasks, todoist};
+use crate::tasks::{FormatType, SortOptions, Task, priority::Priority};
+use crate::templates::{ProjectTemplate, TemplateTask};
+use crate::{format, input, sections, tasks, todoist};
 use serde::{Deserialize, Serialize};
 
 const PAD_WIDTH: usize = 30;
+
+/// What happens to the source project after `project merge` moves its tasks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeSource {
+    Keep,
+    Archive,
+    Delete,
+}
+
+/// How `project empty` decides where each task goes
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmptyStrategy {
+    /// Ask what to do with each task
+    Prompt,
+    /// Move every task into the project given with --into
+    Single,
+    /// Move each task into the project mapped from its first label in label_projects
+    ByLabel,
+}
+
+/// What happened to a task while emptying a project, counted for the summary
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EmptyOutcome {
+    Moved(String),
+    Completed,
+    Deleted,
+    Skipped,
+}
+
 const PROJECT_URL: &str = "https://app.todoist.com/app/project";
 
 // Projects are split into sections
@@ -78,30 +108,77 @@ pub async fn create(
     name: String,
     description: &str,
     is_favorite: bool,
+    template: Option<&ProjectTemplate>,
 ) -> Result<String, Error> {
     let project = todoist::create_project(config, &name, description, is_favorite, true).await?;
     add(config, &project).await?;
-    Ok(format!("Created project {name} and added to config"))
+    let created = format!("Created project {name} and added to config");
+    match template {
+        None => Ok(created),
+        Some(template) => {
+            let (sections, tasks) = apply_template(config, &project, template).await?;
+            Ok(format!(
+                "{created}\nCreated {sections} section(s) and {tasks} task(s) from template"
+            ))
+        }
+    }
+}
+
+/// Creates the sections and tasks of a template in a project, in the order they are listed
+/// Returns the number of sections and tasks created
+async fn apply_template(
+    config: &Config,
+    project: &Project,
+    template: &ProjectTemplate,
+) -> Result<(usize, usize), Error> {
+    let mut task_count = 0;
+    for task in &template.tasks {
+        create_template_task(config, project, None, task).await?;
+        task_count += 1;
+    }
+    for template_section in &template.sections {
+        let section =
+            todoist::create_section(config, &template_section.name, project, false).await?;
+        for task in &template_section.tasks {
+            create_template_task(config, project, Some(&section), task).await?;
+            task_count += 1;
+        }
+    }
+    Ok((template.sections.len(), task_count))
+}
+
+async fn create_template_task(
+    config: &Config,
+    project: &Project,
+    section: Option<&Section>,
+    task: &TemplateTask,
+) -> Result<(), Error> {
+    todoist::create_task(
+        config,
+        &task.content,
+        project,
+        section,
+        task.priority()?,
+        &task.description,
+        task.due.as_deref(),
+        None,
+        &task.labels,
+        None,
+    )
+    .await?;
+    Ok(())
 }
 /// List the projects in config with task counts
 pub async fn list(config: &mut Config) -> Result<String, Error> {
     config.reload_projects().await?;
 
-    let project_handles = config
-        .projects()
-        .await?
-        .into_iter()
-        .map(|project| {
-            let config = config.clone();
-            tokio::spawn(async move { project_name_with_count(&config, &project).await })
-        })
-        .collect::<Vec<_>>();
+    let config: &Config = config;
 
-    let mut projects: Vec<String> = future::join_all(project_handles)
-        .await
-        .into_iter()
-        .map(std::result::Result::unwrap_or_default)
-        .collect();
+    let mut projects: Vec<String> = stream::iter(config.projects().await?)
+        .map(|project| async move { project_name_with_count(config, &project).await })
+        .buffer_unordered(config.max_concurrency())
+        .collect()
+        .await;
     if projects.is_empty() {
         return Ok("No projects found".into());
     }
@@ -117,6 +194,112 @@ pub async fn list(config: &mut Config) -> Result<String, Error> {
     Ok(buffer)
 }
 
+/// List the projects in config as a JSON array of their id, name, favorite status and color
+pub async fn list_json(config: &mut Config) -> Result<String, Error> {
+    config.reload_projects().await?;
+
+    let mut projects = config.projects().await?;
+    projects.sort_by(|a, b| a.name.cmp(&b.name));
+    let projects = projects
+        .iter()
+        .map(|project| {
+            serde_json::json!({
+                "id": project.id,
+                "name": project.name,
+                "is_favorite": project.is_favorite,
+                "color": project.color,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    Ok(serde_json::to_string_pretty(&projects)?)
+}
+
+/// List the projects in config with a badge of their active task count, i.e. "Work (12)"
+pub async fn list_with_counts(config: &mut Config) -> Result<String, Error> {
+    config.reload_projects().await?;
+    let config: &Config = config;
+
+    let mut projects = stream::iter(config.projects().await?)
+        .map(|project| async move {
+            let tasks = todoist::all_tasks_by_project(config, &project, None).await?;
+            Ok::<String, Error>(format!("{} ({})", project.name, tasks.len()))
+        })
+        .buffer_unordered(config.max_concurrency())
+        .try_collect::<Vec<String>>()
+        .await?;
+
+    if projects.is_empty() {
+        return Ok("No projects found".into());
+    }
+    projects.sort();
+    let mut buffer = format::green_string("Projects");
+
+    for key in projects {
+        buffer.push_str("\n - ");
+        buffer.push_str(&key);
+    }
+    Ok(buffer)
+}
+
+/// Summarises the active tasks in a project, with counts of overdue and due today tasks and a count for each priority
+pub async fn stats(config: &Config, project: &Project, json: bool) -> Result<String, Error> {
+    let tasks = todoist::all_tasks_by_project(config, project, None).await?;
+    let overdue = tasks
+        .iter()
+        .filter(|task| task.is_overdue(config).unwrap_or_default())
+        .count();
+    let due_today = tasks
+        .iter()
+        .filter(|task| task.is_today(config).unwrap_or_default())
+        .count();
+    let by_priority = tasks::priority::all_priorities()
+        .into_iter()
+        .rev()
+        .map(|priority| {
+            let count = tasks
+                .iter()
+                .filter(|task| task.priority == priority)
+                .count();
+            (priority, count)
+        })
+        .collect::<Vec<(Priority, usize)>>();
+
+    if json {
+        let priorities = by_priority
+            .iter()
+            .map(|(priority, count)| (priority_key(*priority).to_string(), (*count).into()))
+            .collect::<serde_json::Map<String, serde_json::Value>>();
+        let stats = serde_json::json!({
+            "id": project.id,
+            "name": project.name,
+            "total": tasks.len(),
+            "overdue": overdue,
+            "due_today": due_today,
+            "priority": priorities,
+        });
+        return Ok(serde_json::to_string_pretty(&stats)?);
+    }
+
+    let mut buffer = format::green_string(&format!("Stats for '{}'", project.name));
+    buffer.push_str(&format!("\nTasks: {}", tasks.len()));
+    buffer.push_str(&format!("\nOverdue: {overdue}"));
+    buffer.push_str(&format!("\nDue today: {due_today}"));
+    for (priority, count) in by_priority {
+        buffer.push_str(&format!("\n - {priority}: {count}"));
+    }
+    Ok(buffer)
+}
+
+fn priority_key(priority: Priority) -> &'static str {
+    match priority {
+        Priority::High => "high",
+        Priority::Medium => "medium",
+        Priority::Low => "low",
+        Priority::None => "none",
+    }
+}
+
 /// Formats a string with project name and the count that is a standard length
 async fn project_name_with_count(config: &Config, project: &Project) -> String {
     let count = match count_processable_tasks(config, project).await {
@@ -154,35 +337,85 @@ pub async fn delete(config: &mut Config, project: &Project) -> Result<String, Er
     config.save().await
 }
 
-/// Rename a project in config
+/// Rename a project in config, and in Todoist when `remote` is true
 pub async fn rename(
     config: &mut Config,
     project: &Project,
     new_name: Option<&str>,
+    remote: bool,
 ) -> Result<String, Error> {
     let new_name = match new_name {
         Some(name) => name.to_string(),
         None => input::string_with_default(input::NAME, &project.name)?,
     };
 
-    let new_project = Project {
-        name: new_name,
-        ..project.clone()
+    let new_project = if remote {
+        todoist::rename_project(config, project, &new_name, true).await?
+    } else {
+        Project {
+            name: new_name,
+            ..project.clone()
+        }
     };
     remove(config, project).await?;
-    add(config, &new_project).await
+    add(config, &new_project).await?;
+
+    Ok(format!(
+        "Renamed project '{}' to '{}'",
+        project.name, new_project.name
+    ))
+}
+
+/// Mark a project as favorite in Todoist, or unmark it, and update it in config
+pub async fn favorite(
+    config: &mut Config,
+    project: &Project,
+    is_favorite: bool,
+) -> Result<String, Error> {
+    let body = serde_json::json!({"is_favorite": is_favorite});
+    let updated = todoist::update_project(config, project, body, true).await?;
+    remove(config, project).await?;
+    add(config, &updated).await?;
+
+    if updated.is_favorite {
+        Ok(format!("Project '{}' is now a favorite", updated.name))
+    } else {
+        Ok(format!(
+            "Project '{}' is no longer a favorite",
+            updated.name
+        ))
+    }
 }
 
-/// Get the next task by priority and save its id to config
-pub async fn next_task(config: Config, project: &Project) -> Result<String, Error> {
-    match fetch_next_task(&config, project).await {
+/// Get the next task by priority and save its id to config, opening it in the browser when `open` is set.
+/// Lists the links in its comments beneath it when `links` is set
+pub async fn next_task(
+    config: Config,
+    project: &Project,
+    sort: &SortOptions,
+    open: bool,
+    links: bool,
+) -> Result<String, Error> {
+    match fetch_next_task(&config, project, sort).await {
         Ok(Some((task, remaining))) => {
             let comments = todoist::all_comments(&config, &task.id, None).await?;
+            let links = if links {
+                tasks::format_comment_links(&comments)
+            } else {
+                String::new()
+            };
             let task_string = task
                 .fmt(comments, &config, FormatType::Single, false)
                 .await?;
-            config.set_next_task(task).save().await?;
-            Ok(format!("{task_string}\n{remaining} task(s) remaining"))
+            config.set_next_task(task.clone()).save().await?;
+            // The task is already saved as next, so a browser that fails to open only gets reported
+            let open_failure = match tasks::maybe_open(&config, &task, open) {
+                Ok(()) => String::new(),
+                Err(e) => format!("\n{}", format::red_string(&e.message)),
+            };
+            Ok(format!(
+                "{task_string}{links}{open_failure}\n{remaining} task(s) remaining"
+            ))
         }
         Ok(None) => Ok(format::green_string("No tasks on list")),
         Err(e) => Err(e),
@@ -192,18 +425,19 @@ pub async fn next_task(config: Config, project: &Project) -> Result<String, Erro
 async fn fetch_next_task(
     config: &Config,
     project: &Project,
+    sort: &SortOptions,
 ) -> Result<Option<(Task, usize)>, Error> {
     let tasks = todoist::all_tasks_by_project(config, project, None).await?;
     let filtered_tasks = tasks::filter_not_in_future(tasks, config);
-    let tasks = tasks::sort_by_value(filtered_tasks, config);
+    let filtered_tasks = tasks::filter_skipped(filtered_tasks, config);
+    let tasks = tasks::sort(filtered_tasks, config, *sort);
 
     Ok(tasks.first().map(|task| (task.to_owned(), tasks.len())))
 }
 
 /// Removes all projects from config that don't exist in Todoist
 pub async fn remove_auto(config: &mut Config) -> Result<String, Error> {
-    let projects = todoist::all_projects(config, None).await?;
-    let missing_projects = filter_missing_projects(config, projects).await?;
+    let missing_projects = missing_projects(config).await?;
 
     if missing_projects.is_empty() {
         return Ok(format::green_string("No projects to auto remove"));
@@ -248,9 +482,14 @@ pub async fn remove_all(config: &mut Config) -> Result<String, Error> {
     Ok(format::green_string(message))
 }
 
-/// Returns the projects that are not already in config
+/// Projects in config that no longer exist in Todoist
+pub async fn missing_projects(config: &Config) -> Result<Vec<Project>, Error> {
+    let projects = todoist::all_projects(config, None).await?;
+    filter_missing_projects(config, projects).await
+}
+
 async fn filter_missing_projects(
-    config: &mut Config,
+    config: &Config,
     projects: Vec<Project>,
 ) -> Result<Vec<Project>, Error> {
     let configured_projects = config.projects().await?;
@@ -266,6 +505,65 @@ async fn filter_missing_projects(
     Ok(config)
 }
 
+/// Compares the projects in config with Todoist, listing the ones only in config (stale)
+/// and the ones only in Todoist (unimported), with the commands that fix each
+pub async fn diff_remote(config: &Config, json: bool) -> Result<String, Error> {
+    let projects = todoist::all_projects(config, None).await?;
+    let stale = filter_missing_projects(config, projects.clone()).await?;
+    let unimported = filter_new_projects(config, projects).await?;
+
+    if json {
+        let to_json = |projects: &[Project]| {
+            projects
+                .iter()
+                .map(|project| serde_json::json!({"id": project.id, "name": project.name}))
+                .collect::<Vec<_>>()
+        };
+        let diff = serde_json::json!({
+            "stale": to_json(&stale),
+            "unimported": to_json(&unimported),
+        });
+        return Ok(serde_json::to_string_pretty(&diff)?);
+    }
+
+    if stale.is_empty() && unimported.is_empty() {
+        return Ok(format::green_string("The projects in config match Todoist"));
+    }
+
+    let mut sections = Vec::new();
+    if !stale.is_empty() {
+        let command = format::cyan_string("tod project remove --auto");
+        sections.push(format!(
+            "{}\n{}\nRun {command} to remove them",
+            format::yellow_string(&format!(
+                "Stale, in config but not in Todoist ({}):",
+                stale.len()
+            )),
+            project_names_list(&stale)
+        ));
+    }
+    if !unimported.is_empty() {
+        let command = format::cyan_string("tod project import");
+        sections.push(format!(
+            "{}\n{}\nRun {command} to add them",
+            format::yellow_string(&format!(
+                "Unimported, in Todoist but not in config ({}):",
+                unimported.len()
+            )),
+            project_names_list(&unimported)
+        ));
+    }
+    Ok(sections.join("\n\n"))
+}
+
+fn project_names_list(projects: &[Project]) -> String {
+    projects
+        .iter()
+        .map(|project| format!(" - {}", project.name))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
 /// Fetch projects and prompt to add them to config one by one
 pub async fn import(
     config: &mut Config,
@@ -291,6 +589,7 @@ pub async fn import(
                     "project_import",
                     &format!("Could not find Todoist project named '{project_name}'"),
                 )
+                .with_kind(ErrorKind::NotFound)
             })?;
         let new_projects = filter_new_projects(config, vec![target]).await?;
 
@@ -310,6 +609,7 @@ pub async fn import(
                     "project_import",
                     &format!("Could not find Todoist project with id '{project_id}'"),
                 )
+                .with_kind(ErrorKind::NotFound)
             })?;
         let new_projects = filter_new_projects(config, vec![target]).await?;
 
@@ -327,9 +627,39 @@ pub async fn import(
     Ok(format::green_string("No more projects"))
 }
 
+/// Lets the user pick any number of the Todoist projects that are not in config, then saves them together
+pub async fn import_selected(config: &mut Config) -> Result<String, Error> {
+    let projects = todoist::all_projects(config, None).await?;
+    let new_projects = filter_new_projects(config, projects).await?;
+    if new_projects.is_empty() {
+        return Ok(format::green_string("All projects are already in config"));
+    }
+
+    let selected = input::fuzzy_multi_select(
+        input::PROJECTS,
+        new_projects,
+        config.fuzzy_select(),
+        config.mock_select,
+    )?;
+    if selected.is_empty() {
+        return Ok("No projects selected".into());
+    }
+
+    let names = selected
+        .iter()
+        .map(|project| project.name.clone())
+        .collect::<Vec<String>>()
+        .join(", ");
+    for project in selected {
+        config.add_project(project);
+    }
+    config.save().await?;
+    Ok(format::green_string(&format!("Imported: {names}")))
+}
+
 /// Returns the projects that are not already in config
 async fn filter_new_projects(
-    config: &mut Config,
+    config: &Config,
     projects: Vec<Project>,
 ) -> Result<Vec<Project>, Error> {
     let configured_projects = config.projects().await?;
@@ -393,75 +723,374 @@ async fn maybe_add_project(
     }
 }
 
-pub async fn edit_task(config: &Config, project: &Project) -> Result<String, Error> {
+pub async fn edit_task(config: &Config, project: &Project, editor: bool) -> Result<String, Error> {
     let project_tasks = todoist::all_tasks_by_project(config, project, None).await?;
 
-    let task = input::select(
+    let task = input::fuzzy_select(
         "Choose a task of the project:",
         project_tasks,
+        config.fuzzy_select(),
         config.mock_select,
     )?;
 
-    let options = tasks::edit_task_attributes();
+    tasks::edit(config, &task, editor).await
+}
 
-    let selections = input::multi_select("Choose attributes to edit", options, config.mock_select)?;
+/// Empty a project, sending each task where the strategy says: asking for each one, into `into`, or by its label
+pub async fn empty(
+    config: &mut Config,
+    project: &Project,
+    strategy: EmptyStrategy,
+    into: Option<&Project>,
+) -> Result<String, Error> {
+    let tasks = todoist::all_tasks_by_project(config, project, None).await?;
 
-    if selections.is_empty() {
-        return Err(Error {
-            message: "Nothing selected".to_string(),
-            source: "edit_task".to_string(),
-        });
+    if tasks.is_empty() {
+        return Ok(format::green_string(&format!(
+            "No tasks to empty from '{}'",
+            project.name
+        )));
     }
+    // Subtasks move along with their parents
+    let tasks = tasks
+        .into_iter()
+        .filter(|task| task.parent_id.is_none())
+        .collect::<Vec<Task>>();
 
-    let mut handles = Vec::new();
-    for attribute in selections {
-        // Stops the inputs from rolling over each other in terminal
-        println!();
-        if let Some(handle) = tasks::update_task(config, &task, &attribute).await? {
-            handles.push(handle);
+    let outcomes = match (strategy, into) {
+        (EmptyStrategy::Prompt, _) => {
+            let sections = sections::all_sections(config).await?;
+            let mut handles = Vec::new();
+            let mut outcomes = Vec::new();
+            for task in tasks {
+                let (handle, outcome) = move_task_to_project(config, task, &sections).await?;
+                handles.push(handle);
+                outcomes.push(outcome);
+            }
+            future::join_all(handles).await;
+            outcomes
+        }
+        (EmptyStrategy::Single, Some(into)) => {
+            let routes = tasks.into_iter().map(|task| (task, Some(into.clone())));
+            move_routed_tasks(config, routes.collect()).await?
+        }
+        (EmptyStrategy::Single, None) => {
+            return Err(Error::new(
+                "project_empty",
+                "--strategy single needs the project to move tasks into, use --into",
+            ));
+        }
+        (EmptyStrategy::ByLabel, _) => {
+            let routes = route_by_label(config, tasks).await?;
+            move_routed_tasks(config, routes).await?
         }
+    };
+
+    Ok(format::green_string(&empty_summary(project, &outcomes)))
+}
+
+/// Pairs each task with the project mapped from its first label in label_projects, None when no label is mapped
+async fn route_by_label(
+    config: &Config,
+    tasks: Vec<Task>,
+) -> Result<Vec<(Task, Option<Project>)>, Error> {
+    let Some(label_projects) = config.label_projects.as_ref().filter(|map| !map.is_empty()) else {
+        return Err(Error::new(
+            "project_empty",
+            "--strategy by-label needs label_projects in config, mapping label names to project names",
+        ));
+    };
+    let projects = config.projects().await?;
+    let mut destinations = BTreeMap::new();
+    for (label, project_name) in label_projects {
+        let Some(project) = projects
+            .iter()
+            .find(|project| &project.name == project_name)
+        else {
+            return Err(Error::new(
+                "project_empty",
+                &format!(
+                    "Project '{project_name}' for label '{label}' in label_projects is not in config, import it with tod project import"
+                ),
+            ));
+        };
+        destinations.insert(label.as_str(), project.clone());
     }
 
-    future::join_all(handles).await;
-    Ok("Finished editing task".into())
+    Ok(tasks
+        .into_iter()
+        .map(|task| {
+            let destination = task
+                .labels
+                .iter()
+                .find_map(|label| destinations.get(label.as_str()))
+                .cloned();
+            (task, destination)
+        })
+        .collect())
 }
 
-/// Empty a project by sending tasks to other projects one at a time
-pub async fn empty(config: &mut Config, project: &Project) -> Result<String, Error> {
-    let tasks = todoist::all_tasks_by_project(config, project, None).await?;
+/// Moves each task into its project, leaving the ones without a project where they are
+async fn move_routed_tasks(
+    config: &Config,
+    routes: Vec<(Task, Option<Project>)>,
+) -> Result<Vec<EmptyOutcome>, Error> {
+    let total = routes.len();
+    let results = stream::iter(routes.into_iter().enumerate())
+        .map(|(index, (task, destination))| async move {
+            let Some(destination) = destination else {
+                return Ok(EmptyOutcome::Skipped);
+            };
+            tasks::print_progress(config, index + 1, total, "moving", &task);
+            match todoist::move_task_to_project(config, &task, &destination, false).await {
+                Ok(_) => Ok(EmptyOutcome::Moved(destination.name)),
+                Err(e) => Err(format!("'{}': {}", task.content, e.message)),
+            }
+        })
+        .buffer_unordered(config.max_concurrency())
+        .collect::<Vec<Result<EmptyOutcome, String>>>()
+        .await;
 
-    if tasks.is_empty() {
-        Ok(format::green_string(&format!(
-            "No tasks to empty from '{}'",
+    let (outcomes, failures): (Vec<_>, Vec<_>) = results.into_iter().partition(Result::is_ok);
+    let outcomes = outcomes
+        .into_iter()
+        .filter_map(Result::ok)
+        .collect::<Vec<_>>();
+    if failures.is_empty() {
+        return Ok(outcomes);
+    }
+    let moved = outcomes
+        .iter()
+        .filter(|outcome| matches!(outcome, EmptyOutcome::Moved(_)))
+        .count();
+    let mut message = format!(
+        "Moved {moved} task(s), failed to move {} task(s):",
+        failures.len()
+    );
+    for failure in failures.into_iter().filter_map(Result::err) {
+        message.push_str("\n - ");
+        message.push_str(&failure);
+    }
+    Err(Error::new("project_empty", &message))
+}
+
+/// One line for each destination with the number of tasks that went there
+fn empty_summary(project: &Project, outcomes: &[EmptyOutcome]) -> String {
+    let mut counts = BTreeMap::new();
+    for outcome in outcomes {
+        *counts.entry(outcome).or_insert(0) += 1;
+    }
+    let mut summary = format!("Emptied '{}'", project.name);
+    for (outcome, count) in counts {
+        let line = match outcome {
+            EmptyOutcome::Moved(name) => format!("Moved {count} task(s) into '{name}'"),
+            EmptyOutcome::Completed => format!("Completed {count} task(s)"),
+            EmptyOutcome::Deleted => format!("Deleted {count} task(s)"),
+            EmptyOutcome::Skipped => format!("Left {count} task(s) in '{}'", project.name),
+        };
+        summary.push_str("\n - ");
+        summary.push_str(&line);
+    }
+    summary
+}
+
+/// Finds tasks in a project with the same content, ignoring case and surrounding whitespace, and keeps one of each.
+/// The others are completed, or deleted when delete is set. With auto the oldest task is kept without asking.
+pub async fn dedupe(
+    config: &Config,
+    project: &Project,
+    auto: bool,
+    delete: bool,
+) -> Result<String, Error> {
+    let tasks = todoist::all_tasks_by_project(config, project, None).await?;
+    let groups = duplicate_groups(tasks);
+    if groups.is_empty() {
+        return Ok(format::green_string(&format!(
+            "No duplicate tasks in '{}'",
             project.name
-        )))
-    } else {
-        let sections = sections::all_sections(config).await?;
+        )));
+    }
 
-        let tasks = tasks
-            .into_iter()
-            .filter(|task| task.parent_id.is_none())
-            .collect::<Vec<Task>>();
+    let mut duplicates = Vec::new();
+    for mut group in groups {
+        // Oldest first, tasks without an added date go last
+        group.sort_by(|a, b| match (&a.added_at, &b.added_at) {
+            (Some(a), Some(b)) => a.cmp(b),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => std::cmp::Ordering::Equal,
+        });
+        let keep = if auto {
+            0
+        } else {
+            match select_task_to_keep(config, &group)? {
+                Some(keep) => keep,
+                None => continue,
+            }
+        };
+        group.remove(keep);
+        duplicates.extend(group);
+    }
+
+    let action = if delete { "deleting" } else { "completing" };
+    let total = duplicates.len();
+    let failures = stream::iter(duplicates.into_iter().enumerate())
+        .map(|(index, task)| async move {
+            tasks::print_progress(config, index + 1, total, action, &task);
+            let result = if delete {
+                todoist::delete_task(config, &task.id, false).await
+            } else {
+                todoist::complete_task(config, &task, false).await
+            };
+            result
+                .err()
+                .map(|e| format!("'{}': {}", task.content, e.message))
+        })
+        .buffer_unordered(config.max_concurrency())
+        .filter_map(future::ready)
+        .collect::<Vec<String>>()
+        .await;
+
+    let resolved = total - failures.len();
+    let verb = if delete { "deleted" } else { "completed" };
+    let mut message = format!(
+        "Resolved {resolved} duplicate(s) in '{}', the extra copies were {verb}",
+        project.name
+    );
+    if failures.is_empty() {
+        return Ok(format::green_string(&message));
+    }
+    message.push_str(&format!(
+        "\nFailed to resolve {} duplicate(s):",
+        failures.len()
+    ));
+    for failure in &failures {
+        message.push_str("\n - ");
+        message.push_str(failure);
+    }
+    Err(Error::new("project_dedupe", &message))
+}
 
-        let mut handles = Vec::new();
-        for task in tasks {
-            handles.push(move_task_to_project(config, task, &sections).await?);
+/// Groups of two or more tasks with the same content, ignoring case and surrounding whitespace, in the order first seen
+fn duplicate_groups(tasks: Vec<Task>) -> Vec<Vec<Task>> {
+    let mut groups: Vec<(String, Vec<Task>)> = Vec::new();
+    for task in tasks {
+        let key = task.content.trim().to_lowercase();
+        match groups.iter_mut().find(|(content, _)| *content == key) {
+            Some((_, group)) => group.push(task),
+            None => groups.push((key, vec![task])),
         }
-        future::join_all(handles).await;
-        Ok(format::green_string(&format!(
-            "Successfully emptied '{}'",
-            project.name
-        )))
     }
+    groups
+        .into_iter()
+        .map(|(_, group)| group)
+        .filter(|group| group.len() > 1)
+        .collect()
+}
+
+/// Asks which of the duplicate tasks to keep, None when the group is skipped
+fn select_task_to_keep(config: &Config, group: &[Task]) -> Result<Option<usize>, Error> {
+    let mut options = group
+        .iter()
+        .map(|task| {
+            let added = task.added_at.as_deref().unwrap_or("unknown");
+            format!("Keep '{}' added {added} ({})", task.content, task.id)
+        })
+        .collect::<Vec<String>>();
+    options.push(input::SKIP.to_string());
+    let selection = input::select(input::OPTION, options.clone(), config.mock_select)?;
+
+    Ok(options
+        .iter()
+        .position(|option| *option == selection)
+        .filter(|index| *index < group.len()))
+}
+
+/// Move all tasks from one project into another, then archive or delete the source when asked.
+/// With `strict` the first failed move stops the merge and leaves the source project as it is
+pub async fn merge(
+    config: &mut Config,
+    from: &Project,
+    into: &Project,
+    tasks: Vec<Task>,
+    source: MergeSource,
+    strict: bool,
+) -> Result<String, Error> {
+    // Subtasks move along with their parents
+    let tasks = tasks
+        .into_iter()
+        .filter(|task| task.parent_id.is_none())
+        .collect::<Vec<Task>>();
+    let total = tasks.len();
+
+    let shared: &Config = config;
+    let failures = {
+        let mut moves = std::pin::pin!(
+            stream::iter(tasks.into_iter().enumerate())
+                .map(|(index, task)| async move {
+                    tasks::print_progress(shared, index + 1, total, "moving", &task);
+                    match todoist::move_task_to_project(shared, &task, into, false).await {
+                        Ok(_) => Ok(()),
+                        Err(e) => Err((task, e)),
+                    }
+                })
+                .buffer_unordered(shared.max_concurrency())
+        );
+        let mut failures = Vec::new();
+        while let Some(result) = moves.next().await {
+            if let Err((task, e)) = result {
+                // Moves still in flight are dropped, the source project is left as it is
+                if strict {
+                    return Err(tasks::stopped_by_strict(&task, &e));
+                }
+                failures.push(format!("'{}': {}", task.content, e.message));
+            }
+        }
+        failures
+    };
+
+    let moved = total - failures.len();
+    let mut message = format!(
+        "Moved {moved} task(s) from '{}' into '{}'",
+        from.name, into.name
+    );
+    if !failures.is_empty() {
+        message.push_str(&format!("\nFailed to move {} task(s):", failures.len()));
+        for failure in &failures {
+            message.push_str("\n - ");
+            message.push_str(failure);
+        }
+        if source != MergeSource::Keep {
+            message.push_str(&format!("\nKept '{}' as it still has tasks", from.name));
+        }
+        return Err(Error::new("project_merge", &message));
+    }
+
+    match source {
+        MergeSource::Keep => (),
+        MergeSource::Archive => {
+            todoist::archive_project(config, from, true).await?;
+            remove(config, from).await?;
+            message.push_str(&format!("\nArchived '{}'", from.name));
+        }
+        MergeSource::Delete => {
+            delete(config, from).await?;
+            message.push_str(&format!("\nDeleted '{}'", from.name));
+        }
+    }
+
+    Ok(format::green_string(&message))
 }
 
-/// Put dates on all tasks without dates
+/// Put dates on all tasks without dates, stopping at the first failed update when `strict` is set
 pub async fn schedule(
     config: &Config,
     project: &Project,
     filter: TaskFilter,
     skip_recurring: bool,
-    sort: &SortOrder,
+    sort: &SortOptions,
+    smart: bool,
+    strict: bool,
 ) -> Result<String, Error> {
     let tasks = todoist::all_tasks_by_project(config, project, None).await?;
     let tasks = tasks::sort(tasks, config, *sort);
@@ -486,8 +1115,12 @@ pub async fn schedule(
             project.name
         )))
     } else {
-        let handles = stream::iter(filtered_tasks)
-            .then(|task| tasks::spawn_schedule_task(config.clone(), task))
+        let total = filtered_tasks.len();
+        let handles = stream::iter(filtered_tasks.into_iter().enumerate())
+            .then(|(index, task)| {
+                tasks::print_progress(config, index + 1, total, "scheduling", &task);
+                tasks::spawn_schedule_task(config.clone(), task, smart, strict)
+            })
             .try_collect::<Vec<_>>()
             .await?
             .into_iter()
@@ -501,10 +1134,12 @@ pub async fn schedule(
         )))
     }
 }
+/// Put deadlines on all non-recurring tasks without deadlines, stopping at the first failed update when `strict` is set
 pub async fn deadline(
     config: &Config,
     project: &Project,
-    sort: &SortOrder,
+    sort: &SortOptions,
+    strict: bool,
 ) -> Result<String, Error> {
     let tasks = todoist::all_tasks_by_project(config, project, None).await?;
     let tasks = tasks::sort(tasks, config, *sort);
@@ -520,8 +1155,12 @@ pub async fn deadline(
             project.name
         )))
     } else {
-        let handles = stream::iter(filtered_tasks)
-            .then(|task| tasks::spawn_deadline_task(config.clone(), task))
+        let total = filtered_tasks.len();
+        let handles = stream::iter(filtered_tasks.into_iter().enumerate())
+            .then(|(index, task)| {
+                tasks::print_progress(config, index + 1, total, "deadlining", &task);
+                tasks::spawn_deadline_task(config.clone(), task, strict)
+            })
             .try_collect::<Vec<_>>()
             .await?
             .into_iter()
@@ -540,7 +1179,7 @@ pub async fn move_task_to_project(
     config: &mut Config,
     task: Task,
     sections: &[Section],
-) -> Result<JoinHandle<()>, Error> {
+) -> Result<(JoinHandle<()>, EmptyOutcome), Error> {
     let comments = Vec::new();
     let text = task
         .fmt(comments, config, FormatType::Single, false)
@@ -554,13 +1193,24 @@ pub async fn move_task_to_project(
     let selection = input::select("Choose", options, config.mock_select)?;
 
     match selection.as_str() {
-        "Complete" => Ok(tasks::spawn_complete_task(config.clone(), task.id)),
-
-        "Delete" => Ok(tasks::spawn_delete_task(config.clone(), task.id)),
-        "Skip" => Ok(tokio::spawn(async move {})),
+        "Complete" => Ok((
+            tasks::spawn_complete_task(config.clone(), task),
+            EmptyOutcome::Completed,
+        )),
+
+        "Delete" => Ok((
+            tasks::spawn_delete_task(config.clone(), task.id),
+            EmptyOutcome::Deleted,
+        )),
+        "Skip" => Ok((tokio::spawn(async move {}), EmptyOutcome::Skipped)),
         _ => {
             let projects = config.projects().await?;
-            let project = input::select("Select project", projects, config.mock_select)?;
+            let project = input::fuzzy_select(
+                "Select project",
+                projects,
+                config.fuzzy_select(),
+                config.mock_select,
+            )?;
 
             let sections: Vec<Section> = sections
                 .iter()
@@ -568,10 +1218,11 @@ pub async fn move_task_to_project(
                 .cloned()
                 .collect();
 
+            let outcome = EmptyOutcome::Moved(project.name.clone());
             let section_names: Vec<String> = sections.iter().map(|x| x.name.clone()).collect();
             if section_names.is_empty() || config.no_sections.unwrap_or_default() {
                 let config = config.clone();
-                Ok(tokio::spawn(async move {
+                let handle = tokio::spawn(async move {
                     if let Err(e) =
                         todoist::move_task_to_project(&config, &task, &project, false).await
                     {
@@ -580,17 +1231,22 @@ pub async fn move_task_to_project(
                             .send(e)
                             .expect("expected value or result, got None or Err");
                     }
-                }))
+                });
+                Ok((handle, outcome))
             } else {
-                let section_name =
-                    input::select("Select section", section_names, config.mock_select)?;
+                let section_name = input::fuzzy_select(
+                    "Select section",
+                    section_names,
+                    config.fuzzy_select(),
+                    config.mock_select,
+                )?;
                 let section = sections
                     .iter()
                     .find(|x| x.name == section_name.as_str())
                     .expect("Section does not exist")
                     .clone();
                 let config = config.clone();
-                Ok(tokio::spawn(async move {
+                let handle = tokio::spawn(async move {
                     if let Err(e) =
                         todoist::move_task_to_section(&config, &task, &section, false).await
                     {
@@ -599,7 +1255,8 @@ pub async fn move_task_to_project(
                             .send(e)
                             .expect("expected value or result, got None or Err");
                     }
-                }))
+                });
+                Ok((handle, outcome))
             }
         }
     }
@@ -634,24 +1291,102 @@ mod tests {
         assert_eq!(Ok("✓".to_string()), result);
     }
     #[tokio::test]
-    async fn test_list() {
+    async fn test_create_with_template() {
         let mut server = mockito::Server::new_async().await;
-        let mock = server
-            .mock("GET", "/api/v1/projects?limit=200")
+        let project_mock = server
+            .mock("POST", "/api/v1/projects")
+            .match_body(mockito::Matcher::PartialJson(
+                serde_json::json!({"name": "Doomsday"}),
+            ))
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(ResponseFromFile::Projects.read().await)
+            .with_body(ResponseFromFile::Project.read().await)
             .create_async()
             .await;
-
-        let mut config = test::fixtures::config()
-            .await
-            .create()
-            .await
-            .expect("expected value or result, got None or Err")
-            .with_mock_url(server.url())
-            .with_projects(vec![test::fixtures::project()]);
-
+        let section_mock = server
+            .mock("POST", "/api/v1/sections")
+            .match_body(mockito::Matcher::PartialJson(
+                serde_json::json!({"name": "Bread", "project_id": "123"}),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::Section.read().await)
+            .create_async()
+            .await;
+        let unsectioned_mock = server
+            .mock("POST", "/api/v1/tasks/")
+            .match_body(mockito::Matcher::PartialJson(
+                serde_json::json!({"content": "Plan", "priority": 4}),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::Task.read().await)
+            .create_async()
+            .await;
+        let sectioned_mock = server
+            .mock("POST", "/api/v1/tasks/")
+            .match_body(mockito::Matcher::PartialJson(
+                serde_json::json!({"content": "Knead", "section_id": "1234"}),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::Task.read().await)
+            .create_async()
+            .await;
+
+        let mut config = test::fixtures::config()
+            .await
+            .create()
+            .await
+            .expect("expected value or result, got None or Err")
+            .with_mock_url(server.url());
+        let template: ProjectTemplate = serde_json::from_str(
+            r#"{
+                "tasks": [{"content": "Plan", "priority": 4}],
+                "sections": [{"name": "Bread", "tasks": [{"content": "Knead"}]}]
+            }"#,
+        )
+        .expect("template should parse");
+
+        let result = create(
+            &mut config,
+            "Doomsday".to_string(),
+            "",
+            false,
+            Some(&template),
+        )
+        .await;
+        assert_eq!(
+            result,
+            Ok(String::from(
+                "Created project Doomsday and added to config\nCreated 1 section(s) and 2 task(s) from template"
+            ))
+        );
+        project_mock.assert_async().await;
+        section_mock.assert_async().await;
+        unsectioned_mock.assert_async().await;
+        sectioned_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_list() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api/v1/projects?limit=200")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::Projects.read().await)
+            .create_async()
+            .await;
+
+        let mut config = test::fixtures::config()
+            .await
+            .create()
+            .await
+            .expect("expected value or result, got None or Err")
+            .with_mock_url(server.url())
+            .with_projects(vec![test::fixtures::project()]);
+
         config
             .save()
             .await
@@ -663,6 +1398,187 @@ mod tests {
         mock.expect(3);
     }
 
+    #[tokio::test]
+    async fn test_list_with_counts() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api/v1/projects?limit=200")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::Projects.read().await)
+            .create_async()
+            .await;
+        let mock2 = server
+            .mock("GET", "/api/v1/tasks/?project_id=123&limit=200")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::TodayTasks.read().await)
+            .create_async()
+            .await;
+
+        let mut config = test::fixtures::config()
+            .await
+            .with_mock_url(server.url())
+            .with_projects(vec![test::fixtures::project()]);
+
+        let result = list_with_counts(&mut config).await;
+        assert_eq!(result, Ok(String::from("Projects\n - Doomsday (1)")));
+        mock.assert();
+        mock2.assert();
+    }
+
+    #[tokio::test]
+    async fn test_stats() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api/v1/tasks/?project_id=123&limit=200")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::TodayTasks.read().await)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let config = test::fixtures::config().await.with_mock_url(server.url());
+        let project = test::fixtures::project();
+
+        let text = stats(&config, &project, false)
+            .await
+            .expect("stats should be shown");
+        assert_eq!(
+            text,
+            "Stats for 'myproject'\nTasks: 1\nOverdue: 0\nDue today: 1\n - HIGH (P1): 0\n - MEDIUM (P2): 1\n - LOW (P3): 0\n - NONE (P4): 0"
+        );
+
+        let json = stats(&config, &project, true)
+            .await
+            .expect("stats should be shown as json");
+        let json: serde_json::Value = serde_json::from_str(&json).expect("output should be json");
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "id": "123",
+                "name": "myproject",
+                "total": 1,
+                "overdue": 0,
+                "due_today": 1,
+                "priority": {"high": 0, "medium": 1, "low": 0, "none": 0},
+            })
+        );
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn list_json_outputs_project_fields() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api/v1/projects?limit=200")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::Projects.read().await)
+            .create_async()
+            .await;
+        let mut config = test::fixtures::config().await.with_mock_url(server.url());
+
+        let result = list_json(&mut config)
+            .await
+            .expect("listing projects as json should succeed");
+        let value: serde_json::Value =
+            serde_json::from_str(&result).expect("output should be valid json");
+        assert_eq!(
+            value,
+            serde_json::json!([
+                {"id": "123", "name": "Doomsday", "is_favorite": false, "color": "blue"}
+            ])
+        );
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn merge_reports_failed_moves_and_keeps_source() {
+        let mut server = mockito::Server::new_async().await;
+        let move_mock = server
+            .mock("POST", "/api/v1/tasks/6Xqhv4cwxgjwG9w8/move")
+            .with_status(500)
+            .create_async()
+            .await;
+        let archive_mock = server
+            .mock("POST", "/api/v1/projects/123/archive")
+            .expect(0)
+            .create_async()
+            .await;
+
+        let mut config = test::fixtures::config().await.with_mock_url(server.url());
+        let from = test::fixtures::project();
+        let into = Project {
+            id: "456".to_string(),
+            name: "destination".to_string(),
+            ..test::fixtures::project()
+        };
+        let tasks = vec![test::fixtures::today_task().await];
+
+        let error = merge(
+            &mut config,
+            &from,
+            &into,
+            tasks,
+            MergeSource::Archive,
+            false,
+        )
+        .await
+        .expect_err("failed moves should be reported");
+        assert_eq!(error.source, "project_merge");
+        assert!(error.message.starts_with("Moved 0 task(s) from"));
+        assert!(error.message.contains("Failed to move 1 task(s):"));
+        assert!(error.message.contains("as it still has tasks"));
+        move_mock.assert();
+        archive_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn merge_with_strict_stops_at_the_first_failed_move() {
+        let mut server = mockito::Server::new_async().await;
+        let failed_mock = server
+            .mock("POST", "/api/v1/tasks/6Xqhv4cwxgjwG9w8/move")
+            .with_status(500)
+            .create_async()
+            .await;
+        let skipped_mock = server
+            .mock("POST", "/api/v1/tasks/second/move")
+            .expect(0)
+            .create_async()
+            .await;
+
+        let mut config = test::fixtures::config().await.with_mock_url(server.url());
+        config.args.max_concurrency = Some(1);
+        let from = test::fixtures::project();
+        let into = Project {
+            id: "456".to_string(),
+            name: "destination".to_string(),
+            ..test::fixtures::project()
+        };
+        let first = test::fixtures::today_task().await;
+        let second = Task {
+            id: "second".to_string(),
+            ..first.clone()
+        };
+
+        let error = merge(
+            &mut config,
+            &from,
+            &into,
+            vec![first, second],
+            MergeSource::Keep,
+            true,
+        )
+        .await
+        .expect_err("the failed move should stop the merge");
+        assert_eq!(error.source, "strict");
+        assert!(error.message.contains("because of --strict"));
+        failed_mock.assert();
+        skipped_mock.assert();
+    }
+
     #[tokio::test]
     async fn test_get_next_task() {
         let mut server = mockito::Server::new_async().await;
@@ -707,12 +1623,19 @@ mod tests {
             .await
             .expect("expected value or result, got None or Err");
 
-        let response = next_task(config_with_timezone, project)
-            .await
-            .expect("expected value or result, got None or Err");
+        let response = next_task(
+            config_with_timezone,
+            project,
+            &SortOptions::default(),
+            false,
+            true,
+        )
+        .await
+        .expect("expected value or result, got None or Err");
 
         assert!(response.contains("TEST"));
         assert!(response.contains("1 task(s) remaining"));
+        assert!(response.contains("Comments"));
         mock.assert();
         mock2.assert();
     }
@@ -756,6 +1679,47 @@ mod tests {
         assert!(config_keys.contains(&"Doomsday".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_import_selected() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api/v1/projects?limit=200")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::NewProjects.read().await)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let mut config = test::fixtures::config()
+            .await
+            .with_mock_url(server.url())
+            .mock_select(0)
+            .create()
+            .await
+            .expect("config should be created");
+
+        assert_eq!(
+            import_selected(&mut config).await,
+            Ok("Imported: Doomsday".to_string())
+        );
+        let saved = config.reload().await.expect("config should reload");
+        let ids = saved
+            .projects()
+            .await
+            .expect("projects should load")
+            .into_iter()
+            .map(|project| project.id)
+            .collect::<Vec<String>>();
+        assert_eq!(ids, vec!["123".to_string(), "890".to_string()]);
+
+        assert_eq!(
+            import_selected(&mut config).await,
+            Ok("All projects are already in config".to_string())
+        );
+        mock.assert_async().await;
+    }
+
     #[tokio::test]
     async fn test_import_by_project_name() {
         let mut server = mockito::Server::new_async().await;
@@ -830,7 +1794,8 @@ mod tests {
             Err(Error::new(
                 "project_import",
                 "Could not find Todoist project named 'does-not-exist'"
-            ))
+            )
+            .with_kind(ErrorKind::NotFound))
         );
         mock.assert_async().await;
     }
@@ -859,7 +1824,8 @@ mod tests {
             Err(Error::new(
                 "project_import",
                 "Could not find Todoist project with id '999999'"
-            ))
+            )
+            .with_kind(ErrorKind::NotFound))
         );
         mock.assert_async().await;
     }
@@ -893,6 +1859,61 @@ mod tests {
         assert_eq!(projects.is_empty(), true);
     }
 
+    #[tokio::test]
+    async fn diff_remote_lists_stale_and_unimported_projects() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api/v1/projects?limit=200")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::NewProjects.read().await)
+            .expect(2)
+            .create_async()
+            .await;
+        let config = test::fixtures::config().await.with_mock_url(server.url());
+
+        let result = diff_remote(&config, false)
+            .await
+            .expect("diff should succeed");
+        assert_eq!(
+            result,
+            "Stale, in config but not in Todoist (1):\n - myproject\nRun tod project remove --auto to remove them\n\n\
+             Unimported, in Todoist but not in config (1):\n - Doomsday\nRun tod project import to add them"
+        );
+
+        let result = diff_remote(&config, true)
+            .await
+            .expect("diff should succeed");
+        let json: serde_json::Value = serde_json::from_str(&result).expect("output should be JSON");
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "stale": [{"id": "123", "name": "myproject"}],
+                "unimported": [{"id": "890", "name": "Doomsday"}],
+            })
+        );
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn diff_remote_reports_when_config_matches() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api/v1/projects?limit=200")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::Projects.read().await)
+            .create_async()
+            .await;
+        let config = test::fixtures::config().await.with_mock_url(server.url());
+
+        let result = diff_remote(&config, false)
+            .await
+            .expect("diff should succeed");
+        assert_eq!(result, "The projects in config match Todoist");
+        mock.assert_async().await;
+    }
+
     #[tokio::test]
     async fn test_remove_all() {
         let mut config = test::fixtures::config()
@@ -964,25 +1985,191 @@ mod tests {
         let project = binding
             .first()
             .expect("expected value or result, got None or Err");
-        let result = empty(&mut config, project).await;
-        assert_eq!(result, Ok(String::from("Successfully emptied 'myproject'")));
+        let result = empty(&mut config, project, EmptyStrategy::Prompt, None).await;
+        assert_eq!(
+            result,
+            Ok(format::green_string(
+                "Emptied 'myproject'\n - Moved 1 task(s) into 'myproject'"
+            ))
+        );
         mock.expect(2);
         mock2.assert();
         mock3.assert();
         mock5.expect(2);
     }
 
+    #[tokio::test]
+    async fn empty_with_single_and_by_label_strategies() {
+        let mut server = mockito::Server::new_async().await;
+        let tasks_mock = server
+            .mock("GET", "/api/v1/tasks/?project_id=123&limit=200")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::TodayTasks.read().await)
+            .expect(3)
+            .create_async()
+            .await;
+        let move_mock = server
+            .mock("POST", "/api/v1/tasks/6Xqhv4cwxgjwG9w8/move")
+            .match_body(mockito::Matcher::PartialJson(
+                serde_json::json!({"project_id": "890"}),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::Task.read().await)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let mut config = test::fixtures::config().await.with_mock_url(server.url());
+        let project = test::fixtures::project();
+        let doomsday = Project {
+            id: "890".to_string(),
+            name: "Doomsday".to_string(),
+            ..test::fixtures::project()
+        };
+        config.add_project(doomsday.clone());
+
+        let result = empty(
+            &mut config,
+            &project,
+            EmptyStrategy::Single,
+            Some(&doomsday),
+        )
+        .await;
+        assert_eq!(
+            result,
+            Ok(format::green_string(
+                "Emptied 'myproject'\n - Moved 1 task(s) into 'Doomsday'"
+            ))
+        );
+
+        config.label_projects = Some(
+            [("computer".to_string(), "Doomsday".to_string())]
+                .into_iter()
+                .collect(),
+        );
+        let result = empty(&mut config, &project, EmptyStrategy::ByLabel, None).await;
+        assert_eq!(
+            result,
+            Ok(format::green_string(
+                "Emptied 'myproject'\n - Moved 1 task(s) into 'Doomsday'"
+            ))
+        );
+
+        config.label_projects = Some(
+            [("computer".to_string(), "Nowhere".to_string())]
+                .into_iter()
+                .collect(),
+        );
+        let error = empty(&mut config, &project, EmptyStrategy::ByLabel, None)
+            .await
+            .expect_err("unknown project in label_projects should fail");
+        assert!(error.message.contains("'Nowhere'"));
+        tasks_mock.assert();
+        move_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn dedupe_auto_keeps_the_oldest_task() {
+        let mut tasks: serde_json::Value =
+            serde_json::from_str(&ResponseFromFile::TodayTasks.read().await)
+                .expect("tasks response should be valid JSON");
+        let mut older = tasks["results"][0].clone();
+        older["id"] = "older".into();
+        older["content"] = "  test ".into();
+        older["added_at"] = "2020-01-01T00:00:00Z".into();
+        tasks["results"]
+            .as_array_mut()
+            .expect("results should be an array")
+            .push(older);
+
+        let mut server = mockito::Server::new_async().await;
+        let tasks_mock = server
+            .mock("GET", "/api/v1/tasks/?project_id=123&limit=200")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(tasks.to_string())
+            .create_async()
+            .await;
+        let delete_mock = server
+            .mock("DELETE", "/api/v1/tasks/6Xqhv4cwxgjwG9w8")
+            .with_status(204)
+            .create_async()
+            .await;
+
+        let config = test::fixtures::config().await.with_mock_url(server.url());
+        let result = dedupe(&config, &test::fixtures::project(), true, true).await;
+        assert_eq!(
+            result,
+            Ok(format::green_string(
+                "Resolved 1 duplicate(s) in 'myproject', the extra copies were deleted"
+            ))
+        );
+        tasks_mock.assert();
+        delete_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn duplicate_groups_ignores_case_and_whitespace() {
+        let today = test::fixtures::today_task().await;
+        let groups = duplicate_groups(vec![
+            Task {
+                id: "1".into(),
+                content: "Buy milk".into(),
+                ..today.clone()
+            },
+            Task {
+                id: "2".into(),
+                content: "Call mum".into(),
+                ..today.clone()
+            },
+            Task {
+                id: "3".into(),
+                content: " buy MILK ".into(),
+                ..today.clone()
+            },
+        ]);
+        let ids = groups
+            .iter()
+            .map(|group| {
+                group
+                    .iter()
+                    .map(|task| task.id.as_str())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec![vec!["1", "3"]]);
+    }
+
+    #[test]
+    fn empty_summary_counts_each_destination() {
+        let project = test::fixtures::project();
+        let outcomes = [
+            EmptyOutcome::Skipped,
+            EmptyOutcome::Moved("work".to_string()),
+            EmptyOutcome::Completed,
+            EmptyOutcome::Moved("work".to_string()),
+        ];
+        assert_eq!(
+            empty_summary(&project, &outcomes),
+            "Emptied 'myproject'\n - Moved 2 task(s) into 'work'\n - Completed 1 task(s)\n - Left 1 task(s) in 'myproject'"
+        );
+    }
+
     #[tokio::test]
     async fn test_move_task_to_project() {
         let mut config = test::fixtures::config().await.mock_select(2);
         let task = test::fixtures::today_task().await;
         let sections: Vec<Section> = Vec::new();
 
-        move_task_to_project(&mut config, task, &sections)
+        let (handle, outcome) = move_task_to_project(&mut config, task, &sections)
             .await
-            .expect("expected value or result, got None or Err")
+            .expect("expected value or result, got None or Err");
+        handle
             .await
             .expect("expected value or result, got None or Err");
+        assert_eq!(outcome, EmptyOutcome::Skipped);
     }
 
     #[tokio::test]
@@ -1008,7 +2195,7 @@ mod tests {
             .first()
             .expect("expected value or result, got None or Err");
 
-        let result = edit_task(&config, project);
+        let result = edit_task(&config, project, false);
         assert_eq!(result.await, Ok("Finished editing task".to_string()));
         mock.assert();
     }
@@ -1043,6 +2230,78 @@ mod tests {
         mock.assert_async().await;
     }
     #[tokio::test]
+    async fn test_favorite_updates_todoist_and_config() {
+        let mut server = mockito::Server::new_async().await;
+        let response = ResponseFromFile::Project
+            .read()
+            .await
+            .replace("\"is_favorite\": false", "\"is_favorite\": true");
+        let mock = server
+            .mock("POST", "/api/v1/projects/123")
+            .match_body(mockito::Matcher::Json(
+                serde_json::json!({"is_favorite": true}),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(response)
+            .create_async()
+            .await;
+
+        let mut config = test::fixtures::config()
+            .await
+            .with_mock_url(server.url())
+            .create()
+            .await
+            .expect("expected value or result, got None or Err");
+        let project = test::fixtures::project();
+
+        let result = favorite(&mut config, &project, true).await;
+        assert_eq!(
+            result,
+            Ok("Project 'Doomsday' is now a favorite".to_string())
+        );
+        mock.assert_async().await;
+
+        let projects = config
+            .projects()
+            .await
+            .expect("expected value or result, got None or Err");
+        assert_eq!(projects.len(), 1);
+        assert!(projects.iter().all(|project| project.is_favorite));
+    }
+    #[tokio::test]
+    async fn test_unfavorite_reports_new_state() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/api/v1/projects/123")
+            .match_body(mockito::Matcher::Json(
+                serde_json::json!({"is_favorite": false}),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::Project.read().await)
+            .create_async()
+            .await;
+
+        let mut config = test::fixtures::config()
+            .await
+            .with_mock_url(server.url())
+            .create()
+            .await
+            .expect("expected value or result, got None or Err");
+        let project = Project {
+            is_favorite: true,
+            ..test::fixtures::project()
+        };
+
+        let result = favorite(&mut config, &project, false).await;
+        assert_eq!(
+            result,
+            Ok("Project 'Doomsday' is no longer a favorite".to_string())
+        );
+        mock.assert_async().await;
+    }
+    #[tokio::test]
     async fn test_schedule() {
         let mut server = mockito::Server::new_async().await;
         let mock = server
@@ -1081,8 +2340,16 @@ mod tests {
         let project = binding
             .first()
             .expect("expected value or result, got None or Err");
-        let sort = &SortOrder::Value;
-        let result = schedule(&config, project, TaskFilter::Unscheduled, false, sort);
+        let sort = &SortOptions::default();
+        let result = schedule(
+            &config,
+            project,
+            TaskFilter::Unscheduled,
+            false,
+            sort,
+            false,
+            false,
+        );
         assert_eq!(
             result.await,
             Ok("Successfully scheduled tasks in 'myproject'".to_string())
@@ -1097,7 +2364,15 @@ mod tests {
         let project = binding
             .first()
             .expect("expected value or result, got None or Err");
-        let result = schedule(&config, project, TaskFilter::Overdue, false, sort);
+        let result = schedule(
+            &config,
+            project,
+            TaskFilter::Overdue,
+            false,
+            sort,
+            false,
+            false,
+        );
         assert_eq!(
             result.await,
             Ok("No tasks to schedule in 'myproject'".to_string())
@@ -1112,13 +2387,29 @@ mod tests {
         let project = binding
             .first()
             .expect("expected value or result, got None or Err");
-        let result = schedule(&config, project, TaskFilter::Unscheduled, false, sort);
+        let result = schedule(
+            &config,
+            project,
+            TaskFilter::Unscheduled,
+            false,
+            sort,
+            false,
+            false,
+        );
         assert_eq!(
             result.await,
             Ok("Successfully scheduled tasks in 'myproject'".to_string())
         );
 
-        let result = schedule(&config, project, TaskFilter::Unscheduled, true, sort);
+        let result = schedule(
+            &config,
+            project,
+            TaskFilter::Unscheduled,
+            true,
+            sort,
+            false,
+            false,
+        );
         assert_eq!(
             result.await,
             Ok("Successfully scheduled tasks in 'myproject'".to_string())
@@ -1167,8 +2458,8 @@ mod tests {
         let project = binding
             .first()
             .expect("expected value or result, got None or Err");
-        let sort = &SortOrder::Value;
-        let result = deadline(&config, project, sort);
+        let sort = &SortOptions::default();
+        let result = deadline(&config, project, sort, false);
         assert_eq!(
             result.await,
             Ok("Successfully deadlined tasks in 'myproject'".to_string())
@@ -1183,13 +2474,13 @@ mod tests {
         let project = binding
             .first()
             .expect("expected value or result, got None or Err");
-        let result = deadline(&config, project, sort);
+        let result = deadline(&config, project, sort, false);
         assert_eq!(
             result.await,
             Ok("Successfully deadlined tasks in 'myproject'".to_string())
         );
 
-        let result = deadline(&config, project, sort);
+        let result = deadline(&config, project, sort, false);
         assert_eq!(
             result.await,
             Ok("Successfully deadlined tasks in 'myproject'".to_string())
@@ -21,6 +21,24 @@ pub static DATETIME_REGEX: LazyLock<Regex> = LazyLock::new(|| {
         .expect("invalid DATETIME_REGEX pattern YYYY-MM-DD HH:MM")
 });
 
+/// Matches the start of a numeric date such as "2024-01" or "2024/1", used to catch malformed dates
+pub static NUMERIC_DATE_PREFIX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\d{4}[-/.]\d").expect("invalid NUMERIC_DATE_PREFIX pattern"));
+
+/// Matches offsets in days or weeks such as "3d" or "2 weeks", first capture group is the number and second the unit
+pub static DAYS_OFFSET_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(\d+)\s*(d|day|days|w|wk|wks|week|weeks)$")
+        .expect("invalid DAYS_OFFSET_REGEX pattern")
+});
+
+/// Matches durations such as "1h30m", "2 hours" or "45 min", first capture group is hours and second is minutes
+pub static DURATION_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^(?:(\d+)\s*(?:h|hr|hrs|hour|hours))?\s*(?:(\d+)\s*(?:m|min|mins|minute|minutes))?$",
+    )
+    .expect("invalid DURATION_REGEX pattern")
+});
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -55,6 +73,18 @@ mod tests {
         assert!(!DATETIME_REGEX.is_match(""));
     }
 
+    #[test]
+    fn test_duration_regex_captures_hours_and_minutes() {
+        let caps = DURATION_REGEX
+            .captures("1h 30m")
+            .expect("should match duration");
+        assert_eq!(&caps[1], "1");
+        assert_eq!(&caps[2], "30");
+        assert!(DURATION_REGEX.is_match("45 min"));
+        assert!(DURATION_REGEX.is_match("2 hours"));
+        assert!(!DURATION_REGEX.is_match("soon"));
+    }
+
     #[test]
     fn test_markdown_link_matches_valid() {
         let text = "[Google](https://google.com)";
@@ -1,5 +1,11 @@
-use crate::{config::Config, errors::Error, input, projects::Project, todoist};
-use futures::future;
+use crate::{
+    config::Config,
+    errors::{Error, ErrorKind},
+    format, input,
+    projects::Project,
+    todoist,
+};
+use futures::{StreamExt, future, stream};
 use serde::Deserialize;
 
 // Projects are split into sections
@@ -40,15 +46,14 @@ impl SectionResponse {
 }
 
 // Fetch all sections for all projects
-pub async fn all_sections(config: &mut Config) -> Result<Vec<Section>, Error> {
+pub async fn all_sections(config: &Config) -> Result<Vec<Section>, Error> {
     let projects = config.projects().await?;
 
-    let var = future::join_all(
-        projects
-            .iter()
-            .map(|project| todoist::all_sections_by_project(config, project, None)),
-    )
-    .await;
+    let var = stream::iter(&projects)
+        .map(|project| todoist::all_sections_by_project(config, project, None))
+        .buffered(config.max_concurrency())
+        .collect::<Vec<_>>()
+        .await;
 
     let sections = var.into_iter().filter_map(Result::ok).flatten().collect();
     Ok(sections)
@@ -56,12 +61,22 @@ pub async fn all_sections(config: &mut Config) -> Result<Vec<Section>, Error> {
 
 pub async fn select_section(config: &Config, project: &Project) -> Result<Option<Section>, Error> {
     let sections = todoist::all_sections_by_project(config, project, None).await?;
+    pick_section(config, &sections)
+}
+
+/// Prompts for one of the sections, with "No section" offered first. None when there are no sections
+pub fn pick_section(config: &Config, sections: &[Section]) -> Result<Option<Section>, Error> {
     let mut section_names: Vec<String> = sections.iter().map(|x| x.name.clone()).collect();
     if section_names.is_empty() {
         Ok(None)
     } else {
         section_names.insert(0, "No section".to_string());
-        let section_name = input::select(input::SECTION, section_names, config.mock_select)?;
+        let section_name = input::fuzzy_select(
+            input::SECTION,
+            section_names,
+            config.fuzzy_select(),
+            config.mock_select,
+        )?;
 
         let section = sections
             .iter()
@@ -71,6 +86,80 @@ pub async fn select_section(config: &Config, project: &Project) -> Result<Option
     }
 }
 
+/// Finds a section of the project by name, ignoring case
+pub async fn find_section(
+    config: &Config,
+    project: &Project,
+    name: &str,
+) -> Result<Section, Error> {
+    let sections = todoist::all_sections_by_project(config, project, None).await?;
+    if let Some(section) = sections
+        .iter()
+        .find(|section| section.name.eq_ignore_ascii_case(name))
+    {
+        return Ok(section.clone());
+    }
+
+    let available = if sections.is_empty() {
+        String::from("it has no sections")
+    } else {
+        let names = sections
+            .iter()
+            .map(|section| section.name.as_str())
+            .collect::<Vec<&str>>()
+            .join(", ");
+        format!("available sections are {names}")
+    };
+    let message = format!(
+        "No section named '{name}' in '{}', {available}",
+        project.name
+    );
+    Err(Error::new("find_section", &message).with_kind(ErrorKind::NotFound))
+}
+
+/// Lists the sections of a project in order with how many tasks each has, or as a JSON array when `json` is set
+pub async fn list(config: &Config, project: &Project, json: bool) -> Result<String, Error> {
+    let (sections, tasks) = future::try_join(
+        todoist::all_sections_by_project(config, project, None),
+        todoist::all_tasks_by_project(config, project, None),
+    )
+    .await?;
+    let mut sections = sections
+        .into_iter()
+        .map(|section| {
+            let count = tasks
+                .iter()
+                .filter(|task| task.section_id.as_ref() == Some(&section.id))
+                .count();
+            (section, count)
+        })
+        .collect::<Vec<(Section, usize)>>();
+    sections.sort_by_key(|(section, _)| section.section_order);
+
+    if json {
+        let sections = sections
+            .iter()
+            .map(|(section, count)| {
+                serde_json::json!({
+                    "id": section.id,
+                    "name": section.name,
+                    "task_count": count,
+                })
+            })
+            .collect::<Vec<_>>();
+        return Ok(serde_json::to_string_pretty(&sections)?);
+    }
+
+    if sections.is_empty() {
+        return Ok(format!("No sections in '{}'", project.name));
+    }
+    let mut buffer = format::green_string(&format!("Sections in '{}'", project.name));
+    for (section, count) in sections {
+        buffer.push_str(&format!("\n - {} ({count})", section.name));
+    }
+    Ok(buffer)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -167,4 +256,79 @@ mod tests {
         assert_eq!(expected, result);
         mock.assert();
     }
+
+    #[tokio::test]
+    async fn test_find_section() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api/v1/sections?project_id=123&limit=200")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::Sections.read().await)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let config = test::fixtures::config().await.with_mock_url(server.url());
+        let project = test::fixtures::project();
+
+        let section = find_section(&config, &project, "bread")
+            .await
+            .expect("section should be found");
+        assert_eq!(section.id, "1234");
+
+        let error = find_section(&config, &project, "Milk")
+            .await
+            .expect_err("missing section should be an error");
+        assert_eq!(
+            error.message,
+            "No section named 'Milk' in 'myproject', available sections are Bread"
+        );
+        assert_eq!(error.kind, ErrorKind::NotFound);
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_list() {
+        let mut server = mockito::Server::new_async().await;
+        let sections_mock = server
+            .mock("GET", "/api/v1/sections?project_id=123&limit=200")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::Sections.read().await)
+            .expect(2)
+            .create_async()
+            .await;
+        let tasks = ResponseFromFile::TodayTasks
+            .read()
+            .await
+            .replace(r#""section_id": null"#, r#""section_id": "1234""#);
+        let tasks_mock = server
+            .mock("GET", "/api/v1/tasks/?project_id=123&limit=200")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(tasks)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let config = test::fixtures::config().await.with_mock_url(server.url());
+        let project = test::fixtures::project();
+
+        let text = list(&config, &project, false)
+            .await
+            .expect("sections should be listed");
+        assert_eq!(text, "Sections in 'myproject'\n - Bread (1)");
+
+        let json = list(&config, &project, true)
+            .await
+            .expect("sections should be listed as json");
+        let json: serde_json::Value = serde_json::from_str(&json).expect("output should be json");
+        assert_eq!(
+            json,
+            serde_json::json!([{"id": "1234", "name": "Bread", "task_count": 1}])
+        );
+        sections_mock.assert();
+        tasks_mock.assert();
+    }
 }
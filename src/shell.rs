@@ -14,6 +14,17 @@ pub enum Shell {
     Elvish,
 }
 
+/// Aliases emitted by `tod shell aliases`, as (alias, arguments to tod)
+const ALIASES: [(&str, &str); 7] = [
+    ("tq", "task quick-add -c"),
+    ("tn", "task next"),
+    ("to", "task complete"),
+    ("tv", "list view"),
+    ("tp", "list process"),
+    ("ts", "list schedule"),
+    ("tpl", "project list"),
+];
+
 /// Starts a local system command in the background and reports failures through tx.
 /// Suppresses stdout so command output cannot interfere with terminal rendering.
 pub fn execute_command(command: &str, tx: UnboundedSender<Error>) {
@@ -66,6 +77,25 @@ fn shell_command(command: &str) -> Command {
     }
 }
 
+/// Opens text in the user's editor through a temporary file and returns what was saved.
+/// Uses $VISUAL or $EDITOR, falling back to a platform default such as nano or notepad.
+pub fn edit_text(text: &str) -> Result<String, Error> {
+    if cfg!(test) {
+        return Ok(text.to_string());
+    }
+    edit::edit(text).map_err(|e| {
+        Error::new(
+            "editor",
+            &format!("The editor did not exit successfully, nothing was changed: {e}"),
+        )
+    })
+}
+
+/// Opens a URL with the platform opener, i.e. the default browser
+pub fn open_url(url: &str) -> Result<(), Error> {
+    open::that(url).map_err(|e| Error::new("open", &format!("Could not open {url}: {e}")))
+}
+
 pub(crate) fn generate_completions(shell: Shell) {
     let mut cli = Cli::command();
 
@@ -93,6 +123,24 @@ pub(crate) fn generate_completions(shell: Shell) {
     }
 }
 
+/// Alias definitions for the most common commands, one per line in the syntax of the shell
+pub(crate) fn generate_aliases(shell: Shell) -> String {
+    ALIASES
+        .iter()
+        .map(|(alias, args)| {
+            let command = format!("{LOWERCASE_NAME} {args}");
+            match shell {
+                Shell::Bash | Shell::Zsh => format!("alias {alias}='{command}'"),
+                Shell::Fish => format!("alias {alias} '{command}'"),
+                // PowerShell aliases cannot include arguments, so use functions instead
+                Shell::PowerShell => format!("function {alias} {{ {command} @args }}"),
+                Shell::Elvish => format!("fn {alias} {{|@args| {command} $@args }}"),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -229,4 +277,17 @@ mod tests {
             .failure()
             .stderr(contains("No such").or(contains("cannot find")));
     }
+
+    #[test]
+    fn test_generate_aliases_uses_shell_syntax() {
+        let bash = generate_aliases(Shell::Bash);
+        assert!(bash.contains("alias tq='tod task quick-add -c'"));
+        assert_eq!(bash.lines().count(), ALIASES.len());
+
+        let fish = generate_aliases(Shell::Fish);
+        assert!(fish.contains("alias tq 'tod task quick-add -c'"));
+
+        let powershell = generate_aliases(Shell::PowerShell);
+        assert!(powershell.contains("function tn { tod task next @args }"));
+    }
 }
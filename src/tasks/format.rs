@@ -3,12 +3,22 @@ use terminal_size::{Width, terminal_size};
 use super::{DateTimeInfo, Duration, Task, Unit, priority};
 use crate::{comments::Comment, config::Config, errors::Error, format, projects::Project, time};
 
+/// Tasks overdue by at least this many days have their age shown in red
+const OVERDUE_AGE_RED_DAYS: i64 = 7;
+
 pub fn content(task: &Task, config: &Config) -> String {
+    // Stripped before styling so that any widths worked out from the text match what is printed
+    let text = if config.ascii_content() {
+        format::ascii_only(&task.content)
+    } else {
+        task.content.clone()
+    };
     let content = match task.priority {
-        priority::Priority::Low => format::blue_string(&task.content),
-        priority::Priority::Medium => format::yellow_string(&task.content),
-        priority::Priority::High => format::red_string(&task.content),
-        priority::Priority::None => format::normal_string(&task.content),
+        _ if task.checked => format::strikethrough_string(&text),
+        priority::Priority::Low => format::blue_string(&text),
+        priority::Priority::Medium => format::yellow_string(&text),
+        priority::Priority::High => format::red_string(&text),
+        priority::Priority::None => format::normal_string(&text),
     };
 
     format::maybe_format_text(&content, config)
@@ -38,7 +48,7 @@ pub fn labels(task: &Task) -> String {
     format!(" {} {}", format::purple_string("@"), task.labels.join(" "))
 }
 
-pub fn due(task: &Task, config: &Config, buffer: &str) -> String {
+pub fn due(task: &Task, config: &Config, buffer: &str, show_age: bool) -> String {
     let due_icon = format::purple_string("!");
     let recurring_icon = format::purple_string("↻");
 
@@ -55,7 +65,9 @@ pub fn due(task: &Task, config: &Config, buffer: &str) -> String {
             };
             let date_string = time::date_to_string(*date, config).unwrap_or_default();
 
-            format!("\n{buffer}{due_icon} {date_string}{recurring_icon}")
+            let age = overdue_age(task, config, show_age);
+
+            format!("\n{buffer}{due_icon} {date_string}{age}{recurring_icon}")
         }
         Ok(DateTimeInfo::DateTime {
             datetime,
@@ -85,13 +97,49 @@ pub fn due(task: &Task, config: &Config, buffer: &str) -> String {
                 }) => format!(" for {amount} min"),
             };
 
-            format!("\n{buffer}{due_icon} {datetime_string}{duration_string}{recurring_icon}")
+            let age = overdue_age(task, config, show_age);
+
+            format!("\n{buffer}{due_icon} {datetime_string}{duration_string}{age}{recurring_icon}")
         }
         Ok(DateTimeInfo::NoDateTime) => String::new(),
         Err(e) => e.to_string(),
     }
 }
 
+/// Duration from `list timebox` as "[30m]", "[1h30m]" or "[2d]", empty when the task has none
+pub fn duration(task: &Task) -> String {
+    let text = match task.duration {
+        None => return String::new(),
+        Some(Duration {
+            amount,
+            unit: Unit::Day,
+        }) => format!("{amount}d"),
+        Some(Duration {
+            amount,
+            unit: Unit::Minute,
+        }) => match (amount / 60, amount % 60) {
+            (0, minutes) => format!("{minutes}m"),
+            (hours, 0) => format!("{hours}h"),
+            (hours, minutes) => format!("{hours}h{minutes}m"),
+        },
+    };
+    format::cyan_string(&format!("[{text}]"))
+}
+
+/// Days overdue as " (3d overdue)" when `show_age` is set, from `list view --show-age`. Red once it is a week or more
+fn overdue_age(task: &Task, config: &Config, show_age: bool) -> String {
+    if !show_age {
+        return String::new();
+    }
+    match task.days_overdue(config) {
+        Some(days) if days >= OVERDUE_AGE_RED_DAYS => {
+            format!(" {}", format::red_string(&format!("({days}d overdue)")))
+        }
+        Some(days) => format!(" {}", format::yellow_string(&format!("({days}d overdue)"))),
+        None => String::new(),
+    }
+}
+
 pub fn number_comments(quantity: usize) -> String {
     let comment_icon = format::purple_string("★");
     if quantity == 1 {
@@ -100,9 +148,14 @@ pub fn number_comments(quantity: usize) -> String {
 
     format!("\n{comment_icon} {quantity} comments")
 }
+/// The task's page in the Todoist web app
+pub fn task_url(task_id: &str) -> String {
+    format!("https://app.todoist.com/app/task/{task_id}")
+}
+
 /// Returns a hyperlink-formatted URL formatted as "[link]" for a given task ID if hyperlinks are enabled in the config.
 pub fn maybe_format_task_id(task_id: &str, config: &Config) -> String {
-    let url = format!("https://app.todoist.com/app/task/{task_id}");
+    let url = task_url(task_id);
     if format::hyperlinks_disabled(config) {
         url
     } else {
@@ -221,6 +274,20 @@ mod tests {
     use pretty_assertions::assert_eq;
     use supports_hyperlinks::Stream;
 
+    #[tokio::test]
+    async fn content_is_ascii_only_when_asked() {
+        let task = Task {
+            content: "🔥 Café “launch” 🚀".to_string(),
+            ..test::fixtures::today_task().await
+        };
+        let mut config = Config::default();
+        config.disable_links = true;
+        assert_eq!(content(&task, &config), "🔥 Café “launch” 🚀");
+
+        config.ascii = Some(true);
+        assert_eq!(content(&task, &config), "Cafe \"launch\"");
+    }
+
     #[test]
     fn test_task_url_enabled() {
         let config = Config::default();
@@ -367,7 +434,7 @@ mod tests {
             due: None,
             ..test::fixtures::today_task().await
         };
-        assert_eq!(due(&task, &config, ""), "");
+        assert_eq!(due(&task, &config, "", false), "");
     }
 
     #[test]
@@ -398,6 +465,37 @@ mod tests {
         assert!(not_found.contains("Project not in config"));
     }
 
+    #[tokio::test]
+    async fn test_overdue_age() {
+        let config = test::fixtures::config().await;
+        let base_task = test::fixtures::today_task().await;
+        let task_due = |date: &str| Task {
+            due: Some(DateInfo {
+                date: date.to_string(),
+                is_recurring: false,
+                string: String::new(),
+                lang: "en".to_string(),
+                timezone: None,
+            }),
+            ..base_task.clone()
+        };
+
+        // Fixed time is 2025-05-10 03:00 in America/Vancouver
+        let late_evening = task_due("2025-05-09T05:00:00Z");
+        assert_eq!(overdue_age(&late_evening, &config, false), String::new());
+
+        assert_eq!(late_evening.days_overdue(&config), Some(2));
+        assert_eq!(
+            overdue_age(&late_evening, &config, true),
+            format!(" {}", format::yellow_string("(2d overdue)"))
+        );
+        assert_eq!(
+            overdue_age(&task_due("2025-05-01"), &config, true),
+            format!(" {}", format::red_string("(9d overdue)"))
+        );
+        assert_eq!(overdue_age(&task_due("2025-05-10"), &config, true), "");
+    }
+
     #[tokio::test]
     async fn test_due_various_branches() {
         let config = test::fixtures::config().await;
@@ -414,7 +512,7 @@ mod tests {
             }),
             ..base_task.clone()
         };
-        let out = due(&task_date, &config, "");
+        let out = due(&task_date, &config, "", false);
         assert!(out.contains("!"));
 
         // Datetime due with duration and recurring flag (→ DateTimeInfo::DateTime)
@@ -432,8 +530,27 @@ mod tests {
             }),
             ..base_task
         };
-        let out = due(&task_datetime, &config, "");
+        let out = due(&task_datetime, &config, "", false);
         assert!(out.contains("for 2 days"));
         assert!(out.contains("↻"));
     }
+
+    #[tokio::test]
+    async fn duration_is_shown_in_brackets() {
+        let base_task = test::fixtures::today_task().await;
+        let with_duration = |amount, unit| Task {
+            duration: Some(Duration { amount, unit }),
+            ..base_task.clone()
+        };
+
+        assert_eq!(duration(&with_duration(30, Unit::Minute)), "[30m]");
+        assert_eq!(duration(&with_duration(120, Unit::Minute)), "[2h]");
+        assert_eq!(duration(&with_duration(90, Unit::Minute)), "[1h30m]");
+        assert_eq!(duration(&with_duration(2, Unit::Day)), "[2d]");
+        let without_duration = Task {
+            duration: None,
+            ..base_task
+        };
+        assert_eq!(duration(&without_duration), "");
+    }
 }
@@ -1,6 +1,11 @@
 use chrono::DateTime;
 use chrono::NaiveDate;
 use chrono_tz::Tz;
+use futures::future;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use regex::{Regex, RegexBuilder};
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 use std::collections::HashSet;
@@ -18,9 +23,10 @@ use crate::errors::Error;
 use crate::input::CONTENT;
 use crate::input::DATE_AND_TIME;
 use crate::input::DateTimeInput;
+use crate::labels::Label;
 use crate::projects;
 use crate::tasks::priority::Priority;
-use crate::{input, time, todoist};
+use crate::{input, regexes, shell, time, todoist};
 
 #[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
 pub struct Task {
@@ -73,6 +79,20 @@ impl TaskResponse {
     }
 }
 
+/// Response from the completed tasks endpoint, which lists them under `items`
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct CompletedTaskResponse {
+    pub items: Vec<Task>,
+    pub next_cursor: Option<String>,
+}
+
+impl CompletedTaskResponse {
+    pub fn from_json(json: &str) -> Result<CompletedTaskResponse, Error> {
+        let response: CompletedTaskResponse = serde_json::from_str(json)?;
+        Ok(response)
+    }
+}
+
 // Update task_attributes fn when adding here
 #[derive(Eq, PartialEq)]
 pub enum TaskAttribute {
@@ -164,14 +184,18 @@ struct Body {
 
 #[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
 pub enum Unit {
-    #[serde(rename(deserialize = "minute"))]
+    // Todoist sends lowercase units, the config file stores the next task with the variant names
+    #[serde(rename(deserialize = "minute"), alias = "Minute")]
     Minute,
-    #[serde(rename(deserialize = "day"))]
+    #[serde(rename(deserialize = "day"), alias = "Day")]
     Day,
 }
 
 pub enum FormatType {
-    List,
+    /// One line per part of the task, with days overdue when `show_age` is set
+    List {
+        show_age: bool,
+    },
     Single,
 }
 
@@ -189,14 +213,20 @@ enum DateTimeInfo {
     },
 }
 
-#[derive(clap::ValueEnum, Debug, Copy, Clone)]
+#[derive(clap::ValueEnum, Debug, Copy, Clone, Default)]
 pub enum SortOrder {
     /// Sort by Tod's configured sort order
+    #[default]
+    #[value(alias = "priority")]
     Value,
     /// Sort by datetime only
     Datetime,
     /// Leave Todoist's default sorting in place
     Todoist,
+    /// Shuffle the tasks, use --seed to get the same order again
+    Random,
+    /// Sort by content alphabetically, ignoring case
+    Alpha,
 }
 
 impl std::fmt::Display for SortOrder {
@@ -205,11 +235,73 @@ impl std::fmt::Display for SortOrder {
             SortOrder::Value => write!(f, "value"),
             SortOrder::Todoist => write!(f, "todoist"),
             SortOrder::Datetime => write!(f, "datetime"),
+            SortOrder::Random => write!(f, "random"),
+            SortOrder::Alpha => write!(f, "alpha"),
+        }
+    }
+}
+
+/// How a list of tasks is ordered, from `--sort`, `--seed` and `--reverse`
+#[derive(Debug, Copy, Clone, Default)]
+pub struct SortOptions {
+    pub order: SortOrder,
+    /// Seed for `SortOrder::Random`, a new one is picked on every run when None
+    pub seed: Option<u64>,
+    /// Flip the sorted list, i.e. lowest priority first
+    pub reverse: bool,
+}
+
+impl From<SortOrder> for SortOptions {
+    fn from(order: SortOrder) -> Self {
+        SortOptions {
+            order,
+            ..SortOptions::default()
         }
     }
 }
 
+/// A part of a task that `list view --columns` can show, in the order given
+#[derive(clap::ValueEnum, Debug, Copy, Clone, Eq, PartialEq)]
+pub enum TaskColumn {
+    Priority,
+    Content,
+    Description,
+    Due,
+    Duration,
+    Labels,
+    Project,
+}
+
 impl Task {
+    /// Formats a task on a single line, showing only the given columns in their order
+    pub async fn fmt_columns(
+        &self,
+        config: &Config,
+        columns: &[TaskColumn],
+        show_age: bool,
+    ) -> Result<String, Error> {
+        let mut parts = Vec::new();
+        for column in columns {
+            let part = match column {
+                TaskColumn::Priority => self.priority.symbol(config),
+                TaskColumn::Content => format::content(self, config),
+                TaskColumn::Description => self.description.lines().collect::<Vec<_>>().join(" "),
+                TaskColumn::Due => format::due(self, config, "", show_age),
+                TaskColumn::Duration => format::duration(self),
+                TaskColumn::Labels if self.labels.is_empty() => String::new(),
+                TaskColumn::Labels => format::labels(self),
+                TaskColumn::Project => format::project(self, config, "").await?,
+            };
+            let part = part.trim();
+            if !part.is_empty() {
+                parts.push(part.to_string());
+            }
+        }
+        let prefix = if self.checked { "- [x] " } else { "- " };
+
+        Ok(format!("{prefix}{}", parts.join(" ")))
+    }
+
     pub async fn fmt(
         &self,
         comments: Vec<Comment>,
@@ -219,7 +311,7 @@ impl Task {
     ) -> Result<String, Error> {
         let content = format::content(self, config);
         let buffer = match format {
-            FormatType::List => "  ".into(),
+            FormatType::List { .. } => "  ".into(),
             FormatType::Single => String::new(),
         };
 
@@ -236,9 +328,14 @@ impl Task {
         // Format_task_id returns the same format if urls are disabled
         let url = format::maybe_format_task_id(&self.id, config);
 
-        let due = format::due(self, config, &buffer);
+        let show_age = matches!(format, FormatType::List { show_age: true });
+        let due = format::due(self, config, &buffer, show_age);
         let prefix = match format {
-            FormatType::List => "- ".into(),
+            FormatType::List { .. } if self.checked => "- [x] ".into(),
+            FormatType::List { .. } => match self.priority.symbol(config).as_str() {
+                "" => "- ".into(),
+                symbol => format!("- {symbol} "),
+            },
             FormatType::Single => String::new(),
         };
 
@@ -337,6 +434,15 @@ impl Task {
         self.due.is_none()
     }
 
+    /// The day the task is due on, in the task's timezone
+    pub fn due_date(&self, config: &Config) -> Option<NaiveDate> {
+        match self.datetimeinfo(config) {
+            Ok(DateTimeInfo::Date { date, .. }) => Some(date),
+            Ok(DateTimeInfo::DateTime { datetime, .. }) => Some(datetime.date_naive()),
+            Ok(DateTimeInfo::NoDateTime) | Err(_) => None,
+        }
+    }
+
     // Returns true if the datetime is today and there is a time
     pub fn is_today(&self, config: &Config) -> Result<bool, Error> {
         let boolean = match self.datetimeinfo(config) {
@@ -373,12 +479,34 @@ impl Task {
         Ok(boolean)
     }
 
+    /// Number of days the task is overdue by, None when it isn't overdue
+    pub fn days_overdue(&self, config: &Config) -> Option<i64> {
+        let days = match self.datetimeinfo(config) {
+            Ok(DateTimeInfo::Date { date, .. }) => {
+                -time::naive_date_days_in_future(date, config).ok()?
+            }
+            Ok(DateTimeInfo::DateTime { datetime, .. }) => {
+                time::days_since_datetime(&datetime, config).ok()?
+            }
+            Ok(DateTimeInfo::NoDateTime) | Err(_) => return None,
+        };
+        (days > 0).then_some(days)
+    }
+
     /// Returns true if it is a recurring task
     pub fn is_recurring(&self) -> bool {
         self.due
             .as_ref()
             .is_some_and(|DateInfo { is_recurring, .. }| *is_recurring)
     }
+
+    /// Returns the recurrence pattern, i.e. "every monday", if it is a recurring task
+    pub fn recurrence(&self) -> Option<&str> {
+        self.due
+            .as_ref()
+            .filter(|DateInfo { is_recurring, .. }| *is_recurring)
+            .map(|DateInfo { string, .. }| string.as_str())
+    }
 }
 
 pub fn filter_not_in_future(tasks: Vec<Task>, config: &Config) -> Vec<Task> {
@@ -392,11 +520,252 @@ pub fn filter_not_in_future(tasks: Vec<Task>, config: &Config) -> Vec<Task> {
         .collect()
 }
 
-pub fn sort(tasks: Vec<Task>, config: &Config, sort: SortOrder) -> Vec<Task> {
-    match sort {
+/// Prints which task a bulk operation is on to stderr, i.e. "[3/27] scheduling: Buy groceries"
+pub fn print_progress(config: &Config, position: usize, total: usize, action: &str, task: &Task) {
+    if let Some(line) = progress_line(config, position, total, action, task) {
+        eprintln!("{line}");
+    }
+}
+
+/// Hidden with --quiet, and when selections are mocked in tests
+fn progress_line(
+    config: &Config,
+    position: usize,
+    total: usize,
+    action: &str,
+    task: &Task,
+) -> Option<String> {
+    if config.args.quiet || config.mock_select.is_some() {
+        None
+    } else {
+        Some(format!("[{position}/{total}] {action}: {}", task.content))
+    }
+}
+
+/// Drops tasks that were passed over with `task next --skip`
+pub fn filter_skipped(tasks: Vec<Task>, config: &Config) -> Vec<Task> {
+    let skipped_tasks = config.skipped_tasks();
+    tasks
+        .into_iter()
+        .filter(|task| !skipped_tasks.contains(&task.id))
+        .collect()
+}
+
+/// Answers for `list timebox` from `--default-duration` and `--skip-date`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TimeboxDefaults {
+    /// Minutes filled in at the duration prompt, accepted with Enter
+    pub duration: Option<u32>,
+    /// Only set the duration of tasks that already have a due date, without asking for a date or time
+    pub skip_date: bool,
+}
+
+/// Due date bounds from `list view --due-after` and `--due-before`, neither bound is inclusive
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DueRange {
+    pub after: Option<NaiveDate>,
+    pub before: Option<NaiveDate>,
+}
+
+/// Drops tasks due outside of the range, and tasks without a due date when either bound is set
+pub fn filter_due_range(tasks: Vec<Task>, config: &Config, range: DueRange) -> Vec<Task> {
+    if range == DueRange::default() {
+        return tasks;
+    }
+
+    tasks
+        .into_iter()
+        .filter(|task| {
+            task.due_date(config).is_some_and(|date| {
+                range.after.is_none_or(|after| date > after)
+                    && range.before.is_none_or(|before| date < before)
+            })
+        })
+        .collect()
+}
+
+/// Drops tasks below the minimum priority, keeps all tasks when there is no minimum
+pub fn filter_min_priority(tasks: Vec<Task>, min_priority: Option<Priority>) -> Vec<Task> {
+    match min_priority {
+        None => tasks,
+        Some(min_priority) => tasks
+            .into_iter()
+            .filter(|task| task.priority.to_integer() >= min_priority.to_integer())
+            .collect(),
+    }
+}
+
+/// Keeps only recurring tasks when `recurring` is true, only one-off tasks when it is false, and every task when it is None
+pub fn filter_recurring(tasks: Vec<Task>, recurring: Option<bool>) -> Vec<Task> {
+    match recurring {
+        None => tasks,
+        Some(recurring) => tasks
+            .into_iter()
+            .filter(|task| task.is_recurring() == recurring)
+            .collect(),
+    }
+}
+
+/// Keeps tasks whose content or description matches, and every task when there is nothing to match
+pub fn filter_content(tasks: Vec<Task>, content: Option<&Regex>) -> Vec<Task> {
+    match content {
+        None => tasks,
+        Some(content) => tasks
+            .into_iter()
+            .filter(|task| content.is_match(&task.content) || content.is_match(&task.description))
+            .collect(),
+    }
+}
+
+/// Matches text anywhere in a task ignoring case, or as a regular expression when regex is set
+pub fn content_matcher(text: &str, regex: bool) -> Result<Regex, Error> {
+    let pattern = if regex {
+        text.to_string()
+    } else {
+        regex::escape(text)
+    };
+    RegexBuilder::new(&pattern)
+        .case_insensitive(!regex)
+        .build()
+        .map_err(|e| {
+            Error::new(
+                "content_contains",
+                &format!("Could not compile '{text}' as a regular expression: {e}"),
+            )
+        })
+}
+
+/// Sorts tasks by the sort order, then flips them when `reverse` is set
+pub fn sort(tasks: Vec<Task>, config: &Config, sort: SortOptions) -> Vec<Task> {
+    let mut tasks = match sort.order {
         SortOrder::Value => sort_by_value(tasks, config),
         SortOrder::Datetime => sort_by_datetime(tasks, config),
         SortOrder::Todoist => tasks,
+        SortOrder::Random => shuffle(tasks, sort.seed.unwrap_or_else(rand::random)),
+        SortOrder::Alpha => sort_by_alpha(tasks),
+    };
+    if sort.reverse {
+        tasks.reverse();
+    }
+    tasks
+}
+
+/// Shuffles the tasks, the same seed always gives the same order
+fn shuffle(mut tasks: Vec<Task>, seed: u64) -> Vec<Task> {
+    tasks.shuffle(&mut StdRng::seed_from_u64(seed));
+    tasks
+}
+
+/// Opens the task in the browser when `open` is set, from `task next --open`.
+/// Never opens in tests, with a mocked API or with --quiet.
+pub fn maybe_open(config: &Config, task: &Task, open: bool) -> Result<(), Error> {
+    if !open || config.args.quiet || config.mock_url.is_some() || cfg!(test) {
+        return Ok(());
+    }
+    shell::open_url(&format::task_url(&task.id))
+}
+
+/// Markdown links in the content and then the description of a task, as (text, url)
+pub fn markdown_links(task: &Task) -> Vec<(String, String)> {
+    links_in([&task.content, &task.description])
+}
+
+/// Markdown links in comments, oldest comment first, as (text, url)
+pub fn comment_links(comments: &[Comment]) -> Vec<(String, String)> {
+    links_in(comments.iter().map(|comment| &comment.content))
+}
+
+fn links_in<'a>(texts: impl IntoIterator<Item = &'a String>) -> Vec<(String, String)> {
+    texts
+        .into_iter()
+        .flat_map(|text| regexes::MARKDOWN_LINK.captures_iter(text))
+        .map(|captures| (captures[1].to_string(), captures[2].to_string()))
+        .collect()
+}
+
+/// Lists the markdown links in comments to show beneath a task, empty when there are none
+pub fn format_comment_links(comments: &[Comment]) -> String {
+    let links = comment_links(comments);
+    if links.is_empty() {
+        return String::new();
+    }
+    let lines = links
+        .iter()
+        .map(|(text, url)| format!(" - {text}: {}", crate::format::cyan_string(url)))
+        .collect::<Vec<String>>()
+        .join("\n");
+    format!("Links in comments\n{lines}\n")
+}
+
+/// Splits text written in a file or editor into content from the first line and description from the rest.
+/// None when the first line is empty.
+pub fn split_content(text: &str) -> Option<(String, String)> {
+    let (first_line, rest) = text.split_once('\n').unwrap_or((text, ""));
+    let content = first_line.trim();
+    if content.is_empty() {
+        return None;
+    }
+    let description = rest.trim_start_matches(['\r', '\n']).trim_end();
+
+    Some((content.to_string(), description.to_string()))
+}
+
+/// Prompts for which attributes of a task to change and updates each of them,
+/// or opens the content and description in $EDITOR when `editor` is set
+pub async fn edit(config: &Config, task: &Task, editor: bool) -> Result<String, Error> {
+    if editor {
+        return edit_in_editor(config, task).await;
+    }
+    let options = edit_task_attributes();
+
+    let selections = input::multi_select(input::ATTRIBUTES, options, config.mock_select)?;
+
+    if selections.is_empty() {
+        return Err(Error::new("edit_task", "Nothing selected"));
+    }
+
+    let mut handles = Vec::new();
+    for attribute in selections {
+        // Stops the inputs from rolling over each other in terminal
+        println!();
+        if let Some(handle) = update_task(config, task, &attribute).await? {
+            handles.push(handle);
+        }
+    }
+
+    future::join_all(handles).await;
+    Ok("Finished editing task".into())
+}
+
+/// Opens the content and description of a task in $EDITOR and saves whichever of them changed
+async fn edit_in_editor(config: &Config, task: &Task) -> Result<String, Error> {
+    let text = if task.description.is_empty() {
+        task.content.clone()
+    } else {
+        format!("{}\n\n{}", task.content, task.description)
+    };
+    let edited = shell::edit_text(&text)?;
+    let Some((content, description)) = split_content(&edited) else {
+        return Err(Error::new(
+            "edit_task",
+            "The editor was closed without any task content, nothing was changed",
+        ));
+    };
+
+    let mut changed = false;
+    if content != task.content {
+        todoist::update_task_content(config, &task.id, &content, true).await?;
+        changed = true;
+    }
+    if description != task.description {
+        todoist::update_task_description(config, &task.id, &description, true).await?;
+        changed = true;
+    }
+
+    if changed {
+        Ok("Finished editing task".into())
+    } else {
+        Ok("Nothing changed".into())
     }
 }
 
@@ -409,7 +778,11 @@ pub async fn update_task(
         TaskAttribute::Content => {
             let value = task.content.as_str();
 
-            let new_value = input::string_with_default("Enter new content:", value)?;
+            let new_value = if config.use_editor() {
+                edited_line(value)?
+            } else {
+                input::string_with_default("Enter new content:", value)?
+            };
 
             if *value == new_value {
                 Ok(None)
@@ -421,7 +794,11 @@ pub async fn update_task(
         TaskAttribute::Description => {
             let value = task.description.as_str();
 
-            let new_value = input::string_with_default("Enter a new description:", value)?;
+            let new_value = if config.use_editor() {
+                shell::edit_text(value)?.trim_end().to_string()
+            } else {
+                input::string_with_default("Enter a new description:", value)?
+            };
 
             if *value == new_value {
                 Ok(None)
@@ -443,8 +820,8 @@ pub async fn update_task(
                 Ok(Some(handle))
             }
         }
-        TaskAttribute::Due => spawn_schedule_task(config.clone(), task.clone()).await,
-        TaskAttribute::Deadline => spawn_deadline_task(config.clone(), task.clone()).await,
+        TaskAttribute::Due => spawn_schedule_task(config.clone(), task.clone(), false, false).await,
+        TaskAttribute::Deadline => spawn_deadline_task(config.clone(), task.clone(), false).await,
         TaskAttribute::Labels => {
             let label_string = input::string(
                 "Enter labels separated by spaces:",
@@ -462,22 +839,41 @@ pub async fn update_task(
     }
 }
 
+/// Edits the content of a task in $EDITOR, only the first line is kept
+fn edited_line(value: &str) -> Result<String, Error> {
+    let edited = shell::edit_text(value)?;
+    match split_content(&edited) {
+        Some((content, _)) => Ok(content),
+        None => Err(Error::new(
+            "edit_task",
+            "The editor was closed without any task content, nothing was changed",
+        )),
+    }
+}
+
 pub async fn label_task(
     config: &Config,
     task: Task,
-    labels: &[String],
+    labels: &[Label],
 ) -> Result<JoinHandle<()>, Error> {
     let comments = Vec::new();
     let text = task.fmt(comments, config, FormatType::Single, true).await?;
     println!("{text}");
-    let mut options = labels.to_vec();
+    // Options are shown in the label's color, so map the selection back to the label by position
+    let mut options = labels.iter().map(ToString::to_string).collect::<Vec<_>>();
     options.push(input::SKIP.to_string());
-    let label = input::select("Select label", options, config.mock_select)?;
+    let selection = input::select("Select label", options.clone(), config.mock_select)?;
+    let label = options
+        .iter()
+        .position(|option| *option == selection)
+        .and_then(|index| labels.get(index))
+        .map(|label| label.name.clone());
 
     let config = config.clone();
     Ok(tokio::spawn(async move {
-        if label.as_str() == input::SKIP {
-        } else if let Err(e) = todoist::add_task_label(&config, &task, label, false).await {
+        if let Some(label) = label
+            && let Err(e) = todoist::add_task_label(&config, &task, label, false).await
+        {
             config
                 .tx()
                 .send(e)
@@ -516,7 +912,7 @@ pub async fn process_task(
     match selection.as_str() {
         input::COMPLETE => {
             reloaded_config.save().await.expect("Could not save config");
-            Ok(Some(spawn_complete_task(reloaded_config, task.id)))
+            Ok(Some(spawn_complete_task(reloaded_config, task)))
         }
         input::DELETE => Ok(Some(spawn_delete_task(config.clone(), task.id))),
         input::COMMENT => {
@@ -556,6 +952,7 @@ pub async fn timebox_task(
     task: Task,
     task_count: &mut i32,
     with_project: bool,
+    defaults: TimeboxDefaults,
 ) -> Result<Option<JoinHandle<()>>, Error> {
     let options = [
         input::TIMEBOX,
@@ -575,8 +972,17 @@ pub async fn timebox_task(
     *task_count -= 1;
     let selection = input::select("Select an option", options, config.mock_select)?;
     match selection.as_str() {
+        input::TIMEBOX if defaults.skip_date && task.due.is_some() => {
+            let duration = get_duration(config, defaults.duration)?;
+
+            Ok(Some(spawn_update_task_duration(
+                config.clone(),
+                task,
+                duration,
+            )))
+        }
         input::TIMEBOX => {
-            let (due_string, duration) = get_timebox(config, &task)?;
+            let (due_string, duration) = get_timebox(config, &task, defaults.duration)?;
 
             Ok(Some(spawn_update_task_due(
                 config.clone(),
@@ -587,7 +993,7 @@ pub async fn timebox_task(
         }
 
         input::DELETE => Ok(Some(spawn_delete_task(config.clone(), task.id))),
-        input::COMPLETE => Ok(Some(spawn_complete_task(config.clone(), task.id))),
+        input::COMPLETE => Ok(Some(spawn_complete_task(config.clone(), task))),
         input::SKIP => {
             // Do nothing
             Ok(Some(tokio::spawn(async move {})))
@@ -603,7 +1009,11 @@ pub async fn timebox_task(
 }
 
 /// Returns Date, time and duration for a task, uses the date and time on task if available, otherwise prompts. Always prompts for duration.
-fn get_timebox(config: &Config, task: &Task) -> Result<(String, u32), Error> {
+fn get_timebox(
+    config: &Config,
+    task: &Task,
+    default_duration: Option<u32>,
+) -> Result<(String, u32), Error> {
     let datetime = if let Task {
         due: Some(DateInfo { date, .. }),
         ..
@@ -626,20 +1036,37 @@ fn get_timebox(config: &Config, task: &Task) -> Result<(String, u32), Error> {
         format!("{date} {time}")
     };
 
-    let duration = input::string(input::DURATION, config.mock_string.clone())?;
+    let duration = get_duration(config, default_duration)?;
+
+    Ok((datetime, duration))
+}
 
-    Ok((datetime, duration.parse::<u32>()?))
+/// Prompts for a duration, with the default from `--default-duration` filled in when given
+fn get_duration(config: &Config, default_duration: Option<u32>) -> Result<u32, Error> {
+    match default_duration {
+        Some(minutes) => input::duration_with_default(input::DURATION, minutes),
+        None => input::duration(input::DURATION, config.mock_string.clone()),
+    }
 }
 
+/// Prompts for a new due date, offering quick picks such as Tomorrow first when `smart` is set.
+/// With `strict` the update is awaited, see `start_update`
 pub async fn spawn_schedule_task(
     config: Config,
     task: Task,
+    smart: bool,
+    strict: bool,
 ) -> Result<Option<JoinHandle<()>>, Error> {
     let comments = Vec::new();
     let text = task
         .fmt(comments, &config, FormatType::Single, true)
         .await?;
     println!("{text}");
+    if smart && let Some(date) = select_smart_date(&config)? {
+        let due_string = date.format(time::FORMAT_DATE).to_string();
+        let handle = spawn_update_task_due(config, task, due_string, None);
+        return Ok(Some(handle));
+    }
     let datetime_input = input::datetime(
         config.mock_select,
         config.mock_string.clone(),
@@ -649,24 +1076,40 @@ pub async fn spawn_schedule_task(
     )?;
     match datetime_input {
         input::DateTimeInput::Complete => {
-            let handle = spawn_complete_task(config, task.id);
-            Ok(Some(handle))
+            start_update(config, task, TaskUpdate::Complete, strict).await
         }
         DateTimeInput::Skip => Ok(None),
 
         input::DateTimeInput::Text(due_string) => {
-            let handle = spawn_update_task_due(config, task, due_string, None);
-            Ok(Some(handle))
+            start_update(config, task, TaskUpdate::Due(due_string), strict).await
         }
         input::DateTimeInput::None => {
-            let handle = spawn_update_task_due(config, task, "No date".to_string(), None);
-            Ok(Some(handle))
+            let update = TaskUpdate::Due("No date".to_string());
+            start_update(config, task, update, strict).await
         }
     }
 }
+/// Returns the picked date, or None when the user chose Custom
+fn select_smart_date(config: &Config) -> Result<Option<NaiveDate>, Error> {
+    let smart_dates = time::smart_dates(config)?;
+    let mut options = smart_dates
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<String>>();
+    options.push(input::CUSTOM.to_string());
+
+    let selection = input::select(input::SELECT_DATE, options, config.mock_select)?;
+    Ok(smart_dates
+        .into_iter()
+        .find(|smart_date| smart_date.to_string() == selection)
+        .map(|smart_date| smart_date.date))
+}
+
+/// Prompts for a new deadline. With `strict` the update is awaited, see `start_update`
 pub async fn spawn_deadline_task(
     config: Config,
     task: Task,
+    strict: bool,
 ) -> Result<Option<JoinHandle<()>>, Error> {
     let comments = Vec::new();
     let text = task
@@ -682,26 +1125,74 @@ pub async fn spawn_deadline_task(
     )?;
     match datetime_input {
         input::DateTimeInput::Complete => {
-            let handle = spawn_complete_task(config, task.id);
-            Ok(Some(handle))
+            start_update(config, task, TaskUpdate::Complete, strict).await
         }
         DateTimeInput::Skip => Ok(None),
 
         input::DateTimeInput::Text(date) => {
-            let handle = spawn_update_task_deadline(config, task.id, Some(date));
-            Ok(Some(handle))
+            start_update(config, task, TaskUpdate::Deadline(Some(date)), strict).await
         }
         input::DateTimeInput::None => {
-            let handle = spawn_update_task_deadline(config, task.id, None);
-            Ok(Some(handle))
+            start_update(config, task, TaskUpdate::Deadline(None), strict).await
+        }
+    }
+}
+
+/// A change picked for one task while scheduling or deadlining
+enum TaskUpdate {
+    Complete,
+    Due(String),
+    Deadline(Option<String>),
+}
+
+/// Starts the update in another thread so the next task can be shown straight away, failures are reported at the end.
+/// With `strict`, from --strict, the update is awaited instead, so the first failure stops the command
+async fn start_update(
+    config: Config,
+    task: Task,
+    update: TaskUpdate,
+    strict: bool,
+) -> Result<Option<JoinHandle<()>>, Error> {
+    if !strict {
+        let handle = match update {
+            TaskUpdate::Complete => spawn_complete_task(config, task),
+            TaskUpdate::Due(due_string) => spawn_update_task_due(config, task, due_string, None),
+            TaskUpdate::Deadline(date) => spawn_update_task_deadline(config, task.id, date),
+        };
+        return Ok(Some(handle));
+    }
+
+    let result = match update {
+        TaskUpdate::Complete => todoist::complete_task(&config, &task, false).await,
+        TaskUpdate::Due(due_string) => {
+            todoist::update_task_due_natural_language(&config, &task, due_string, None, false).await
+        }
+        TaskUpdate::Deadline(date) => {
+            todoist::update_task_deadline(&config, &task.id, date, false).await
         }
+    };
+    match result {
+        Ok(_) => Ok(None),
+        Err(e) => Err(stopped_by_strict(&task, &e)),
     }
 }
 
+/// The error that ends a bulk command run with --strict, keeping the kind of the failure for the exit code
+pub fn stopped_by_strict(task: &Task, error: &Error) -> Error {
+    Error::new(
+        "strict",
+        &format!(
+            "Stopped at '{}' because of --strict, tasks handled before it keep their changes\n{}",
+            task.content, error.message
+        ),
+    )
+    .with_kind(error.kind)
+}
+
 /// Completes task inside another thread
-pub fn spawn_complete_task(config: Config, task_id: String) -> JoinHandle<()> {
+pub fn spawn_complete_task(config: Config, task: Task) -> JoinHandle<()> {
     tokio::spawn(async move {
-        if let Err(e) = todoist::complete_task(&config, &task_id, false).await {
+        if let Err(e) = todoist::complete_task(&config, &task, false).await {
             config
                 .tx()
                 .send(e)
@@ -742,6 +1233,18 @@ pub fn spawn_update_task_due(
     })
 }
 
+/// Sets the duration of a task inside another thread
+pub fn spawn_update_task_duration(config: Config, task: Task, minutes: u32) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        if let Err(e) = todoist::update_task_duration(&config, &task, minutes, false).await {
+            config
+                .tx()
+                .send(e)
+                .expect("Failed to send error on task channel");
+        }
+    })
+}
+
 /// creates a reminder inside another thread
 pub fn spawn_create_reminder(config: Config, task: Task, due_string: String) -> JoinHandle<()> {
     tokio::spawn(async move {
@@ -773,7 +1276,8 @@ pub fn spawn_update_task_deadline(
 /// Updates task inside another thread
 pub fn spawn_comment_task(config: Config, task_id: String, task_comment: String) -> JoinHandle<()> {
     tokio::spawn(async move {
-        if let Err(e) = todoist::create_comment(&config, &task_id, &task_comment, false).await {
+        if let Err(e) = todoist::create_comment(&config, &task_id, &task_comment, None, false).await
+        {
             config
                 .tx()
                 .send(e)
@@ -903,6 +1407,12 @@ pub fn sort_by_datetime(mut tasks: Vec<Task>, config: &Config) -> Vec<Task> {
     tasks
 }
 
+/// Case-insensitive by content, ties are broken by id so the order is the same on every run
+pub fn sort_by_alpha(mut tasks: Vec<Task>) -> Vec<Task> {
+    tasks.sort_by_cached_key(|task| (task.content.to_lowercase(), task.id.clone()));
+    tasks
+}
+
 // We don't want to process parent tasks when child tasks are unchecked, or child tasks when they are checked
 // We additionally need to make sure that parent tasks are not in the future
 
@@ -959,10 +1469,12 @@ async fn parent_in_future(task: &Task, task_ids: &HashSet<String>, config: &Conf
     }
 }
 
+/// Prompts for a priority. With `quick_keys` pressing 1-4 sets P1-P4 straight away and any other key opens the full menu
 pub async fn set_priority(
     config: &Config,
     task: Task,
     with_project: bool,
+    quick_keys: bool,
 ) -> Result<JoinHandle<()>, Error> {
     let comments = Vec::new();
     let text = task
@@ -970,13 +1482,24 @@ pub async fn set_priority(
         .await?;
     println!("{text}");
 
-    let options = vec![
-        Priority::None,
-        Priority::Low,
-        Priority::Medium,
-        Priority::High,
-    ];
-    let priority = input::select(input::PRIORITY, options, config.mock_select)?;
+    let quick_priority = if quick_keys {
+        let key = input::key(input::PRIORITY_KEYS, config.mock_string.clone())?;
+        priority_from_key(key)?
+    } else {
+        None
+    };
+    let priority = match quick_priority {
+        Some(priority) => priority,
+        None => {
+            let options = vec![
+                Priority::None,
+                Priority::Low,
+                Priority::Medium,
+                Priority::High,
+            ];
+            input::select(input::PRIORITY, options, config.mock_select)?
+        }
+    };
 
     let config = config.clone();
     Ok(tokio::spawn(async move {
@@ -989,6 +1512,14 @@ pub async fn set_priority(
     }))
 }
 
+/// Maps the keys 1-4 to P1-P4, which are stored the other way around as 4-1
+fn priority_from_key(key: Option<char>) -> Result<Option<Priority>, Error> {
+    match key.and_then(|key| key.to_digit(10)) {
+        Some(digit @ 1..=4) => priority::from_integer(u8::try_from(5 - digit).ok()),
+        _ => Ok(None),
+    }
+}
+
 pub async fn create_reminder(config: &Config, task: Task) -> Result<Option<JoinHandle<()>>, Error> {
     let comments = Vec::new();
     let text = task.fmt(comments, config, FormatType::Single, true).await?;
@@ -1004,7 +1535,7 @@ pub async fn create_reminder(config: &Config, task: Task) -> Result<Option<JoinH
     let config = config.clone();
     match datetime_input {
         input::DateTimeInput::Complete => {
-            let handle = spawn_complete_task(config, task.id);
+            let handle = spawn_complete_task(config, task);
             Ok(Some(handle))
         }
         DateTimeInput::Skip | input::DateTimeInput::None => Ok(None),
@@ -1023,6 +1554,112 @@ mod tests {
     use pretty_assertions::assert_eq;
     use serde_test::{Token, assert_de_tokens};
 
+    #[test]
+    fn split_content_takes_first_line_as_content() {
+        assert_eq!(
+            split_content("  Write the report \n\n## Notes\nmore\n"),
+            Some(("Write the report".to_string(), "## Notes\nmore".to_string()))
+        );
+        assert_eq!(
+            split_content("Only content"),
+            Some(("Only content".to_string(), String::new()))
+        );
+        assert_eq!(split_content("\nno content"), None);
+        assert_eq!(split_content(""), None);
+    }
+
+    #[test]
+    fn comment_links_lists_markdown_links_in_order() {
+        let comments = vec![
+            Comment {
+                content: "See [the spec](https://example.com/spec)".to_string(),
+                ..test::fixtures::comment()
+            },
+            test::fixtures::comment(),
+            Comment {
+                content: "[Notes](https://example.com/notes) and [more](https://example.com/more)"
+                    .to_string(),
+                ..test::fixtures::comment()
+            },
+        ];
+
+        assert_eq!(
+            comment_links(&comments),
+            vec![
+                (
+                    "the spec".to_string(),
+                    "https://example.com/spec".to_string()
+                ),
+                ("Notes".to_string(), "https://example.com/notes".to_string()),
+                ("more".to_string(), "https://example.com/more".to_string()),
+            ]
+        );
+        assert_eq!(format_comment_links(&[test::fixtures::comment()]), "");
+        assert!(format_comment_links(&comments).starts_with("Links in comments\n - the spec: "));
+    }
+
+    #[tokio::test]
+    async fn edit_in_editor_reports_nothing_changed() {
+        let config = test::fixtures::config().await;
+        let task = test::fixtures::today_task().await;
+
+        let result = edit(&config, &task, true).await;
+        assert_eq!(result, Ok("Nothing changed".to_string()));
+    }
+
+    #[tokio::test]
+    async fn maybe_open_never_opens_in_tests() {
+        let config = test::fixtures::config().await;
+        let task = test::fixtures::today_task().await;
+
+        assert_eq!(maybe_open(&config, &task, true), Ok(()));
+        assert_eq!(
+            format::task_url(&task.id),
+            "https://app.todoist.com/app/task/6Xqhv4cwxgjwG9w8"
+        );
+    }
+
+    #[tokio::test]
+    async fn filter_content_matches_text_or_regex() {
+        let today = test::fixtures::today_task().await;
+        let tasks = vec![
+            Task {
+                id: "1".into(),
+                content: "Buy MILK".into(),
+                ..today.clone()
+            },
+            Task {
+                id: "2".into(),
+                content: "Call mum".into(),
+                description: "ask about the milkman".into(),
+                ..today.clone()
+            },
+            Task {
+                id: "3".into(),
+                content: "Walk".into(),
+                ..today.clone()
+            },
+        ];
+        let ids = |tasks: Vec<Task>| tasks.into_iter().map(|task| task.id).collect::<Vec<_>>();
+
+        let matcher = content_matcher("milk", false).expect("text should compile");
+        assert_eq!(
+            ids(filter_content(tasks.clone(), Some(&matcher))),
+            vec!["1", "2"]
+        );
+
+        let matcher = content_matcher("^(Walk|Call)", true).expect("regex should compile");
+        assert_eq!(
+            ids(filter_content(tasks.clone(), Some(&matcher))),
+            vec!["2", "3"]
+        );
+
+        assert_eq!(ids(filter_content(tasks, None)).len(), 3);
+
+        let error = content_matcher("milk(", true).expect_err("unclosed group should fail");
+        assert_eq!(error.source, "content_contains");
+    }
+
     #[test]
     fn unit_deserializes_with_serde_tokens() {
         assert_de_tokens(
@@ -1041,6 +1678,13 @@ mod tests {
         );
     }
 
+    #[test]
+    fn unit_round_trips_through_the_config_file() {
+        let json = serde_json::to_string(&Unit::Minute).expect("unit should serialize");
+        let unit: Unit = serde_json::from_str(&json).expect("unit should deserialize");
+        assert_eq!(unit, Unit::Minute);
+    }
+
     #[tokio::test]
     async fn test_task_from_json_valid() {
         let json = ResponseFromFile::TodayTask.read().await;
@@ -1068,11 +1712,132 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn start_update_with_strict_returns_the_first_failure() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/api/v1/tasks/6Xqhv4cwxgjwG9w8")
+            .with_status(500)
+            .create_async()
+            .await;
+        let config = test::fixtures::config().await.with_mock_url(server.url());
+        let task = test::fixtures::today_task().await;
+
+        let update = TaskUpdate::Due("tomorrow".to_string());
+        let error = start_update(config, task, update, true)
+            .await
+            .expect_err("a failed update should stop the command with --strict");
+        assert_eq!(error.source, "strict");
+        assert!(error.message.contains("because of --strict"));
+        mock.assert_async().await;
+    }
+
     #[tokio::test]
     async fn test_sort_order_display() {
         assert_eq!(SortOrder::Value.to_string(), "value");
         assert_eq!(SortOrder::Datetime.to_string(), "datetime");
         assert_eq!(SortOrder::Todoist.to_string(), "todoist");
+        assert_eq!(SortOrder::Random.to_string(), "random");
+        assert_eq!(SortOrder::Alpha.to_string(), "alpha");
+    }
+
+    #[tokio::test]
+    async fn test_sort_reverse() {
+        let config = test::fixtures::config().await;
+        let task = test::fixtures::today_task().await;
+        let tasks = (0..3)
+            .map(|number| Task {
+                id: number.to_string(),
+                ..task.clone()
+            })
+            .collect::<Vec<Task>>();
+        let ids = |tasks: Vec<Task>| tasks.into_iter().map(|task| task.id).collect::<Vec<_>>();
+
+        assert_eq!(
+            ids(sort(tasks.clone(), &config, SortOrder::Todoist.into())),
+            vec!["0", "1", "2"]
+        );
+        let reversed = SortOptions {
+            reverse: true,
+            ..SortOrder::Todoist.into()
+        };
+        assert_eq!(ids(sort(tasks, &config, reversed)), vec!["2", "1", "0"]);
+    }
+
+    #[tokio::test]
+    async fn test_sort_random_is_repeatable_with_seed() {
+        let config = test::fixtures::config().await;
+        let seeded = |seed| SortOptions {
+            order: SortOrder::Random,
+            seed: Some(seed),
+            reverse: false,
+        };
+        let task = test::fixtures::today_task().await;
+        let tasks = (0..20)
+            .map(|number| Task {
+                id: number.to_string(),
+                ..task.clone()
+            })
+            .collect::<Vec<Task>>();
+        let ids = |tasks: Vec<Task>| tasks.into_iter().map(|task| task.id).collect::<Vec<_>>();
+
+        let first = ids(sort(tasks.clone(), &config, seeded(42)));
+        let second = ids(sort(tasks.clone(), &config, seeded(42)));
+        assert_eq!(first, second);
+        assert_ne!(first, ids(tasks.clone()));
+
+        let mut sorted = first.clone();
+        sorted.sort();
+        let mut expected = ids(tasks.clone());
+        expected.sort();
+        assert_eq!(sorted, expected);
+
+        assert_ne!(ids(sort(tasks, &config, seeded(7))), first);
+    }
+
+    #[test]
+    fn test_sort_order_accepts_priority_alias() {
+        use clap::ValueEnum;
+
+        let sort = SortOrder::from_str("priority", false).expect("priority should parse");
+        assert_matches!(sort, SortOrder::Value);
+        assert_eq!(sort.to_string(), "value");
+    }
+
+    #[tokio::test]
+    async fn test_sort_alpha_ignores_case_and_breaks_ties_by_id() {
+        use clap::ValueEnum;
+
+        let config = test::fixtures::config().await;
+        let task = test::fixtures::today_task().await;
+        let tasks = [
+            ("1", "banana"),
+            ("2", "Apple"),
+            ("4", "cherry"),
+            ("3", "apple"),
+        ]
+        .into_iter()
+        .map(|(id, content)| Task {
+            id: id.into(),
+            content: content.into(),
+            ..task.clone()
+        })
+        .collect::<Vec<Task>>();
+        let sort_order = SortOrder::from_str("alpha", false).expect("alpha should parse");
+
+        let sorted = sort(tasks, &config, sort_order.into())
+            .into_iter()
+            .map(|task| (task.id, task.content))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            sorted,
+            vec![
+                ("2".to_string(), "Apple".to_string()),
+                ("3".to_string(), "apple".to_string()),
+                ("1".to_string(), "banana".to_string()),
+                ("4".to_string(), "cherry".to_string()),
+            ]
+        );
     }
 
     #[tokio::test]
@@ -1084,7 +1849,7 @@ mod tests {
             ..test::fixtures::today_task().await
         };
         let tasks = vec![t1.clone(), t2.clone()];
-        let sorted = sort(tasks.clone(), &config, SortOrder::Todoist);
+        let sorted = sort(tasks.clone(), &config, SortOrder::Todoist.into());
         assert_eq!(sorted, tasks);
     }
 
@@ -1134,6 +1899,136 @@ mod tests {
         assert_eq!(result[0].id, no_date.id);
     }
 
+    #[tokio::test]
+    async fn test_progress_line() {
+        let task = test::fixtures::today_task().await;
+        let mut config = test::fixtures::config().await;
+
+        assert_eq!(
+            progress_line(&config, 3, 27, "scheduling", &task),
+            Some(format!("[3/27] scheduling: {}", task.content))
+        );
+
+        config.args.quiet = true;
+        assert_eq!(progress_line(&config, 3, 27, "scheduling", &task), None);
+
+        let config = test::fixtures::config().await.mock_select(0);
+        assert_eq!(progress_line(&config, 3, 27, "scheduling", &task), None);
+    }
+
+    #[tokio::test]
+    async fn test_filter_due_range() {
+        let config = test::fixtures::config().await;
+        let base = test::fixtures::today_task().await;
+        let task_on = |date: &str, id: &str| Task {
+            id: id.into(),
+            due: Some(DateInfo {
+                date: date.into(),
+                is_recurring: false,
+                lang: "en".into(),
+                string: date.into(),
+                timezone: None,
+            }),
+            ..base.clone()
+        };
+        let early = task_on("2025-01-10", "early");
+        let middle = task_on("2025-01-15T09:00:00Z", "middle");
+        let late = task_on("2025-01-20", "late");
+        let undated = Task {
+            id: "undated".into(),
+            due: None,
+            ..base.clone()
+        };
+        let tasks = vec![early.clone(), middle.clone(), late, undated];
+        let date = |s: &str| NaiveDate::parse_from_str(s, time::FORMAT_DATE).ok();
+
+        assert_eq!(
+            filter_due_range(tasks.clone(), &config, DueRange::default()),
+            tasks
+        );
+        assert_eq!(
+            filter_due_range(
+                tasks.clone(),
+                &config,
+                DueRange {
+                    after: date("2025-01-10"),
+                    before: date("2025-01-20"),
+                }
+            ),
+            vec![middle.clone()]
+        );
+        assert_eq!(
+            filter_due_range(
+                tasks,
+                &config,
+                DueRange {
+                    after: None,
+                    before: date("2025-01-16"),
+                }
+            ),
+            vec![early, middle]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_filter_skipped() {
+        let task = test::fixtures::today_task().await;
+        let other = Task {
+            id: "other-id".into(),
+            ..task.clone()
+        };
+        let config = test::fixtures::config().await.skip_task(&task.id);
+
+        let tasks = filter_skipped(vec![task, other.clone()], &config);
+        assert_eq!(tasks, vec![other]);
+    }
+
+    #[tokio::test]
+    async fn test_filter_min_priority() {
+        let medium = test::fixtures::today_task().await;
+        let high = Task {
+            id: "high-id".into(),
+            priority: Priority::High,
+            ..medium.clone()
+        };
+        let none = Task {
+            id: "none-id".into(),
+            priority: Priority::None,
+            ..medium.clone()
+        };
+        let tasks = vec![medium.clone(), high.clone(), none.clone()];
+
+        let result = filter_min_priority(tasks.clone(), Some(Priority::Medium));
+        assert_eq!(result, vec![medium, high]);
+
+        let result = filter_min_priority(tasks.clone(), None);
+        assert_eq!(result, tasks);
+    }
+
+    #[tokio::test]
+    async fn test_filter_recurring() {
+        let one_off = Task {
+            due: None,
+            ..test::fixtures::today_task().await
+        };
+        let recurring = Task {
+            id: "recurring-id".into(),
+            due: Some(DateInfo {
+                date: "2024-01-01".into(),
+                is_recurring: true,
+                lang: "en".into(),
+                string: "every day".into(),
+                timezone: None,
+            }),
+            ..one_off.clone()
+        };
+        let tasks = vec![one_off.clone(), recurring.clone()];
+
+        assert_eq!(filter_recurring(tasks.clone(), Some(true)), vec![recurring]);
+        assert_eq!(filter_recurring(tasks.clone(), Some(false)), vec![one_off]);
+        assert_eq!(filter_recurring(tasks.clone(), None), tasks);
+    }
+
     #[tokio::test]
     async fn test_is_recurring_true() {
         let task = Task {
@@ -1173,6 +2068,24 @@ mod tests {
         assert!(!task.is_recurring());
     }
 
+    #[tokio::test]
+    async fn test_recurrence() {
+        let recurring = Task {
+            due: Some(DateInfo {
+                date: "2024-01-01".into(),
+                is_recurring: true,
+                lang: "en".into(),
+                string: "every monday".into(),
+                timezone: None,
+            }),
+            ..test::fixtures::today_task().await
+        };
+        assert_eq!(recurring.recurrence(), Some("every monday"));
+
+        let not_recurring = test::fixtures::today_task().await;
+        assert_eq!(not_recurring.recurrence(), None);
+    }
+
     #[tokio::test]
     async fn test_edit_task_attributes_contains_all() {
         let attrs = edit_task_attributes();
@@ -1256,6 +2169,32 @@ mod tests {
         assert!(task_text.contains("Today @ computer"));
     }
 
+    #[tokio::test]
+    async fn fmt_columns_uses_given_columns_and_order() {
+        let config = test::fixtures::config().await;
+        let task = test::fixtures::today_task().await;
+        let task = Task {
+            due: Some(DateInfo {
+                date: time::date_string_today(&config)
+                    .expect("Failed to unwrap date_string_today result in tasks test"),
+                ..task.due.clone().expect("today_task should have a due date")
+            }),
+            ..task
+        };
+
+        let text = task
+            .fmt_columns(&config, &[TaskColumn::Due, TaskColumn::Content], false)
+            .await
+            .expect("columns should format");
+
+        assert!(text.starts_with("- "));
+        assert!(!text.contains('\n'));
+        assert!(!text.contains("computer"));
+        let due = text.find("Today").expect("due should be shown");
+        let content = text.find("TEST").expect("content should be shown");
+        assert!(due < content);
+    }
+
     #[tokio::test]
     async fn datetime_works_with_date() {
         let config = test::fixtures::config().await;
@@ -1771,7 +2710,37 @@ mod tests {
             .mock_select(1)
             .with_mock_url(server.url());
 
-        let future = set_priority(&config, task, false)
+        let future = set_priority(&config, task, false, false)
+            .await
+            .expect("expected value or result, got None or Err");
+
+        tokio::join!(future)
+            .0
+            .expect("expected value or result, got None or Err");
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_set_priority_with_quick_keys() {
+        let task = test::fixtures::today_task().await;
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/api/v1/tasks/6Xqhv4cwxgjwG9w8")
+            .match_body(mockito::Matcher::PartialJson(
+                serde_json::json!({"priority": 4}),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::TodayTask.read().await)
+            .create_async()
+            .await;
+        // No mock_select, so the full menu would panic if it were shown
+        let config = test::fixtures::config()
+            .await
+            .with_mock_string("1")
+            .with_mock_url(server.url());
+
+        let future = set_priority(&config, task, false, true)
             .await
             .expect("expected value or result, got None or Err");
 
@@ -1781,6 +2750,17 @@ mod tests {
         mock.assert();
     }
 
+    #[test]
+    fn test_priority_from_key() {
+        assert_eq!(priority_from_key(Some('1')), Ok(Some(Priority::High)));
+        assert_eq!(priority_from_key(Some('2')), Ok(Some(Priority::Medium)));
+        assert_eq!(priority_from_key(Some('3')), Ok(Some(Priority::Low)));
+        assert_eq!(priority_from_key(Some('4')), Ok(Some(Priority::None)));
+        assert_eq!(priority_from_key(Some('5')), Ok(None));
+        assert_eq!(priority_from_key(Some('x')), Ok(None));
+        assert_eq!(priority_from_key(None), Ok(None));
+    }
+
     #[tokio::test]
     async fn test_process_task() {
         let mut server = mockito::Server::new_async().await;
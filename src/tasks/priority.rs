@@ -1,4 +1,5 @@
-use crate::{errors::Error, format};
+use crate::{config::Config, errors::Error, format};
+use serde::{Deserialize, Serialize};
 use std::fmt::Display;
 
 /// Add to `all_priorities` function if adding another priority
@@ -33,6 +34,48 @@ impl Priority {
             Priority::High => 4,
         }
     }
+
+    /// Symbol shown before the task content in lists, empty when there is none.
+    /// Configured symbols that aren't ASCII are replaced with the ASCII defaults when ASCII output is requested.
+    pub fn symbol(self, config: &Config) -> String {
+        self.symbol_from(config.priority_symbols.as_ref(), config.ascii())
+    }
+
+    fn symbol_from(self, symbols: Option<&PrioritySymbols>, ascii: bool) -> String {
+        let configured = symbols.and_then(|symbols| match self {
+            Priority::None => symbols.none.clone(),
+            Priority::Low => symbols.low.clone(),
+            Priority::Medium => symbols.medium.clone(),
+            Priority::High => symbols.high.clone(),
+        });
+
+        match configured {
+            Some(symbol) if !ascii || symbol.is_ascii() => symbol,
+            _ if ascii => self.default_symbol(true).to_string(),
+            _ => self.default_symbol(false).to_string(),
+        }
+    }
+
+    fn default_symbol(self, ascii: bool) -> &'static str {
+        match (self, ascii) {
+            (Priority::None, _) => "",
+            (Priority::Low, false) => "·",
+            (Priority::Low, true) => ".",
+            (Priority::Medium, _) => "!",
+            (Priority::High, false) => "‼",
+            (Priority::High, true) => "!!",
+        }
+    }
+}
+
+/// Overrides for the symbols shown before task content in lists, keyed by priority
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PrioritySymbols {
+    pub high: Option<String>,
+    pub medium: Option<String>,
+    pub low: Option<String>,
+    pub none: Option<String>,
 }
 
 pub fn from_integer(priority: Option<u8>) -> Result<Option<Priority>, Error> {
@@ -59,6 +102,29 @@ mod tests {
     use super::*;
     use pretty_assertions::assert_eq;
 
+    #[test]
+    fn test_symbol_from() {
+        assert_eq!(Priority::High.symbol_from(None, false), "‼");
+        assert_eq!(Priority::Medium.symbol_from(None, false), "!");
+        assert_eq!(Priority::Low.symbol_from(None, false), "·");
+        assert_eq!(Priority::None.symbol_from(None, false), "");
+        assert_eq!(Priority::High.symbol_from(None, true), "!!");
+        assert_eq!(Priority::Low.symbol_from(None, true), ".");
+
+        let symbols = PrioritySymbols {
+            high: Some("🔥".to_string()),
+            none: Some("-".to_string()),
+            ..PrioritySymbols::default()
+        };
+        assert_eq!(Priority::High.symbol_from(Some(&symbols), false), "🔥");
+        assert_eq!(Priority::None.symbol_from(Some(&symbols), false), "-");
+        assert_eq!(Priority::Low.symbol_from(Some(&symbols), false), "·");
+
+        // Configured symbols that aren't ASCII fall back to the ASCII defaults
+        assert_eq!(Priority::High.symbol_from(Some(&symbols), true), "!!");
+        assert_eq!(Priority::None.symbol_from(Some(&symbols), true), "-");
+    }
+
     #[test]
     fn test_all_priorities() {
         let result = all_priorities();
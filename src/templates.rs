@@ -0,0 +1,246 @@
+//! Project templates for `project create --template`, a JSON file listing the sections and tasks to create in a new project
+//! The file is validated as a whole before anything is created in Todoist
+use crate::errors::Error;
+use crate::tasks::priority::{self, Priority};
+use crate::time;
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::path::Path;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProjectTemplate {
+    /// Tasks that are not in a section
+    pub tasks: Vec<TemplateTask>,
+    pub sections: Vec<TemplateSection>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TemplateSection {
+    pub name: String,
+    #[serde(default)]
+    pub tasks: Vec<TemplateTask>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TemplateTask {
+    pub content: String,
+    #[serde(default)]
+    pub description: String,
+    /// From 1 (without priority) to 4 (highest), same as `task create --priority`
+    pub priority: Option<u8>,
+    /// Date in format YYYY-MM-DD, YYYY-MM-DD HH:MM, or natural language
+    pub due: Option<String>,
+    #[serde(default)]
+    pub labels: Vec<String>,
+}
+
+impl TemplateTask {
+    pub fn priority(&self) -> Result<Priority, Error> {
+        Ok(priority::from_integer(self.priority)?.unwrap_or(Priority::None))
+    }
+}
+
+impl ProjectTemplate {
+    /// Reads and validates a template file, which has to be JSON
+    pub async fn load(path: &Path) -> Result<ProjectTemplate, Error> {
+        if let Some(extension) = path.extension()
+            && !extension.eq_ignore_ascii_case("json")
+        {
+            return Err(Error::new(
+                "project_template",
+                &format!(
+                    "{} is not a JSON file, templates are only read from JSON such as garden.json",
+                    path.display()
+                ),
+            ));
+        }
+        let json = tokio::fs::read_to_string(path).await.map_err(|e| {
+            Error::new(
+                "project_template",
+                &format!("Could not read {}: {e}", path.display()),
+            )
+        })?;
+        let template: ProjectTemplate = serde_json::from_str(&json).map_err(|e| {
+            Error::new(
+                "project_template",
+                &format!("{} is not a valid template: {e}", path.display()),
+            )
+        })?;
+        template.validate()?;
+        Ok(template)
+    }
+
+    /// Checks every section and task so that a bad template fails before the project is created
+    pub fn validate(&self) -> Result<(), Error> {
+        let mut problems = Vec::new();
+        let mut names = HashSet::new();
+        for (index, section) in self.sections.iter().enumerate() {
+            let name = section.name.trim();
+            if name.is_empty() {
+                problems.push(format!("section {} has no name", index + 1));
+            } else if !names.insert(name.to_lowercase()) {
+                problems.push(format!("section '{name}' is listed more than once"));
+            }
+        }
+
+        let all_tasks = self
+            .tasks
+            .iter()
+            .map(|task| ("the project".to_string(), task))
+            .chain(self.sections.iter().flat_map(|section| {
+                section
+                    .tasks
+                    .iter()
+                    .map(|task| (format!("section '{}'", section.name), task))
+            }));
+        for (location, task) in all_tasks {
+            if task.content.trim().is_empty() {
+                problems.push(format!("a task in {location} has no content"));
+                continue;
+            }
+            if let Err(e) = task.priority() {
+                problems.push(format!("task '{}': {}", task.content, e.message));
+            }
+            if let Some(due) = &task.due
+                && let Err(e) = time::validate_due(due)
+            {
+                problems.push(format!("task '{}': {}", task.content, e.message));
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::new(
+                "project_template",
+                &format!("Invalid template:\n- {}", problems.join("\n- ")),
+            ))
+        }
+    }
+
+    pub fn task_count(&self) -> usize {
+        self.tasks.len()
+            + self
+                .sections
+                .iter()
+                .map(|section| section.tasks.len())
+                .sum::<usize>()
+    }
+
+    /// Outline of what would be created, used for --dry-run
+    pub fn preview(&self, project_name: &str) -> String {
+        let mut buffer = format!("Would create project {project_name}");
+        for task in &self.tasks {
+            buffer.push_str(&format!("\n - {}", task.content));
+        }
+        for section in &self.sections {
+            buffer.push_str(&format!("\n {}", section.name));
+            for task in &section.tasks {
+                buffer.push_str(&format!("\n   - {}", task.content));
+            }
+        }
+        buffer.push_str(&format!(
+            "\nwith {} section(s) and {} task(s)",
+            self.sections.len(),
+            self.task_count()
+        ));
+        buffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const TEMPLATE: &str = r#"{
+        "tasks": [{"content": "Write a README", "priority": 4}],
+        "sections": [
+            {"name": "Backlog", "tasks": [{"content": "Collect ideas", "due": "tomorrow", "labels": ["home"]}]},
+            {"name": "Done"}
+        ]
+    }"#;
+
+    #[tokio::test]
+    async fn load_should_read_and_validate_template() {
+        let dir = tempfile::tempdir().expect("temp dir should be created");
+        let path = dir.path().join("template.json");
+        tokio::fs::write(&path, TEMPLATE)
+            .await
+            .expect("template should be written");
+
+        let template = ProjectTemplate::load(&path)
+            .await
+            .expect("template should load");
+        assert_eq!(template.sections.len(), 2);
+        assert_eq!(template.task_count(), 2);
+        assert_eq!(template.tasks[0].priority(), Ok(Priority::High));
+        assert_eq!(template.sections[0].tasks[0].labels, vec!["home"]);
+        assert_eq!(
+            template.preview("Garden"),
+            "Would create project Garden\n - Write a README\n Backlog\n   - Collect ideas\n Done\nwith 2 section(s) and 2 task(s)"
+        );
+    }
+
+    #[tokio::test]
+    async fn load_should_reject_unknown_fields() {
+        let dir = tempfile::tempdir().expect("temp dir should be created");
+        let path = dir.path().join("template.json");
+        tokio::fs::write(&path, r#"{"sections": [{"name": "A", "colour": "red"}]}"#)
+            .await
+            .expect("template should be written");
+
+        let error = ProjectTemplate::load(&path)
+            .await
+            .expect_err("unknown fields should fail");
+        assert!(error.message.contains("is not a valid template"));
+        assert!(error.message.contains("colour"));
+    }
+
+    #[tokio::test]
+    async fn load_should_reject_other_formats() {
+        let dir = tempfile::tempdir().expect("temp dir should be created");
+        let path = dir.path().join("template.yaml");
+        tokio::fs::write(&path, "sections:\n  - name: Backlog\n")
+            .await
+            .expect("template should be written");
+
+        let error = ProjectTemplate::load(&path)
+            .await
+            .expect_err("a yaml template should fail");
+        assert!(error.message.contains("is not a JSON file"));
+    }
+
+    #[test]
+    fn validate_should_list_every_problem() {
+        let template: ProjectTemplate = serde_json::from_str(
+            r#"{
+                "tasks": [{"content": " "}],
+                "sections": [
+                    {"name": "Backlog", "tasks": [{"content": "Plan", "priority": 7}]},
+                    {"name": "backlog"},
+                    {"name": "", "tasks": [{"content": "Ship", "due": "2024-02-30"}]}
+                ]
+            }"#,
+        )
+        .expect("template should parse");
+
+        let error = template.validate().expect_err("template should be invalid");
+        assert!(
+            error
+                .message
+                .contains("a task in the project has no content")
+        );
+        assert!(error.message.contains("task 'Plan': Invalid priority: 7"));
+        assert!(
+            error
+                .message
+                .contains("section 'backlog' is listed more than once")
+        );
+        assert!(error.message.contains("section 3 has no name"));
+        assert!(error.message.contains("task 'Ship'"));
+    }
+}
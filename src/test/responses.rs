@@ -9,6 +9,10 @@ pub enum ResponseFromFile {
     AccessToken,
     /// List of all kinds of comments
     CommentsAllTypes,
+    /// Two people sharing a project
+    Collaborators,
+    /// A task completed today
+    CompletedTasks,
     /// An unscheduled task
     Task,
     TodayTasksWithoutDuration,
@@ -25,6 +29,8 @@ pub enum ResponseFromFile {
     Projects,
     // Has a new ID
     NewProjects,
+    /// The Todoist inbox alongside another project
+    InboxProjects,
     Section,
     Sections,
     Reminder,
@@ -64,6 +70,7 @@ impl ResponseFromFile {
         let replace_with: Vec<(&str, String)> = match self {
             Self::AccessToken
             | Self::CommentsAllTypes
+            | Self::Collaborators
             | Self::Comment
             | Self::Task
             | Self::Section
@@ -75,10 +82,12 @@ impl ResponseFromFile {
             | Self::Reminders
             | Self::Projects
             | Self::NewProjects
+            | Self::InboxProjects
             | Self::User => Vec::new(),
             Self::TodayTask
             | Self::UnscheduledTasks
             | Self::TodayTasksWithoutDuration
+            | Self::CompletedTasks
             | Self::TodayTasks => vec![("INSERTDATE", super::today_date().await)],
             Self::Versions => vec![("INSERTVERSION", VERSION.to_string())],
         };
@@ -1,17 +1,23 @@
 use crate::errors::Error;
+use crate::tasks::DateInfo;
 use crate::{config::Config, regexes};
 
-use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, Utc};
+use chrono::{
+    DateTime, Datelike, Duration, Months, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, Utc,
+    Weekday,
+};
 use chrono_tz::Tz;
+use std::fmt::Display;
 use std::str::FromStr;
 
 pub const FORMAT_DATE: &str = "%Y-%m-%d";
 const FORMAT_TIME: &str = "%H:%M";
 const FORMAT_DATETIME: &str = "%Y-%m-%dT%H:%M:%S";
-const FORMAT_DATETIME_ZULU: &str = "%Y-%m-%dT%H:%M:%SZ";
+pub const FORMAT_DATETIME_ZULU: &str = "%Y-%m-%dT%H:%M:%SZ";
 const FORMAT_DATETIME_LONG: &str = "%Y-%m-%dT%H:%M:%S%.fZ";
 
 pub const FORMAT_DATE_AND_TIME: &str = "%Y-%m-%d %H:%M";
+const DUE_FORMATS: &str = "Accepted formats are YYYY-MM-DD, YYYY-MM-DD HH:MM, or natural language such as 'tomorrow', 'friday at 3pm' or 'every monday'";
 
 #[cfg(test)] //Fixed Time Provider for Testing
 use crate::test_time::FixedTimeProvider;
@@ -82,6 +88,12 @@ pub fn datetime_now(config: &Config) -> Result<DateTime<Tz>, Error> {
     Ok(config.time_provider.now(tz))
 }
 
+/// Returns midnight at the start of a date in the config timezone
+pub fn start_of_day(date: NaiveDate, config: &Config) -> Result<DateTime<Tz>, Error> {
+    let tz = timezone_from_str(&config.get_timezone()?)?;
+    naive_datetime_to_datetime(date.and_time(NaiveTime::MIN), tz)
+}
+
 // Checks if datetime is today
 pub fn datetime_is_today(datetime: DateTime<Tz>, config: &Config) -> Result<bool, Error> {
     is_date_today(datetime.date_naive(), config)
@@ -93,10 +105,10 @@ pub fn datetime_from_str(str: &str, timezone: Tz) -> Result<DateTime<Tz>, Error>
         19 => parse_datetime(str, timezone, FORMAT_DATETIME),
         20 => parse_datetime(str, Tz::UTC, FORMAT_DATETIME_ZULU),
         27 => parse_datetime(str, Tz::UTC, FORMAT_DATETIME_LONG),
-        length => Err(Error {
-            source: "datetime_from_str".to_string(),
-            message: format!("cannot parse {length} length DateTime: {str}"),
-        }),
+        length => Err(Error::new(
+            "datetime_from_str",
+            &format!("cannot parse {length} length DateTime: {str}"),
+        )),
     }
 }
 
@@ -125,6 +137,34 @@ pub fn is_datetime(string: &str) -> bool {
     regexes::DATETIME_REGEX.is_match(string)
 }
 
+/// Checks a due date before it is sent to Todoist. Natural language is left for Todoist to parse,
+/// but anything that starts like a numeric date must be a real date in YYYY-MM-DD form
+pub fn validate_due(due: &str) -> Result<(), Error> {
+    let due = due.trim();
+    let invalid = || {
+        Error::new(
+            "validate_due",
+            &format!("Invalid due date '{due}'\n{DUE_FORMATS}"),
+        )
+    };
+
+    if !due.chars().any(char::is_alphanumeric) {
+        return Err(invalid());
+    }
+
+    let first_word = due.split_whitespace().next().unwrap_or_default();
+    if regexes::NUMERIC_DATE_PREFIX.is_match(first_word) {
+        if !is_date(first_word) || NaiveDate::parse_from_str(first_word, FORMAT_DATE).is_err() {
+            return Err(invalid());
+        }
+        if is_datetime(due) && NaiveDateTime::parse_from_str(due, FORMAT_DATE_AND_TIME).is_err() {
+            return Err(invalid());
+        }
+    }
+
+    Ok(())
+}
+
 // ----------- DATE FUNCTIONS --------------
 
 /// Parses a date string into a `NaiveDate` - The string can be in the format YYYY-MM-DD or YYYY-MM-DD HH:MM or YYYY-MM-DDTHH:MM:SS or YYYY-MM-DDTHH:MM:SSZ. Timezone is used to convert the date to UTC. If the string is not in one of these formats, an error is returned.
@@ -190,6 +230,65 @@ pub fn naive_date_days_in_future(date: NaiveDate, config: &Config) -> Result<i64
     let duration: Duration = date - naive_date_today(config)?;
     Ok(duration.num_days())
 }
+
+/// Whole days since a due datetime, counting calendar days in the configured timezone
+pub fn days_since_datetime(datetime: &DateTime<Tz>, config: &Config) -> Result<i64, Error> {
+    let tz = timezone_from_str(&config.get_timezone()?)?;
+    let date = datetime.with_timezone(&tz).date_naive();
+    Ok(-naive_date_days_in_future(date, config)?)
+}
+
+/// A suggested due date offered when scheduling with --smart
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SmartDate {
+    pub label: &'static str,
+    pub date: NaiveDate,
+}
+
+impl Display for SmartDate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.label, self.date.format("%a %Y-%m-%d"))
+    }
+}
+
+/// Returns Today, Tomorrow, the start of next week and This Weekend, relative to today in the config timezone.
+/// Weeks start on week_start from config.
+pub fn smart_dates(config: &Config) -> Result<Vec<SmartDate>, Error> {
+    Ok(smart_dates_from(
+        naive_date_today(config)?,
+        config.week_start()?,
+    ))
+}
+
+fn smart_dates_from(today: NaiveDate, week_start: Weekday) -> Vec<SmartDate> {
+    let (days_into_week, saturday, next_week_label) = match week_start {
+        Weekday::Sun => (today.weekday().num_days_from_sunday(), 6, "Next Sunday"),
+        _ => (today.weekday().num_days_from_monday(), 5, "Next Monday"),
+    };
+    let days_into_week = i64::from(days_into_week);
+    let next_week = today + Duration::days(7 - days_into_week);
+    // Saturday, or today when it is already the weekend
+    let weekend = today + Duration::days((saturday - days_into_week).max(0));
+
+    vec![
+        SmartDate {
+            label: "Today",
+            date: today,
+        },
+        SmartDate {
+            label: "Tomorrow",
+            date: today + Duration::days(1),
+        },
+        SmartDate {
+            label: next_week_label,
+            date: next_week,
+        },
+        SmartDate {
+            label: "This Weekend",
+            date: weekend,
+        },
+    ]
+}
 // ----------- STRING FUNCTIONS --------------
 
 /// Return today's date in format 2021-09-16
@@ -201,9 +300,93 @@ pub fn date_string_today(config: &Config) -> Result<String, Error> {
     Ok(today.format(FORMAT_DATE).to_string())
 }
 
+/// Parses the start of a window into the past, i.e. "2025-05-01", "today", "yesterday", "3d", "7 days ago" or "last 2 weeks".
+/// Todoist only returns completed tasks from up to 3 months at a time, so anything earlier is an error unless
+/// `batched` is set because `--batch-size` splits the window into smaller requests. A future date is always an error.
+pub fn since_date(string: &str, config: &Config, batched: bool) -> Result<NaiveDate, Error> {
+    let today = naive_date_today(config)?;
+    let string = string.trim().to_lowercase();
+    let invalid = || {
+        Error::new(
+            "since_date",
+            &format!(
+                "'{string}' is not a valid --since, use YYYY-MM-DD, today, yesterday, or a number of days or weeks back such as 7 days ago or last 2 weeks"
+            ),
+        )
+    };
+
+    let date = match string.as_str() {
+        "today" => today,
+        "yesterday" => today - Duration::days(1),
+        _ if is_date(&string) => NaiveDate::parse_from_str(&string, FORMAT_DATE)?,
+        _ => {
+            let offset = string
+                .strip_prefix("last ")
+                .or_else(|| string.strip_suffix(" ago"))
+                .unwrap_or(&string)
+                .trim();
+            let captures = regexes::DAYS_OFFSET_REGEX
+                .captures(offset)
+                .ok_or_else(invalid)?;
+            let number = captures[1].parse::<i64>().map_err(|_| invalid())?;
+            let days = if captures[2].starts_with('w') {
+                number.checked_mul(7).ok_or_else(invalid)?
+            } else {
+                number
+            };
+            let offset = TimeDelta::try_days(days).ok_or_else(invalid)?;
+            today.checked_sub_signed(offset).ok_or_else(invalid)?
+        }
+    };
+
+    if date > today {
+        return Err(Error::new(
+            "since_date",
+            &format!("--since {date} is in the future"),
+        ));
+    }
+    let earliest = today
+        .checked_sub_months(Months::new(3))
+        .ok_or_else(invalid)?;
+    if date < earliest && !batched {
+        return Err(Error::new(
+            "since_date",
+            &format!(
+                "--since {date} is too far back, Todoist only returns tasks completed in the last 3 months, since {earliest}. Add --batch-size to go further back"
+            ),
+        ));
+    }
+    Ok(date)
+}
+
+/// Splits the time between since and until into windows of at most `days` days, oldest first.
+/// Without `days` the whole time is one window
+pub fn split_into_windows(
+    since: DateTime<Tz>,
+    until: DateTime<Tz>,
+    days: Option<u32>,
+) -> Vec<(DateTime<Tz>, DateTime<Tz>)> {
+    let Some(days) = days.filter(|days| *days > 0) else {
+        return vec![(since, until)];
+    };
+    let step = Duration::days(i64::from(days));
+    let mut windows = Vec::new();
+    let mut start = since;
+    loop {
+        let end = (start + step).min(until);
+        windows.push((start, end));
+        if end >= until {
+            return windows;
+        }
+        start = end;
+    }
+}
+
 // Formats a date to a string
 pub fn date_to_string(date: NaiveDate, config: &Config) -> Result<String, Error> {
-    if is_date_today(date, config)? {
+    if config.iso_dates() {
+        Ok(date.format(FORMAT_DATE).to_string())
+    } else if is_date_today(date, config)? {
         Ok("Today".into())
     } else {
         Ok(date.format(FORMAT_DATE).to_string())
@@ -214,15 +397,204 @@ pub fn date_to_string(date: NaiveDate, config: &Config) -> Result<String, Error>
 pub fn datetime_to_string(datetime: &DateTime<Tz>, config: &Config) -> Result<String, Error> {
     let timezone = config.get_timezone()?;
     let tz = timezone_from_str(&timezone)?;
-    if datetime_is_today(*datetime, config)? {
+    if config.iso_dates() {
+        Ok(datetime.with_timezone(&tz).to_rfc3339())
+    } else if datetime_is_today(*datetime, config)? {
         Ok(datetime.with_timezone(&tz).format(FORMAT_TIME).to_string())
     } else {
         Ok(datetime.with_timezone(&tz).to_string())
     }
 }
 
+// ----------- DURATION FUNCTIONS --------------
+
+/// Converts a duration such as "90", "1h30m" or "45 min" into minutes
+pub fn duration_to_minutes(string: &str) -> Result<u32, Error> {
+    let string = string.trim().to_lowercase();
+    let invalid = || {
+        Error::new(
+            "duration_to_minutes",
+            &format!(
+                "'{string}' is not a valid duration, use minutes or hours and minutes, i.e. 45, 45 min, 2h or 1h30m"
+            ),
+        )
+    };
+
+    let minutes = if let Ok(minutes) = string.parse::<u32>() {
+        minutes
+    } else {
+        let captures = regexes::DURATION_REGEX
+            .captures(&string)
+            .filter(|captures| captures.get(1).is_some() || captures.get(2).is_some())
+            .ok_or_else(invalid)?;
+        let hours = match captures.get(1) {
+            Some(hours) => hours.as_str().parse::<u32>().map_err(|_| invalid())?,
+            None => 0,
+        };
+        let minutes = match captures.get(2) {
+            Some(minutes) => minutes.as_str().parse::<u32>().map_err(|_| invalid())?,
+            None => 0,
+        };
+        hours
+            .checked_mul(60)
+            .and_then(|hours| hours.checked_add(minutes))
+            .ok_or_else(invalid)?
+    };
+
+    if minutes == 0 {
+        Err(invalid())
+    } else {
+        Ok(minutes)
+    }
+}
+
+/// How far `task snooze` moves a due date
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnoozeOffset {
+    Days(i64),
+    Minutes(i64),
+}
+
+/// Parses a relative offset such as "2h", "1h30m", "3d" or "1w", None when the string is not one
+pub fn snooze_offset(string: &str) -> Option<SnoozeOffset> {
+    let string = string.trim().to_lowercase();
+    if let Some(captures) = regexes::DAYS_OFFSET_REGEX.captures(&string) {
+        let number = captures.get(1)?.as_str().parse::<i64>().ok()?;
+        let days = if captures.get(2)?.as_str().starts_with('w') {
+            number.checked_mul(7)?
+        } else {
+            number
+        };
+        return (days > 0).then_some(SnoozeOffset::Days(days));
+    }
+    duration_to_minutes(&string)
+        .ok()
+        .map(|minutes| SnoozeOffset::Minutes(i64::from(minutes)))
+}
+
+/// Moves a due date by a snooze offset and returns it as a due string in the config timezone.
+/// `due` is the date of the task as given by the API, the offset starts from now when it is None.
+/// Days keep a date without a time as a date, minutes and hours on a date without a time start from now.
+pub fn snooze_due(
+    due: Option<&str>,
+    offset: SnoozeOffset,
+    config: &Config,
+) -> Result<String, Error> {
+    let tz = timezone_from_str(&config.get_timezone()?)?;
+    let too_far = || Error::new("snooze_due", "Cannot snooze that far into the future");
+    match (due, offset) {
+        (Some(date), SnoozeOffset::Days(days)) if date.len() == 10 => {
+            let date = add_snooze_days(date_from_str(date, tz)?, days).ok_or_else(too_far)?;
+            Ok(date.format(FORMAT_DATE).to_string())
+        }
+        (Some(datetime), offset) if datetime.len() > 10 => {
+            let datetime = datetime_from_str(datetime, tz)?;
+            Ok(add_snooze_offset(datetime, offset)
+                .ok_or_else(too_far)?
+                .with_timezone(&tz)
+                .format(FORMAT_DATE_AND_TIME)
+                .to_string())
+        }
+        (None, SnoozeOffset::Days(days)) => {
+            let date = add_snooze_days(naive_date_today(config)?, days).ok_or_else(too_far)?;
+            Ok(date.format(FORMAT_DATE).to_string())
+        }
+        (_, offset) => Ok(add_snooze_offset(datetime_now(config)?, offset)
+            .ok_or_else(too_far)?
+            .format(FORMAT_DATE_AND_TIME)
+            .to_string()),
+    }
+}
+
+/// None when the date would be out of range
+fn add_snooze_days(date: NaiveDate, days: i64) -> Option<NaiveDate> {
+    date.checked_add_signed(TimeDelta::try_days(days)?)
+}
+
+/// None when the datetime would be out of range
+fn add_snooze_offset(datetime: DateTime<Tz>, offset: SnoozeOffset) -> Option<DateTime<Tz>> {
+    let delta = match offset {
+        SnoozeOffset::Days(days) => TimeDelta::try_days(days)?,
+        SnoozeOffset::Minutes(minutes) => TimeDelta::try_minutes(minutes)?,
+    };
+    datetime.checked_add_signed(delta)
+}
+
 // ----------- TZ FUNCTIONS --------------
 
+/// A timezone from the tz database by its IANA name, i.e. "Europe/Berlin"
+pub fn iana_timezone(zone: &str) -> Result<Tz, Error> {
+    zone.parse::<Tz>().map_err(|_| {
+        Error::new(
+            "due_tz",
+            &format!("Unknown timezone '{zone}', use an IANA name such as Europe/Berlin"),
+        )
+    })
+}
+
+/// Reads a due datetime in format YYYY-MM-DD HH:MM as the time on the clock in zone,
+/// returning it in UTC as Todoist's due_datetime expects
+pub fn due_datetime_in_zone(due: &str, zone: &str) -> Result<String, Error> {
+    let tz = iana_timezone(zone)?;
+    let naive = NaiveDateTime::parse_from_str(due, FORMAT_DATE_AND_TIME).map_err(|_| {
+        Error::new(
+            "due_tz",
+            &format!(
+                "--due-tz needs a due date with a time in format YYYY-MM-DD HH:MM, not '{due}'"
+            ),
+        )
+    })?;
+    wall_clock_in_zone(naive, tz)
+}
+
+/// Moves a due time Todoist parsed in the task's or config timezone to the same time on the clock in zone,
+/// returning it in UTC. None when the due date has no time.
+pub fn move_due_to_zone(
+    due: &DateInfo,
+    zone: &str,
+    config: &Config,
+) -> Result<Option<String>, Error> {
+    let tz = iana_timezone(zone)?;
+    if due.date.len() == 10 {
+        return Ok(None);
+    }
+    let parsed_in = match &due.timezone {
+        Some(timezone) => timezone_from_str(timezone)?,
+        None => timezone_from_str(&config.get_timezone()?)?,
+    };
+    let naive = datetime_from_str(&due.date, parsed_in)?
+        .with_timezone(&parsed_in)
+        .naive_local();
+    wall_clock_in_zone(naive, tz).map(Some)
+}
+
+fn wall_clock_in_zone(naive: NaiveDateTime, tz: Tz) -> Result<String, Error> {
+    let datetime = naive_datetime_to_datetime(naive, tz)?;
+    Ok(datetime
+        .with_timezone(&Utc)
+        .format(FORMAT_DATETIME_ZULU)
+        .to_string())
+}
+
+/// Whether the string is a UTC datetime such as 2025-05-12T16:00:00Z
+pub fn is_utc_datetime(string: &str) -> bool {
+    NaiveDateTime::parse_from_str(string, FORMAT_DATETIME_ZULU).is_ok()
+}
+
+/// IANA timezone names containing filter, ignoring case
+pub fn timezone_names(filter: Option<&str>) -> Vec<&'static str> {
+    let filter = filter.map(str::to_lowercase);
+    chrono_tz::TZ_VARIANTS
+        .iter()
+        .map(|tz| tz.name())
+        .filter(|name| {
+            filter
+                .as_ref()
+                .is_none_or(|filter| name.to_lowercase().contains(filter))
+        })
+        .collect()
+}
+
 pub fn timezone_from_str(timezone_string: &str) -> Result<Tz, Error> {
     timezone_string
         .parse::<Tz>()
@@ -427,6 +799,97 @@ mod tests {
         assert_eq!(err.source, "date_from_str");
     }
 
+    #[test]
+    fn test_validate_due() {
+        assert_eq!(validate_due("2024-02-29"), Ok(()));
+        assert_eq!(validate_due("2024-02-29 15:30"), Ok(()));
+        assert_eq!(validate_due("2024-02-29 at 3pm"), Ok(()));
+        assert_eq!(validate_due("tomorrow"), Ok(()));
+        assert_eq!(validate_due("every monday"), Ok(()));
+
+        for due in [
+            "",
+            "  ",
+            "???",
+            "2023-02-29",
+            "2024/02/01",
+            "2024-2-1",
+            "2024-02-01 25:00",
+        ] {
+            let error = validate_due(due).expect_err("invalid due date should fail");
+            assert_eq!(error.source, "validate_due");
+            assert!(error.message.contains("Accepted formats are YYYY-MM-DD"));
+        }
+    }
+
+    #[test]
+    fn test_duration_to_minutes() {
+        assert_eq!(duration_to_minutes("45"), Ok(45));
+        assert_eq!(duration_to_minutes("45 min"), Ok(45));
+        assert_eq!(duration_to_minutes("1h30m"), Ok(90));
+        assert_eq!(duration_to_minutes("1 hour 15 minutes"), Ok(75));
+        assert_eq!(duration_to_minutes(" 2H "), Ok(120));
+    }
+
+    #[test]
+    fn test_duration_to_minutes_invalid() {
+        for invalid in ["", "0", "0h0m", "soon", "1.5h", "-5"] {
+            let result = duration_to_minutes(invalid);
+            assert_eq!(
+                result.map_err(|e| e.source),
+                Err("duration_to_minutes".to_string()),
+                "{invalid} should not parse"
+            );
+        }
+    }
+
+    #[test]
+    fn test_snooze_offset() {
+        assert_eq!(snooze_offset("2h"), Some(SnoozeOffset::Minutes(120)));
+        assert_eq!(snooze_offset("1h30m"), Some(SnoozeOffset::Minutes(90)));
+        assert_eq!(snooze_offset("3d"), Some(SnoozeOffset::Days(3)));
+        assert_eq!(snooze_offset("1 week"), Some(SnoozeOffset::Days(7)));
+        assert_eq!(snooze_offset("0d"), None);
+        assert_eq!(snooze_offset("tomorrow"), None);
+    }
+
+    #[tokio::test]
+    async fn test_snooze_due() {
+        let config = crate::test::fixtures::config().await;
+
+        // Fixed time is 2025-05-10 10:00 UTC, 03:00 in America/Vancouver
+        let snoozed = snooze_due(None, SnoozeOffset::Minutes(120), &config);
+        assert_eq!(snoozed, Ok("2025-05-10 05:00".to_string()));
+
+        let snoozed = snooze_due(None, SnoozeOffset::Days(1), &config);
+        assert_eq!(snoozed, Ok("2025-05-11".to_string()));
+
+        let snoozed = snooze_due(Some("2025-05-12"), SnoozeOffset::Days(2), &config);
+        assert_eq!(snoozed, Ok("2025-05-14".to_string()));
+
+        let snoozed = snooze_due(Some("2025-05-12"), SnoozeOffset::Minutes(30), &config);
+        assert_eq!(snoozed, Ok("2025-05-10 03:30".to_string()));
+
+        let snoozed = snooze_due(
+            Some("2025-05-12T19:00:00Z"),
+            SnoozeOffset::Minutes(90),
+            &config,
+        );
+        assert_eq!(snoozed, Ok("2025-05-12 13:30".to_string()));
+
+        for days in [100_000_000, i64::MAX] {
+            let offset = SnoozeOffset::Days(days);
+            for due in [None, Some("2025-05-12"), Some("2025-05-12T19:00:00Z")] {
+                let snoozed = snooze_due(due, offset, &config).map_err(|e| e.source);
+                assert_eq!(
+                    snoozed,
+                    Err("snooze_due".to_string()),
+                    "{days}d from {due:?}"
+                );
+            }
+        }
+    }
+
     #[test]
     fn test_timezone_from_str_invalid() {
         let result = timezone_from_str("Not/ATimezone");
@@ -487,6 +950,23 @@ mod tests {
         assert_eq!(result, "2020-06-15");
     }
 
+    #[tokio::test]
+    async fn test_date_and_datetime_to_string_with_iso_dates() {
+        let mut config = crate::test::fixtures::config()
+            .await
+            .with_timezone("America/Vancouver");
+        config.iso_dates = Some(true);
+
+        let today = naive_date_today(&config).expect("should get today");
+        let result = date_to_string(today, &config).expect("should format today");
+        assert_eq!(result, today.format(FORMAT_DATE).to_string());
+
+        let datetime =
+            datetime_from_str("2024-01-15T17:30:00Z", Tz::UTC).expect("should parse datetime");
+        let result = datetime_to_string(&datetime, &config).expect("should format datetime");
+        assert_eq!(result, "2024-01-15T09:30:00-08:00");
+    }
+
     #[tokio::test]
     async fn test_date_string_to_naive_date() {
         let result = date_string_to_naive_date("2024-03-20");
@@ -497,9 +977,196 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_smart_dates_from() {
+        let date = |day| NaiveDate::from_ymd_opt(2025, 5, day).expect("valid date");
+        let dates_for = |today| {
+            smart_dates_from(today, Weekday::Mon)
+                .into_iter()
+                .map(|smart_date| smart_date.date)
+                .collect::<Vec<_>>()
+        };
+
+        // Wednesday
+        assert_eq!(
+            dates_for(date(7)),
+            vec![date(7), date(8), date(12), date(10)]
+        );
+        // Saturday and Sunday are already the weekend
+        assert_eq!(
+            dates_for(date(10)),
+            vec![date(10), date(11), date(12), date(10)]
+        );
+        assert_eq!(
+            dates_for(date(11)),
+            vec![date(11), date(12), date(12), date(11)]
+        );
+        // Monday
+        assert_eq!(
+            dates_for(date(12)),
+            vec![date(12), date(13), date(19), date(17)]
+        );
+    }
+
+    #[test]
+    fn test_smart_dates_from_with_sunday_week_start() {
+        let date = |day| NaiveDate::from_ymd_opt(2025, 5, day).expect("valid date");
+        let dates_for = |today| {
+            smart_dates_from(today, Weekday::Sun)
+                .into_iter()
+                .map(|smart_date| smart_date.date)
+                .collect::<Vec<_>>()
+        };
+
+        // Wednesday
+        assert_eq!(
+            dates_for(date(7)),
+            vec![date(7), date(8), date(11), date(10)]
+        );
+        // Saturday is the weekend, Sunday starts a new week
+        assert_eq!(
+            dates_for(date(10)),
+            vec![date(10), date(11), date(11), date(10)]
+        );
+        assert_eq!(
+            dates_for(date(11)),
+            vec![date(11), date(12), date(18), date(17)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_smart_dates_use_week_start() {
+        let mut config = crate::test::fixtures::config().await;
+        config.week_start = Some("Sunday".to_string());
+        let dates = smart_dates(&config).expect("should get smart dates");
+        assert_eq!(dates[2].to_string(), "Next Sunday (Sun 2025-05-11)");
+
+        config.week_start = Some("friday".to_string());
+        let error = smart_dates(&config).expect_err("friday is not a week start");
+        assert_eq!(error.source, "week_start");
+    }
+
+    #[tokio::test]
+    async fn test_smart_dates_use_config_timezone() {
+        let config = crate::test::fixtures::config().await;
+        let dates = smart_dates(&config).expect("should get smart dates");
+        assert_eq!(dates[0].to_string(), "Today (Sat 2025-05-10)");
+
+        // Fixed time is 10:00 UTC on Saturday, which is already Sunday in Kiribati
+        let config = config.with_timezone("Pacific/Kiritimati");
+        let dates = smart_dates(&config).expect("should get smart dates");
+        assert_eq!(dates[0].to_string(), "Today (Sun 2025-05-11)");
+        assert_eq!(dates[2].to_string(), "Next Monday (Mon 2025-05-12)");
+    }
+
+    #[tokio::test]
+    async fn test_since_date() {
+        let config = crate::test::fixtures::config().await;
+        let date = |string: &str| since_date(string, &config, false);
+        let day = |day: u32| NaiveDate::from_ymd_opt(2025, 5, day).expect("valid date");
+
+        // Fixed time is 2025-05-10 in America/Vancouver
+        assert_eq!(date("today"), Ok(day(10)));
+        assert_eq!(date("Yesterday"), Ok(day(9)));
+        assert_eq!(date("2025-05-01"), Ok(day(1)));
+        assert_eq!(date("3d"), Ok(day(7)));
+        assert_eq!(date("7 days ago"), Ok(day(3)));
+        assert_eq!(date("last 1 week"), Ok(day(3)));
+
+        for invalid in [
+            "soon",
+            "2025-05-11",
+            "2025-01-01",
+            "20 weeks ago",
+            "200000000000000 days ago",
+        ] {
+            assert_eq!(
+                date(invalid).map_err(|e| e.source),
+                Err("since_date".to_string()),
+                "{invalid} should not be accepted"
+            );
+        }
+
+        assert_eq!(
+            since_date("2025-01-01", &config, true).map_err(|e| e.source),
+            Ok(NaiveDate::from_ymd_opt(2025, 1, 1).expect("valid date"))
+        );
+    }
+
+    #[test]
+    fn test_split_into_windows() {
+        use chrono::TimeZone;
+        let tz = timezone_from_str("UTC").expect("UTC should parse");
+        let day = |day: u32| {
+            tz.with_ymd_and_hms(2025, 5, day, 0, 0, 0)
+                .single()
+                .expect("valid datetime")
+        };
+
+        assert_eq!(
+            split_into_windows(day(1), day(20), None),
+            vec![(day(1), day(20))]
+        );
+        assert_eq!(
+            split_into_windows(day(1), day(20), Some(7)),
+            vec![(day(1), day(8)), (day(8), day(15)), (day(15), day(20))]
+        );
+        assert_eq!(
+            split_into_windows(day(1), day(15), Some(7)),
+            vec![(day(1), day(8)), (day(8), day(15))]
+        );
+    }
+
     #[test]
     fn test_date_string_to_naive_date_invalid() {
         let result = date_string_to_naive_date("not-a-date");
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_due_datetime_in_zone() {
+        assert_eq!(
+            due_datetime_in_zone("2025-05-12 09:00", "Europe/Berlin"),
+            Ok("2025-05-12T07:00:00Z".to_string())
+        );
+        assert_eq!(
+            due_datetime_in_zone("2025-05-12 09:00", "Mars/Olympus").map_err(|e| e.source),
+            Err("due_tz".to_string())
+        );
+        assert_eq!(
+            due_datetime_in_zone("tomorrow", "Europe/Berlin").map_err(|e| e.source),
+            Err("due_tz".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_move_due_to_zone() {
+        let config = crate::test::fixtures::config().await;
+        let due = |date: &str| DateInfo {
+            date: date.to_string(),
+            is_recurring: false,
+            string: "monday 9am".to_string(),
+            lang: "en".to_string(),
+            timezone: None,
+        };
+
+        // 9:00 in America/Vancouver becomes 9:00 in Europe/Berlin
+        assert_eq!(
+            move_due_to_zone(&due("2025-05-12T16:00:00Z"), "Europe/Berlin", &config),
+            Ok(Some("2025-05-12T07:00:00Z".to_string()))
+        );
+        assert_eq!(
+            move_due_to_zone(&due("2025-05-12"), "Europe/Berlin", &config),
+            Ok(None)
+        );
+        assert!(is_utc_datetime("2025-05-12T07:00:00Z"));
+        assert!(!is_utc_datetime("2025-05-12 07:00"));
+    }
+
+    #[test]
+    fn test_timezone_names() {
+        assert!(timezone_names(None).contains(&"Europe/Berlin"));
+        assert_eq!(timezone_names(Some("vancouver")), vec!["America/Vancouver"]);
+        assert!(timezone_names(Some("Mars")).is_empty());
+    }
 }
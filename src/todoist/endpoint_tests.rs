@@ -0,0 +1,254 @@
+//! Named checks against the live Todoist API, used by `tod test`
+//! Each check creates what it needs and cleans up after itself so that it can be run on its own.
+use futures::future::BoxFuture;
+use std::time::Instant;
+
+use super::{
+    add_task_label, all_comments, all_labels, all_projects, all_sections_by_project,
+    all_tasks_by_filter, all_tasks_by_project, clear_task_deadline, complete_task, create_comment,
+    create_project, create_section, create_task, delete_project, delete_task, get_task,
+    get_user_data, move_task_to_project, move_task_to_section, quick_create_task,
+    update_task_content, update_task_deadline, update_task_description,
+    update_task_due_natural_language, update_task_labels, update_task_priority,
+};
+use crate::config::Config;
+use crate::errors::Error;
+use crate::tasks::priority::Priority;
+use crate::{format, time};
+
+const NAME: &str = "TEST";
+
+type EndpointTest = for<'a> fn(&'a Config) -> BoxFuture<'a, Result<(), Error>>;
+
+/// Endpoint checks by name, in the order `tod test all` runs them
+const ENDPOINT_TESTS: [(&str, EndpointTest); 9] = [
+    ("projects", projects),
+    ("sections", sections),
+    ("tasks", tasks),
+    ("comments", comments),
+    ("quick-add", quick_add),
+    ("filters", filters),
+    ("task-updates", task_updates),
+    ("labels", labels),
+    ("user", user),
+];
+
+/// Names of the endpoint checks that can be run with `tod test endpoint`
+pub fn endpoint_names() -> impl Iterator<Item = &'static str> {
+    ENDPOINT_TESTS.iter().map(|(name, _)| *name)
+}
+
+/// Used to sanity check all the Todoist API endpoints to make sure that we are able to process the JSON payloads they are sending back.
+pub async fn test_all_endpoints(config: &Config) -> Result<String, Error> {
+    let mut failures = Vec::new();
+    for (name, test) in ENDPOINT_TESTS {
+        if !run_endpoint_test(config, name, test).await {
+            failures.push(name);
+        }
+    }
+
+    if failures.is_empty() {
+        Ok(format::green_string("Completed successfully"))
+    } else {
+        Err(Error::new(
+            "test_all_endpoints",
+            &format!("Failed endpoints: {}", failures.join(", ")),
+        ))
+    }
+}
+
+/// Run a single named endpoint check
+pub async fn test_endpoint(config: &Config, name: &str) -> Result<String, Error> {
+    let (name, test) = ENDPOINT_TESTS
+        .into_iter()
+        .find(|(endpoint, _)| *endpoint == name)
+        .ok_or_else(|| {
+            let available = endpoint_names().collect::<Vec<&str>>().join(", ");
+            Error::new(
+                "test_endpoint",
+                &format!("Unknown endpoint '{name}'. Available endpoints: {available}"),
+            )
+        })?;
+
+    if run_endpoint_test(config, name, test).await {
+        Ok(format::green_string("Completed successfully"))
+    } else {
+        Err(Error::new(
+            "test_endpoint",
+            &format!("Failed endpoint: {name}"),
+        ))
+    }
+}
+
+/// Prints whether the check passed and how long it took, returns true if it passed
+async fn run_endpoint_test(config: &Config, name: &str, test: EndpointTest) -> bool {
+    println!("Testing {name}");
+    let start = Instant::now();
+    let result = test(config).await;
+    let elapsed = start.elapsed().as_millis();
+
+    match result {
+        Ok(()) => {
+            println!("{} {name} ({elapsed}ms)", format::green_string("PASS"));
+            true
+        }
+        Err(e) => {
+            println!("{} {name} ({elapsed}ms)\n{e}", format::red_string("FAIL"));
+            false
+        }
+    }
+}
+
+fn projects(config: &Config) -> BoxFuture<'_, Result<(), Error>> {
+    Box::pin(async move {
+        let project = create_project(config, NAME, NAME, false, false).await?;
+        all_projects(config, Some(1)).await?;
+        delete_project(config, &project, false).await?;
+        Ok(())
+    })
+}
+
+fn sections(config: &Config) -> BoxFuture<'_, Result<(), Error>> {
+    Box::pin(async move {
+        let project = create_project(config, NAME, NAME, false, false).await?;
+        create_section(config, NAME, &project, false).await?;
+        all_sections_by_project(config, &project, Some(1)).await?;
+        delete_project(config, &project, false).await?;
+        Ok(())
+    })
+}
+
+fn tasks(config: &Config) -> BoxFuture<'_, Result<(), Error>> {
+    Box::pin(async move {
+        let project = create_project(config, NAME, NAME, false, false).await?;
+        let section = create_section(config, NAME, &project, false).await?;
+        let task = create_task(
+            config,
+            NAME,
+            &project,
+            Some(&section),
+            Priority::None,
+            NAME,
+            None,
+            None,
+            &[],
+            None,
+        )
+        .await?;
+        move_task_to_section(config, &task, &section, false).await?;
+        let task = get_task(config, &task.id).await?;
+        all_tasks_by_project(config, &project, Some(1)).await?;
+        delete_task(config, &task.id, false).await?;
+        delete_project(config, &project, false).await?;
+        Ok(())
+    })
+}
+
+fn comments(config: &Config) -> BoxFuture<'_, Result<(), Error>> {
+    Box::pin(async move {
+        let task = quick_create_task(config, NAME, None, None).await?;
+        create_comment(config, &task.id, NAME, None, false).await?;
+        create_comment(config, &task.id, NAME, None, false).await?;
+        all_comments(config, &task.id, Some(1)).await?;
+        delete_task(config, &task.id, false).await?;
+        Ok(())
+    })
+}
+
+fn quick_add(config: &Config) -> BoxFuture<'_, Result<(), Error>> {
+    Box::pin(async move {
+        let task = quick_create_task(config, NAME, Some(String::from("tomorrow")), None).await?;
+        delete_task(config, &task.id, false).await?;
+        Ok(())
+    })
+}
+
+fn filters(config: &Config) -> BoxFuture<'_, Result<(), Error>> {
+    Box::pin(async move {
+        all_tasks_by_filter(config, "tod", Some(1)).await?;
+        Ok(())
+    })
+}
+
+fn task_updates(config: &Config) -> BoxFuture<'_, Result<(), Error>> {
+    Box::pin(async move {
+        let date = time::date_string_today(config)?;
+        let labels: Vec<String> = vec!["one".into(), "two".into()];
+        let project = create_project(config, NAME, NAME, false, false).await?;
+        let task = quick_create_task(config, NAME, None, None).await?;
+
+        update_task_priority(config, &task.id, &Priority::None, false).await?;
+        update_task_content(config, &task.id, NAME, false).await?;
+        update_task_description(config, &task.id, NAME, false).await?;
+        update_task_deadline(config, &task.id, Some(date), false).await?;
+        clear_task_deadline(config, &task.id, false).await?;
+        update_task_labels(config, &task.id, labels, false).await?;
+        add_task_label(config, &task, "three".into(), false).await?;
+        update_task_due_natural_language(config, &task, "today".into(), None, false).await?;
+        let task = move_task_to_project(config, &task, &project, false).await?;
+        complete_task(config, &task, false).await?;
+        delete_task(config, &task.id, false).await?;
+        delete_project(config, &project, false).await?;
+        Ok(())
+    })
+}
+
+fn labels(config: &Config) -> BoxFuture<'_, Result<(), Error>> {
+    Box::pin(async move {
+        all_labels(config, false, Some(1)).await?;
+        Ok(())
+    })
+}
+
+fn user(config: &Config) -> BoxFuture<'_, Result<(), Error>> {
+    Box::pin(async move {
+        get_user_data(config).await?;
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test;
+    use crate::test::responses::ResponseFromFile;
+    use pretty_assertions::assert_eq;
+    use std::collections::HashSet;
+
+    #[test]
+    fn endpoint_names_are_unique() {
+        let names = endpoint_names().collect::<Vec<&str>>();
+        let unique = names.iter().collect::<HashSet<_>>();
+        assert_eq!(names.len(), unique.len());
+        assert!(names.contains(&"user"));
+    }
+
+    #[tokio::test]
+    async fn test_endpoint_runs_only_the_named_check() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api/v1/user")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::User.read().await)
+            .create_async()
+            .await;
+
+        let config = test::fixtures::config().await.with_mock_url(server.url());
+
+        let result = test_endpoint(&config, "user").await;
+        assert_eq!(result, Ok(format::green_string("Completed successfully")));
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_endpoint_rejects_unknown_name() {
+        let config = test::fixtures::config().await;
+
+        let error = test_endpoint(&config, "nope")
+            .await
+            .expect_err("unknown endpoint should fail");
+        assert_eq!(error.source, "test_endpoint");
+        assert!(error.message.contains("Available endpoints: projects"));
+    }
+}
@@ -1,13 +1,19 @@
-use futures::future;
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use futures::{StreamExt, TryStreamExt, stream};
 use serde_json::{Number, Value, json};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::time::Duration;
 use urlencoding::encode;
+mod endpoint_tests;
+mod queue;
 mod request;
 
-use crate::comments::{Comment, CommentResponse};
+use crate::comments::{Comment, CommentResponse, FileAttachment};
 use crate::config::Config;
-use crate::debug::maybe_print;
-use crate::errors::Error;
+use crate::debug::{self, LogLevel, maybe_print};
+use crate::errors::{Error, ErrorKind};
 use crate::labels::{Label, LabelResponse};
 use crate::oauth::{AccessToken, CLIENT_ID, CLIENT_SECRET};
 use crate::projects::{Project, ProjectResponse};
@@ -15,137 +21,51 @@ use crate::reminders::{Reminder, ReminderResponse};
 use crate::sections::{Section, SectionResponse};
 use crate::shell::execute_command;
 use crate::tasks::priority::Priority;
-use crate::tasks::{Task, TaskResponse};
-use crate::users::User;
-use crate::{format, time};
+use crate::tasks::{CompletedTaskResponse, Task, TaskResponse};
+use crate::time;
+use crate::users::{Collaborator, CollaboratorResponse, User};
 use regex::Regex;
 
+pub use endpoint_tests::{endpoint_names, test_all_endpoints, test_endpoint};
+pub use queue::flush_queue;
+pub use request::get_timeout;
+
 // TODOIST URLS
 pub const TASKS_URL: &str = "/api/v1/tasks/";
 pub const COMMENTS_URL: &str = "/api/v1/comments/";
+const COMPLETED_TASKS_URL: &str = "/api/v1/tasks/completed/by_completion_date";
 const SECTIONS_URL: &str = "/api/v1/sections";
 const REMINDERS_URL: &str = "/api/v1/reminders";
 const USER_URL: &str = "/api/v1/user";
 const PROJECTS_URL: &str = "/api/v1/projects";
 const LABELS_URL: &str = "/api/v1/labels";
+const UPLOADS_URL: &str = "/api/v1/uploads";
 const ACCESS_TOKEN_URL: &str = "/oauth/access_token";
 pub const OAUTH_URL: &str = "/oauth/authorize";
+/// Longest a completion waits on `completion_webhook`
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
 
 /// Number of items that can be requested from API at once
 pub const QUERY_LIMIT: u8 = 200;
 
-/// Used to sanity check all the Todoist API endpoints to make sure that we are able to process the JSON payloads they are sending back.
-pub async fn test_all_endpoints(config: &Config) -> Result<String, Error> {
-    let name = "TEST".to_string();
-    let date = time::date_string_today(config)?;
-    let priority = Priority::None;
-    let labels: Vec<String> = vec!["one".into(), "two".into()];
-
-    println!("Creating project");
-    let project = create_project(config, &name, &name, false, false).await?;
-
-    println!("List projects");
-    let _projects = all_projects(config, Some(1)).await?;
-
-    println!("Creating section");
-    let section = create_section(config, &name, &project, false).await?;
-
-    println!("Creating task with add_task");
-    let task = create_task(
-        config,
-        &name,
-        &project,
-        Some(&section),
-        priority,
-        &name,
-        None,
-        &[],
-    )
-    .await?;
-
-    println!("Getting sections for project");
-    let _sections = all_sections_by_project(config, &project, Some(1)).await?;
-
-    println!("Moving task to section");
-    let _task = move_task_to_section(config, &task, &section, false).await?;
-
-    println!("Getting task with get_task");
-    let task = get_task(config, &task.id).await?;
-
-    println!("Commenting on task twice");
-    let _comment = create_comment(config, &task.id, &name, false).await?;
-
-    let _comment = create_comment(config, &task.id, &name, false).await?;
-
-    println!("Getting comments for task");
-    let _comments = all_comments(config, &task.id, Some(1)).await?;
-
-    println!("Deleting task");
-    delete_task(config, &task.id, false).await?;
-
-    println!("Creating two tasks with quick_add_task");
-    let _task = quick_create_task(config, &name, None).await?;
-    let task = quick_create_task(config, &name, Some(String::from("tomorrow"))).await?;
-
-    println!("Finding tasks with tasks_for_project");
-    let _tasks = all_tasks_by_project(config, &project, Some(1)).await?;
-
-    println!("Finding tasks with tasks_for_filter");
-    let _tasks = all_tasks_by_filter(config, "tod", Some(1)).await?;
-
-    println!("Updating task priority");
-    let _task = update_task_priority(config, &task.id, &priority, false).await?;
-
-    println!("Updating task content");
-    let _task = update_task_content(config, &task.id, &name, false).await?;
-
-    println!("Updating task description");
-    let _task = update_task_description(config, &task.id, &name, false).await?;
-
-    println!("Updating task deadline");
-    let _task = update_task_deadline(config, &task.id, Some(date), false).await?;
-
-    println!("Updating task labels");
-    let _task = update_task_labels(config, &task.id, labels, false).await?;
-
-    println!("Adding task label");
-    let _task = add_task_label(config, &task, "three".into(), false).await?;
-
-    println!("Updating task due with natural language");
-    let _task =
-        update_task_due_natural_language(config, &task, "today".into(), None, false).await?;
-
-    println!("Moving task to project");
-    let task = move_task_to_project(config, &task, &project, false).await?;
-
-    println!("Completing task");
-    let _task = complete_task(config, &task.id, false).await?;
+/// Largest file Todoist accepts as a comment attachment, in bytes
+pub const MAX_UPLOAD_BYTES: u64 = 100 * 1024 * 1024;
 
-    println!("Deleting task");
-    delete_task(config, &task.id, false).await?;
-
-    println!("Deleting project");
-    delete_project(config, &project, false).await?;
-
-    println!("List labels");
-    let _labels = all_labels(config, false, Some(1)).await?;
-
-    println!("Get user data");
-    let _data = get_user_data(config).await?;
-
-    Ok(format::green_string("Completed successfully"))
-}
-
-/// Add a new task to the inbox with natural language support
+/// Add a new task with natural language support, into the inbox when no project is given
 pub async fn quick_create_task(
     config: &Config,
     content: &str,
     reminder: Option<String>,
+    project: Option<&Project>,
 ) -> Result<Task, Error> {
     let url = format!("{TASKS_URL}quick");
-    let body = json!({"text": content, "auto_reminder": true, "reminder": reminder});
+    let mut body = json!({"text": content, "auto_reminder": true, "reminder": reminder});
+    if let Some(project) = project {
+        body["project_id"] = Value::String(project.id.clone());
+    }
 
-    let json = request::post_todoist(config, &url, body, true).await?;
+    let description = format!("quick add '{content}'");
+    let json = request::post_todoist_or_queue(config, &url, body, true, &description).await?;
     maybe_run_command(config.task_create_command.as_deref(), config)?;
     Task::from_json(&json)
 }
@@ -156,13 +76,31 @@ pub async fn get_task(config: &Config, id: &str) -> Result<Task, Error> {
     Task::from_json(&json)
 }
 
-pub async fn get_access_token(config: &Config, code: &str) -> Result<String, Error> {
+pub async fn get_access_token(config: &Config, code: &str) -> Result<AccessToken, Error> {
     let url = ACCESS_TOKEN_URL.to_string();
     let body = json!({"code": code, "client_id": CLIENT_ID, "client_secret": CLIENT_SECRET});
 
     let json = request::post_todoist_no_token(config, &url, body, true).await?;
 
-    AccessToken::from_json(&json).map(|t| t.access_token)
+    AccessToken::from_json(&json)
+}
+
+/// Exchange an OAuth refresh token for a new access token
+pub async fn refresh_access_token(
+    config: &Config,
+    refresh_token: &str,
+) -> Result<AccessToken, Error> {
+    let url = ACCESS_TOKEN_URL.to_string();
+    let body = json!({
+        "grant_type": "refresh_token",
+        "refresh_token": refresh_token,
+        "client_id": CLIENT_ID,
+        "client_secret": CLIENT_SECRET
+    });
+
+    let json = request::post_todoist_no_token(config, &url, body, false).await?;
+
+    AccessToken::from_json(&json)
 }
 
 /// Add Task without natural language support but supports additional parameters
@@ -175,7 +113,9 @@ pub async fn create_task(
     priority: Priority,
     description: &str,
     due: Option<&str>,
+    due_lang: Option<&str>,
     labels: &[String],
+    assignee: Option<&Collaborator>,
 ) -> Result<Task, Error> {
     let project_id = project.id.clone();
     let url = TASKS_URL;
@@ -196,10 +136,19 @@ pub async fn create_task(
     body.insert("labels".to_owned(), Value::Array(labels));
 
     if let Some(date) = due {
-        if time::is_date(date) || time::is_datetime(date) {
+        if time::is_utc_datetime(date) {
+            body.insert("due_datetime".to_owned(), Value::String(date.to_owned()));
+        } else if time::is_date(date) || time::is_datetime(date) {
             body.insert("due_date".to_owned(), Value::String(date.to_owned()));
         } else {
             body.insert("due_string".to_owned(), Value::String(date.to_owned()));
+            let language = match due_lang {
+                Some(language) => Some(language),
+                None => config.language()?,
+            };
+            if let Some(language) = language {
+                body.insert("due_lang".to_owned(), Value::String(language.to_owned()));
+            }
         }
     }
 
@@ -207,9 +156,17 @@ pub async fn create_task(
         body.insert("section_id".to_owned(), Value::String(section.id.clone()));
     }
 
+    if let Some(assignee) = assignee {
+        body.insert(
+            "responsible_uid".to_owned(),
+            Value::String(assignee.id.clone()),
+        );
+    }
+
     let body = json!(body);
 
-    let json = request::post_todoist(config, url, body, true).await?;
+    let description = format!("create task '{content}'");
+    let json = request::post_todoist_or_queue(config, url, body, true, &description).await?;
     maybe_run_command(config.task_create_command.as_deref(), config)?;
     Task::from_json(&json)
 }
@@ -224,8 +181,11 @@ pub async fn create_reminder(
 ) -> Result<Reminder, Error> {
     let task_id = task.id.clone();
     let url = REMINDERS_URL;
-    let body =
+    let mut body =
         json!({"task_id": task_id, "reminder_type": "absolute", "due": {"string": due_string}});
+    if let Some(language) = config.language()? {
+        body["due"]["lang"] = Value::String(language.to_owned());
+    }
 
     let json = request::post_todoist(config, url, body, spinner).await?;
     Reminder::from_json(&json)
@@ -268,12 +228,11 @@ pub async fn all_tasks_by_filters(
     config: &Config,
     filter: &str,
 ) -> Result<Vec<(String, Vec<Task>)>, Error> {
-    let filters: Vec<_> = filter
-        .split(',')
+    stream::iter(filter.split(','))
         .map(|f| all_tasks_by_filter(config, f, None))
-        .collect();
-
-    future::try_join_all(filters).await
+        .buffered(config.max_concurrency())
+        .try_collect()
+        .await
 }
 
 /// Fetches a list of tasks by a single filter query.
@@ -309,6 +268,79 @@ pub async fn all_tasks_by_filter(
     Ok((filter.to_string(), tasks))
 }
 
+/// Fetches tasks completed between two times, in a project or matching a filter query, or everywhere when neither is given.
+/// With `batch_days` the time is split into windows of that many days, fetched up to `max_concurrency` at a time
+pub async fn all_completed_tasks(
+    config: &Config,
+    project: Option<&Project>,
+    filter: Option<&str>,
+    since: DateTime<Tz>,
+    until: DateTime<Tz>,
+    batch_days: Option<u32>,
+) -> Result<Vec<Task>, Error> {
+    let windows = time::split_into_windows(since, until, batch_days);
+    let batches = stream::iter(windows)
+        .map(|(since, until)| completed_tasks_in_window(config, project, filter, since, until))
+        .buffered(config.max_concurrency())
+        .try_collect::<Vec<Vec<Task>>>()
+        .await?;
+
+    // A task completed right on the edge of two windows can be returned by both
+    let mut seen = HashSet::new();
+    Ok(batches
+        .into_iter()
+        .flatten()
+        .filter(|task| seen.insert(task.id.clone()))
+        .collect())
+}
+
+/// Fetches the tasks completed in one window, following the cursor through every page
+async fn completed_tasks_in_window(
+    config: &Config,
+    project: Option<&Project>,
+    filter: Option<&str>,
+    since: DateTime<Tz>,
+    until: DateTime<Tz>,
+) -> Result<Vec<Task>, Error> {
+    let since = encode(
+        &since
+            .with_timezone(&Utc)
+            .format(time::FORMAT_DATETIME_ZULU)
+            .to_string(),
+    )
+    .into_owned();
+    let until = encode(
+        &until
+            .with_timezone(&Utc)
+            .format(time::FORMAT_DATETIME_ZULU)
+            .to_string(),
+    )
+    .into_owned();
+    let mut query = format!("since={since}&until={until}&limit={QUERY_LIMIT}");
+    if let Some(project) = project {
+        query.push_str(&format!("&project_id={}", project.id));
+    }
+    if let Some(filter) = filter {
+        query.push_str(&format!("&filter_query={}", encode(filter)));
+    }
+    let mut tasks = Vec::new();
+    let mut url = format!("{COMPLETED_TASKS_URL}?{query}");
+    let title_regex = config.task_exclude_regex.as_ref();
+
+    loop {
+        let json = request::get_todoist(config, &url, true).await?;
+        let CompletedTaskResponse { items, next_cursor } = CompletedTaskResponse::from_json(&json)?;
+
+        tasks.extend(filter_tasks_by_title(items, title_regex, config));
+
+        match next_cursor {
+            None => break,
+            Some(cursor) => url = format!("{COMPLETED_TASKS_URL}?{query}&cursor={cursor}"),
+        }
+    }
+    Ok(tasks)
+}
+
 /// Fetches a list of tasks by their ids.
 pub async fn all_tasks_by_ids(
     config: &Config,
@@ -371,6 +403,35 @@ pub async fn all_sections_by_project(
     Ok(sections)
 }
 
+pub async fn all_collaborators_by_project(
+    config: &Config,
+    project: &Project,
+    limit: Option<u8>,
+) -> Result<Vec<Collaborator>, Error> {
+    let limit = limit.unwrap_or(QUERY_LIMIT);
+    let project_id = project.id.clone();
+    let mut url = format!("{PROJECTS_URL}/{project_id}/collaborators?limit={limit}");
+    let mut collaborators: Vec<Collaborator> = Vec::new();
+
+    loop {
+        let json = request::get_todoist(config, &url, true).await?;
+        let CollaboratorResponse {
+            results,
+            next_cursor,
+        } = CollaboratorResponse::from_json(&json)?;
+        collaborators.extend(results);
+        match next_cursor {
+            None => break,
+            Some(string) => {
+                url = format!(
+                    "{PROJECTS_URL}/{project_id}/collaborators?limit={limit}&cursor={string}"
+                );
+            }
+        }
+    }
+    Ok(collaborators)
+}
+
 pub async fn all_projects(config: &Config, limit: Option<u8>) -> Result<Vec<Project>, Error> {
     let limit = limit.unwrap_or(QUERY_LIMIT);
     let mut url = format!("{PROJECTS_URL}?limit={limit}");
@@ -393,6 +454,21 @@ pub async fn all_projects(config: &Config, limit: Option<u8>) -> Result<Vec<Proj
     Ok(projects)
 }
 
+/// Looks up the Todoist inbox, which is never imported into config
+pub async fn inbox_project(config: &Config) -> Result<Project, Error> {
+    all_projects(config, None)
+        .await?
+        .into_iter()
+        .find(|project| project.inbox_project == Some(true))
+        .ok_or_else(|| {
+            Error::new(
+                "inbox_project",
+                "Could not find your Todoist inbox, use --project instead",
+            )
+            .with_kind(ErrorKind::NotFound)
+        })
+}
+
 pub async fn all_reminders(config: &Config, limit: Option<u8>) -> Result<Vec<Reminder>, Error> {
     let limit = limit.unwrap_or(QUERY_LIMIT);
     let mut url = format!("{REMINDERS_URL}?limit={limit}");
@@ -440,6 +516,43 @@ pub async fn all_labels(
     Ok(labels)
 }
 
+/// Sets the due date and time of a task from a UTC datetime such as 2025-05-12T16:00:00Z
+pub async fn update_task_due_datetime(
+    config: &Config,
+    task: &Task,
+    due_datetime: &str,
+    spinner: bool,
+) -> Result<String, Error> {
+    let body = json!({ "due_datetime": due_datetime });
+    let url = format!("{TASKS_URL}{}", task.id);
+
+    request::post_todoist(config, &url, body, spinner).await?;
+    Ok("✓".into())
+}
+
+/// Renames a personal label, Todoist updates the tasks that have it
+pub async fn rename_label(
+    config: &Config,
+    label: &Label,
+    name: &str,
+    spinner: bool,
+) -> Result<Label, Error> {
+    let body = json!({ "name": name });
+    let url = format!("{LABELS_URL}/{}", label.id);
+
+    let json = request::post_todoist(config, &url, body, spinner).await?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+/// Deletes a personal label, Todoist removes it from the tasks that have it
+pub async fn delete_label(config: &Config, label: &Label, spinner: bool) -> Result<String, Error> {
+    let url = format!("{LABELS_URL}/{}", label.id);
+    let body = json!({});
+
+    request::delete_todoist(config, &url, body, spinner).await?;
+    Ok("✓".into())
+}
+
 /// Move an task to a different project
 pub async fn move_task_to_project(
     config: &Config,
@@ -471,6 +584,33 @@ pub async fn move_task_to_section(
     Task::from_json(&response)
 }
 
+/// Moves a task out of its section, leaving it in the same project
+pub async fn move_task_out_of_section(
+    config: &Config,
+    task: &Task,
+    spinner: bool,
+) -> Result<Task, Error> {
+    let body = json!({"project_id": task.project_id});
+    let url = format!("{TASKS_URL}{}/move", task.id);
+
+    let response = request::post_todoist(config, &url, body, spinner).await?;
+    Task::from_json(&response)
+}
+
+/// Sets how long a task takes in minutes, leaving its due date as it is
+pub async fn update_task_duration(
+    config: &Config,
+    task: &Task,
+    minutes: u32,
+    spinner: bool,
+) -> Result<String, Error> {
+    let body = json!({ "duration": minutes, "duration_unit": "minute" });
+    let url = format!("{TASKS_URL}{}", task.id);
+
+    request::post_todoist(config, &url, body, spinner).await?;
+    Ok("✓".into())
+}
+
 /// Update the priority of an task by ID
 pub async fn update_task_priority(
     config: &Config,
@@ -521,11 +661,14 @@ pub async fn update_task_due_natural_language(
         due_string
     };
 
-    let body = if let Some(duration) = duration {
+    let mut body = if let Some(duration) = duration {
         json!({ "due_string": due_string, "duration": duration, "duration_unit": "minute" })
     } else {
         json!({ "due_string": due_string })
     };
+    if let Some(language) = config.language()? {
+        body["due_lang"] = Value::String(language.to_owned());
+    }
     let url = format!("{}{}", TASKS_URL, task.id);
 
     request::post_todoist(config, &url, body, spinner).await?;
@@ -558,10 +701,10 @@ pub async fn update_task_deadline(
     let body = match date {
         Some(date) => {
             if !time::is_date(&date) {
-                return Err(Error {
-                    message: "Not a valid date in format YYYY-MM-DD, got: {date}".to_string(),
-                    source: "update_task_deadline".to_string(),
-                });
+                return Err(Error::new(
+                    "update_task_deadline",
+                    "Not a valid date in format YYYY-MM-DD, got: {date}",
+                ));
             }
             json!({"deadline_date": date, "deadline_lang": "en"})
         }
@@ -574,6 +717,20 @@ pub async fn update_task_deadline(
     Ok("✓".into())
 }
 
+/// Remove the deadline from a task by ID
+pub async fn clear_task_deadline(
+    config: &Config,
+    task_id: &str,
+    spinner: bool,
+) -> Result<String, Error> {
+    let body = json!({"deadline_date": null});
+    let url = format!("{TASKS_URL}{task_id}");
+
+    request::post_todoist(config, &url, body, spinner).await?;
+    // Does not pass back a task
+    Ok("✓".into())
+}
+
 /// Update the description of a task by ID
 pub async fn update_task_description(
     config: &Config,
@@ -607,10 +764,20 @@ pub async fn update_task_labels(
 
 /// Complete the last task returned by "next task"
 /// The API does not return any data, so we can't return a new task
-pub async fn complete_task(config: &Config, task_id: &str, spinner: bool) -> Result<String, Error> {
-    let url = format!("{TASKS_URL}{task_id}/close");
-
-    request::post_todoist(config, &url, Value::Null, spinner).await?;
+pub async fn complete_task(config: &Config, task: &Task, spinner: bool) -> Result<String, Error> {
+    let url = format!("{TASKS_URL}{}/close", task.id);
+
+    let description = format!("complete task '{}'", task.content);
+    // A completion queued while offline is an error here, so the webhook only hears about completions Todoist has
+    request::post_todoist_or_queue(config, &url, Value::Null, spinner, &description).await?;
+    if let Some(Err(e)) = maybe_send_completion_webhook(config, task).await {
+        // The completion stands, so the webhook failing is only a warning
+        let text = format!(
+            "Completed '{}' but the completion webhook failed: {e}",
+            task.content
+        );
+        debug::log(config, LogLevel::Warn, &text);
+    }
 
     if !cfg!(test) {
         maybe_run_command(config.task_complete_command.as_deref(), config)?;
@@ -622,6 +789,38 @@ pub async fn complete_task(config: &Config, task_id: &str, spinner: bool) -> Res
     Ok("✓".into())
 }
 
+/// Posts the completed task to `completion_webhook` when it is set, None when it isn't.
+/// Waits at most `WEBHOOK_TIMEOUT` so that a slow webhook doesn't hold up the command
+async fn maybe_send_completion_webhook(config: &Config, task: &Task) -> Option<Result<(), Error>> {
+    let url = config.completion_webhook.clone()?;
+    let project = config
+        .projects()
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .find(|project| project.id == task.project_id)
+        .map(|project| project.name);
+    let body = json!({
+        "id": task.id,
+        "content": task.content,
+        "project_id": task.project_id,
+        "project": project,
+    });
+
+    let result = tokio::time::timeout(WEBHOOK_TIMEOUT, request::post_json(config, &url, &body))
+        .await
+        .unwrap_or_else(|_| {
+            Err(Error::new(
+                "completion_webhook",
+                &format!(
+                    "{url} did not respond within {}s",
+                    WEBHOOK_TIMEOUT.as_secs()
+                ),
+            ))
+        });
+    Some(result)
+}
+
 pub async fn delete_task(config: &Config, task_id: &str, spinner: bool) -> Result<String, Error> {
     let body = json!({});
     let url = format!("{TASKS_URL}{task_id}");
@@ -641,6 +840,43 @@ pub async fn delete_project(
     request::delete_todoist(config, &url, body, spinner).await?;
     Ok("✓".into())
 }
+
+/// Archive a project in Todoist, archived projects keep their tasks but are hidden
+pub async fn archive_project(
+    config: &Config,
+    project: &Project,
+    spinner: bool,
+) -> Result<String, Error> {
+    let url = format!("{}/{}/archive", PROJECTS_URL, project.id);
+    let body = json!({});
+
+    request::post_todoist(config, &url, body, spinner).await?;
+    Ok("✓".into())
+}
+
+/// Change the name of a project in Todoist, returns the updated project
+pub async fn rename_project(
+    config: &Config,
+    project: &Project,
+    name: &str,
+    spinner: bool,
+) -> Result<Project, Error> {
+    update_project(config, project, json!({"name": name}), spinner).await
+}
+
+/// Sets the fields in `body` on a project in Todoist, returning the updated project
+pub async fn update_project(
+    config: &Config,
+    project: &Project,
+    body: Value,
+    spinner: bool,
+) -> Result<Project, Error> {
+    let url = format!("{}/{}", PROJECTS_URL, project.id);
+
+    let json = request::post_todoist(config, &url, body, spinner).await?;
+    Project::from_json(&json)
+}
+
 pub async fn create_project(
     config: &Config,
     name: &str,
@@ -672,9 +908,13 @@ pub async fn create_comment(
     config: &Config,
     task_id: &str,
     content: &str,
+    attachment: Option<&FileAttachment>,
     spinner: bool,
 ) -> Result<Comment, Error> {
-    let body = json!({"task_id": task_id, "content": content});
+    let mut body = json!({"task_id": task_id, "content": content});
+    if let Some(attachment) = attachment {
+        body["attachment"] = json!(attachment);
+    }
     let url = COMMENTS_URL.to_string();
 
     let response = request::post_todoist(config, &url, body, spinner).await?;
@@ -682,6 +922,59 @@ pub async fn create_comment(
     Comment::from_json(&response)
 }
 
+/// Checks that a file exists and is small enough to attach to a comment, returns its size in bytes
+pub async fn validate_upload(path: &Path) -> Result<u64, Error> {
+    let metadata = tokio::fs::metadata(path).await.map_err(|_| {
+        Error::new(
+            "validate_upload",
+            &format!("File not found: {}", path.display()),
+        )
+    })?;
+
+    if !metadata.is_file() {
+        return Err(Error::new(
+            "validate_upload",
+            &format!("Not a file: {}", path.display()),
+        ));
+    }
+
+    let size = metadata.len();
+    if size > MAX_UPLOAD_BYTES {
+        return Err(Error::new(
+            "validate_upload",
+            &format!(
+                "{} is {size} bytes, Todoist accepts files up to {MAX_UPLOAD_BYTES} bytes",
+                path.display()
+            ),
+        ));
+    }
+
+    Ok(size)
+}
+
+/// Uploads a file so that it can be attached to a comment
+pub async fn upload_file(
+    config: &Config,
+    path: &Path,
+    spinner: bool,
+) -> Result<FileAttachment, Error> {
+    validate_upload(path).await?;
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .ok_or_else(|| {
+            Error::new(
+                "upload_file",
+                &format!("Could not get file name from {}", path.display()),
+            )
+        })?;
+    let contents = tokio::fs::read(path).await?;
+
+    let json =
+        request::post_todoist_file(config, UPLOADS_URL, &file_name, contents, spinner).await?;
+    FileAttachment::from_json(&json)
+}
+
 pub async fn get_user_data(config: &Config) -> Result<User, Error> {
     let url = USER_URL.to_string();
     let json = request::get_todoist(config, &url, true).await?;
@@ -803,6 +1096,36 @@ mod tests {
         mock.assert();
     }
 
+    #[tokio::test]
+    async fn test_inbox_project() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api/v1/projects?limit=200")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::InboxProjects.read().await)
+            .create_async()
+            .await;
+
+        let config = test::fixtures::config().await.with_mock_url(server.url());
+        let inbox = inbox_project(&config).await.expect("inbox should be found");
+        assert_eq!(inbox.name, "Inbox");
+        mock.assert();
+
+        let mock = server
+            .mock("GET", "/api/v1/projects?limit=200")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::Projects.read().await)
+            .create_async()
+            .await;
+        let error = inbox_project(&config)
+            .await
+            .expect_err("projects without an inbox should fail");
+        assert_eq!(error.kind, ErrorKind::NotFound);
+        mock.assert();
+    }
+
     #[tokio::test]
     async fn test_quick_create_task() {
         let mut server = mockito::Server::new_async().await;
@@ -820,7 +1143,7 @@ mod tests {
             .with_time_provider(TimeProviderEnum::Fixed(FixedTimeProvider));
 
         assert_eq!(
-            quick_create_task(&config, "testy test", None).await,
+            quick_create_task(&config, "testy test", None, None).await,
             Ok(test::fixtures::today_task().await)
         );
         mock.assert();
@@ -875,13 +1198,54 @@ mod tests {
                 priority,
                 "",
                 None,
-                &[]
+                None,
+                &[],
+                None
             )
             .await,
             Ok(test::fixtures::today_task().await)
         );
         mock.assert();
     }
+
+    #[tokio::test]
+    async fn test_create_task_with_assignee() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/api/v1/tasks/")
+            .match_body(mockito::Matcher::PartialJson(
+                json!({"responsible_uid": "2671355"}),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::TodayTask.read().await)
+            .create_async()
+            .await;
+
+        let config = test::fixtures::config().await.with_mock_url(server.url());
+        let project = test::fixtures::project();
+        let assignee = Collaborator {
+            id: "2671355".to_string(),
+            name: "Alice Smith".to_string(),
+            email: "alice@example.com".to_string(),
+        };
+
+        let result = create_task(
+            &config,
+            "New task",
+            &project,
+            None,
+            priority::Priority::None,
+            "",
+            None,
+            None,
+            &[],
+            Some(&assignee),
+        )
+        .await;
+        assert_eq!(result, Ok(test::fixtures::today_task().await));
+        mock.assert();
+    }
     #[tokio::test]
     async fn test_create_section() {
         let mut server = mockito::Server::new_async().await;
@@ -919,12 +1283,83 @@ mod tests {
         let task = test::fixtures::today_task().await;
         let comment = test::fixtures::comment();
         assert_eq!(
-            create_comment(&config, &task.id, "New comment", true).await,
+            create_comment(&config, &task.id, "New comment", None, true).await,
             Ok(comment)
         );
         mock.assert();
     }
 
+    #[tokio::test]
+    async fn test_upload_file_and_attach_to_comment() {
+        let mut server = mockito::Server::new_async().await;
+        let upload_mock = server
+            .mock("POST", "/api/v1/uploads")
+            .match_header(
+                "content-type",
+                mockito::Matcher::Regex("^multipart/form-data; boundary=".to_string()),
+            )
+            .match_body(mockito::Matcher::Regex("receipt contents".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"file_name": "receipt.txt", "file_size": 16, "file_type": "text/plain", "file_url": "https://files.todoist.com/receipt.txt", "resource_type": "file", "upload_state": "completed"}"#,
+            )
+            .create_async()
+            .await;
+        let comment_mock = server
+            .mock("POST", "/api/v1/comments/")
+            .match_body(mockito::Matcher::PartialJson(json!({
+                "attachment": {"file_name": "receipt.txt", "resource_type": "file"}
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::Comment.read().await)
+            .create_async()
+            .await;
+
+        let dir = tempfile::tempdir().expect("temp dir should be created");
+        let path = dir.path().join("receipt.txt");
+        tokio::fs::write(&path, "receipt contents")
+            .await
+            .expect("file should be written");
+        let config = test::fixtures::config().await.with_mock_url(server.url());
+
+        let attachment = upload_file(&config, &path, false)
+            .await
+            .expect("upload should succeed");
+        assert_eq!(attachment.file_name, "receipt.txt");
+        create_comment(
+            &config,
+            "6Xqhv4cwxgjwG9w8",
+            "Receipt",
+            Some(&attachment),
+            false,
+        )
+        .await
+        .expect("comment should be created");
+        upload_mock.assert();
+        comment_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_validate_upload_rejects_large_and_missing_files() {
+        let dir = tempfile::tempdir().expect("temp dir should be created");
+        let path = dir.path().join("large.bin");
+        let file = std::fs::File::create(&path).expect("file should be created");
+        file.set_len(MAX_UPLOAD_BYTES + 1)
+            .expect("file size should be set");
+
+        let error = validate_upload(&path)
+            .await
+            .expect_err("oversized file should fail");
+        assert!(error.message.contains("Todoist accepts files up to"));
+
+        let error = validate_upload(&dir.path().join("missing.bin"))
+            .await
+            .expect_err("missing file should fail");
+        assert!(error.message.starts_with("File not found"));
+    }
+
     #[tokio::test]
     async fn test_all_tasks_by_project() {
         let mut server = mockito::Server::new_async().await;
@@ -955,6 +1390,34 @@ mod tests {
         mock.assert();
     }
 
+    #[tokio::test]
+    async fn all_completed_tasks_fetches_each_window_once() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock(
+                "GET",
+                mockito::Matcher::Regex(
+                    r"^/api/v1/tasks/completed/by_completion_date\?since=".to_string(),
+                ),
+            )
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::CompletedTasks.read().await)
+            .expect(3)
+            .create_async()
+            .await;
+        let config = test::fixtures::config().await.with_mock_url(server.url());
+        let until = time::datetime_now(&config).expect("now should be known");
+        let since = until - chrono::Duration::days(5);
+
+        let tasks = all_completed_tasks(&config, None, None, since, until, Some(2))
+            .await
+            .expect("completed tasks should be fetched");
+        // Every window returns the same task, which is only kept once
+        assert_eq!(tasks.len(), 1);
+        mock.assert_async().await;
+    }
+
     #[tokio::test]
     async fn test_complete_task() {
         let mut server = mockito::Server::new_async().await;
@@ -969,13 +1432,109 @@ mod tests {
         let config = test::fixtures::config().await.with_mock_url(server.url());
 
         let task = test::fixtures::today_task().await;
-        let response = complete_task(&config, &task.id, false)
+        let response = complete_task(&config, &task, false)
             .await
             .expect("Did not complete task");
         mock.assert();
         assert_eq!(response, String::from("✓"));
     }
 
+    #[tokio::test]
+    async fn completion_webhook_posts_the_task_and_project_name() {
+        let mut server = mockito::Server::new_async().await;
+        let webhook_mock = server
+            .mock("POST", "/webhook")
+            .match_body(mockito::Matcher::Json(json!({
+                "id": "6Xqhv4cwxgjwG9w8",
+                "content": "TEST",
+                "project_id": "6VRRxv8CM6GVmmgf",
+                "project": "Inbox",
+            })))
+            .with_status(200)
+            .create_async()
+            .await;
+
+        let project = Project {
+            id: "6VRRxv8CM6GVmmgf".to_string(),
+            name: "Inbox".to_string(),
+            ..test::fixtures::project()
+        };
+        let mut config = test::fixtures::config()
+            .await
+            .with_mock_url(server.url())
+            .with_projects(vec![project]);
+        config.completion_webhook = Some(format!("{}/webhook", server.url()));
+
+        let task = test::fixtures::today_task().await;
+        let result = maybe_send_completion_webhook(&config, &task).await;
+        assert_eq!(result, Some(Ok(())));
+        webhook_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn completion_webhook_is_skipped_when_unset() {
+        let config = test::fixtures::config().await;
+        let task = test::fixtures::today_task().await;
+        assert!(
+            maybe_send_completion_webhook(&config, &task)
+                .await
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn complete_task_reports_webhook_failure_without_failing() {
+        let mut server = mockito::Server::new_async().await;
+        let close_mock = server
+            .mock("POST", "/api/v1/tasks/6Xqhv4cwxgjwG9w8/close")
+            .with_status(204)
+            .create_async()
+            .await;
+        let webhook_mock = server
+            .mock("POST", "/webhook")
+            .with_status(500)
+            .create_async()
+            .await;
+
+        let dir = tempfile::tempdir().expect("temp dir should be created");
+        let log_file = dir.path().join("tod.log");
+        let mut config = test::fixtures::config().await.with_mock_url(server.url());
+        config.completion_webhook = Some(format!("{}/webhook", server.url()));
+        config.args.log_file = Some(log_file.clone());
+
+        let task = test::fixtures::today_task().await;
+        let response = complete_task(&config, &task, false).await;
+        assert_eq!(response, Ok(String::from("✓")));
+        let log = std::fs::read_to_string(log_file).expect("log should be readable");
+        assert!(log.contains("WARN Completed 'TEST' but the completion webhook failed"));
+        close_mock.assert();
+        webhook_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn complete_task_skips_completion_webhook_when_queued() {
+        let mut webhook_server = mockito::Server::new_async().await;
+        let webhook_mock = webhook_server
+            .mock("POST", "/webhook")
+            .expect(0)
+            .create_async()
+            .await;
+
+        // Nothing listens on port 1, so the completion is queued for later
+        let mut config = test::fixtures::config()
+            .await
+            .with_mock_url("http://127.0.0.1:1".to_string());
+        config.completion_webhook = Some(format!("{}/webhook", webhook_server.url()));
+
+        let task = test::fixtures::today_task().await;
+        let error = complete_task(&config, &task, false)
+            .await
+            .expect_err("an unreachable Todoist should queue the completion");
+        assert_eq!(error.kind, ErrorKind::Network);
+        tokio::task::yield_now().await;
+        webhook_mock.assert();
+    }
+
     #[tokio::test]
     async fn test_move_task_to_project() {
         let mut server = mockito::Server::new_async().await;
@@ -1210,6 +1769,30 @@ mod tests {
         assert_eq!(response, Ok(String::from("✓")));
     }
 
+    #[tokio::test]
+    async fn test_update_task_due_natural_language_sends_language() {
+        let task = test::fixtures::today_task().await;
+        let url: &str = &format!("{}{}", "/api/v1/tasks/", task.id);
+        let mut server = mockito::Server::new_async().await;
+
+        let mock = server
+            .mock("POST", url)
+            .match_body(mockito::Matcher::Json(
+                json!({"due_string": "lunes", "due_lang": "es"}),
+            ))
+            .with_status(204)
+            .create_async()
+            .await;
+
+        let mut config = test::fixtures::config().await.with_mock_url(server.url());
+        config.language = Some("es".to_string());
+
+        let response =
+            update_task_due_natural_language(&config, &task, "lunes".to_string(), None, true).await;
+        mock.assert();
+        assert_eq!(response, Ok(String::from("✓")));
+    }
+
     #[tokio::test]
     async fn test_all_comments_filters_deleted() {
         let mut server = mockito::Server::new_async().await;
@@ -0,0 +1,353 @@
+//! Requests that could not reach Todoist, kept in a file next to the config so that they survive a restart.
+//! They are sent by the next command that loads the config, or by `tod config flush-queue`
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::PathBuf;
+use tokio::fs;
+use tokio::sync::Mutex;
+
+use super::request;
+use crate::cleanup;
+use crate::config::Config;
+use crate::errors::{Error, ErrorKind};
+use crate::format;
+
+/// Held while the queue file is read and written, tasks completed in the background can queue at the same time
+static QUEUE_LOCK: Mutex<()> = Mutex::const_new(());
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueuedRequest {
+    /// Path of the POST request, without the Todoist base url
+    pub url: String,
+    pub body: Value,
+    /// Sent again as the X-Request-Id so that Todoist ignores the request if the first attempt got through
+    pub request_id: String,
+    /// What the request does, i.e. "complete task 'Buy milk'"
+    pub description: String,
+}
+
+/// What happened to the queued requests when they were sent
+#[derive(Debug, Default)]
+pub struct FlushResult {
+    /// Descriptions of the requests that Todoist accepted
+    pub sent: Vec<String>,
+    /// Requests that Todoist rejected, they are dropped from the queue
+    pub failed: Vec<(String, Error)>,
+    /// Number of requests left in the queue because Todoist still could not take them
+    pub remaining: usize,
+    /// Why sending stopped, i.e. Todoist could not be reached or was failing on its side
+    pub stopped_by: Option<Error>,
+}
+
+impl FlushResult {
+    /// True when nothing was sent or dropped
+    pub fn is_unchanged(&self) -> bool {
+        self.sent.is_empty() && self.failed.is_empty()
+    }
+
+    pub fn summary(&self) -> String {
+        let mut lines = Vec::new();
+        if !self.sent.is_empty() {
+            lines.push(format::green_string(&format!(
+                "Sent {} queued request(s) to Todoist:",
+                self.sent.len()
+            )));
+            lines.extend(self.sent.iter().map(|sent| format!("- {sent}")));
+        }
+        if !self.failed.is_empty() {
+            lines.push(format::red_string(&format!(
+                "Todoist rejected {} queued request(s), they were removed from the queue:",
+                self.failed.len()
+            )));
+            lines.extend(
+                self.failed
+                    .iter()
+                    .map(|(failed, error)| format!("- {failed}: {}", error.message)),
+            );
+        }
+        if self.remaining > 0 {
+            let reason = match &self.stopped_by {
+                Some(error) if error.kind != ErrorKind::Network => {
+                    format!("Todoist could not take them right now\n{}", error.message)
+                }
+                _ => String::from("Todoist could not be reached"),
+            };
+            lines.push(format::yellow_string(&format!(
+                "{} request(s) are still queued, {reason}",
+                self.remaining
+            )));
+        }
+        if lines.is_empty() {
+            return String::from("No queued requests");
+        }
+        lines.join("\n")
+    }
+}
+
+/// Adds a request to the end of the queue, returning how many requests are queued
+pub async fn push(config: &Config, request: QueuedRequest) -> Result<usize, Error> {
+    let _lock = QUEUE_LOCK.lock().await;
+    let mut requests = load(config).await?;
+    requests.push(request);
+    save(config, &requests).await?;
+    Ok(requests.len())
+}
+
+/// The error shown in place of the result of a request that was queued
+pub fn queued_error(description: &str, count: usize, error: &Error) -> Error {
+    let command = format::blue_string("tod config flush-queue");
+    Error::new(
+        "queue",
+        &format!(
+            "Todoist could not be reached, so {description} was queued ({count} request(s) queued).\nIt will be sent by the next command, or run {command} to send it now.\n{}",
+            error.message
+        ),
+    )
+    .with_kind(ErrorKind::Network)
+}
+
+/// Sends the queued requests in the order they were queued.
+/// Stops at the first one that can't reach Todoist or that Todoist can't take yet, such as when the token
+/// was rejected or on a server error, leaving it and the rest queued
+pub async fn flush_queue(config: &Config) -> Result<FlushResult, Error> {
+    let _lock = QUEUE_LOCK.lock().await;
+    let requests = load(config).await?;
+    let mut result = FlushResult::default();
+    if requests.is_empty() {
+        return Ok(result);
+    }
+
+    let mut requests = requests.into_iter();
+    let mut remaining = Vec::new();
+    for queued in requests.by_ref() {
+        match request::post_todoist_queued(config, &queued).await {
+            Ok(_) => result.sent.push(queued.description),
+            Err((error, true)) => {
+                remaining.push(queued);
+                result.stopped_by = Some(error);
+                break;
+            }
+            Err((error, false)) => result.failed.push((queued.description, error)),
+        }
+    }
+    remaining.extend(requests);
+    result.remaining = remaining.len();
+
+    if !result.is_unchanged() {
+        save(config, &remaining).await?;
+    }
+    Ok(result)
+}
+
+fn queue_path(config: &Config) -> PathBuf {
+    config.path.with_extension("queue.json")
+}
+
+async fn load(config: &Config) -> Result<Vec<QueuedRequest>, Error> {
+    let path = queue_path(config);
+    let json = match fs::read_to_string(&path).await {
+        Ok(json) => json,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    serde_json::from_str(&json).map_err(|e| {
+        Error::new(
+            "queue",
+            &format!("Could not read the queue at {}: {e}", path.display()),
+        )
+    })
+}
+
+/// Writes the queue, removing the file once it is empty
+async fn save(config: &Config, requests: &[QueuedRequest]) -> Result<(), Error> {
+    let path = queue_path(config);
+    if requests.is_empty() {
+        return match fs::remove_file(&path).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        };
+    }
+
+    let json = serde_json::to_string_pretty(requests)?;
+    cleanup::write_file(&path, &json).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test;
+    use serde_json::json;
+
+    // Nothing listens on port 1, so requests fail the same way they do without a connection
+    const UNREACHABLE_URL: &str = "http://127.0.0.1:1";
+
+    fn queued_request(description: &str) -> QueuedRequest {
+        QueuedRequest {
+            url: "/api/v1/tasks/".to_string(),
+            body: json!({"content": description}),
+            request_id: format!("request-{description}"),
+            description: format!("create task '{description}'"),
+        }
+    }
+
+    #[tokio::test]
+    async fn push_adds_to_the_end_of_the_queue() {
+        let config = test::fixtures::config().await;
+
+        let count = push(&config, queued_request("milk"))
+            .await
+            .expect("request should be queued");
+        assert_eq!(count, 1);
+        let count = push(&config, queued_request("bread"))
+            .await
+            .expect("request should be queued");
+        assert_eq!(count, 2);
+
+        let queued = load(&config).await.expect("queue should load");
+        assert_eq!(
+            queued,
+            vec![queued_request("milk"), queued_request("bread")]
+        );
+    }
+
+    #[test]
+    fn queued_error_explains_how_to_send_it() {
+        let network_error =
+            Error::new("reqwest", "error sending request").with_kind(ErrorKind::Network);
+
+        let error = queued_error("create task 'milk'", 2, &network_error);
+        assert_eq!(error.kind, ErrorKind::Network);
+        assert!(
+            error
+                .message
+                .contains("create task 'milk' was queued (2 request(s) queued)")
+        );
+        assert!(error.message.contains("tod config flush-queue"));
+        assert!(error.message.contains("error sending request"));
+    }
+
+    #[tokio::test]
+    async fn flush_queue_sends_requests_with_their_request_id() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/api/v1/tasks/")
+            .match_header("X-Request-Id", "request-milk")
+            .match_body(mockito::Matcher::Json(json!({"content": "milk"})))
+            .with_status(200)
+            .with_body("{}")
+            .expect(1)
+            .create_async()
+            .await;
+        let config = test::fixtures::config().await.with_mock_url(server.url());
+        save(&config, &[queued_request("milk")])
+            .await
+            .expect("queue should save");
+
+        let result = flush_queue(&config).await.expect("flush should succeed");
+        assert_eq!(result.sent, vec!["create task 'milk'"]);
+        assert_eq!(result.remaining, 0);
+        assert!(
+            result
+                .summary()
+                .contains("Sent 1 queued request(s) to Todoist")
+        );
+        assert!(!queue_path(&config).exists());
+        mock.assert();
+
+        let result = flush_queue(&config)
+            .await
+            .expect("empty flush should succeed");
+        assert!(result.is_unchanged());
+        assert_eq!(result.summary(), "No queued requests");
+    }
+
+    #[tokio::test]
+    async fn flush_queue_keeps_requests_while_todoist_is_unreachable() {
+        let config = test::fixtures::config()
+            .await
+            .with_mock_url(UNREACHABLE_URL.to_string());
+        let requests = vec![queued_request("milk"), queued_request("bread")];
+        save(&config, &requests).await.expect("queue should save");
+
+        let result = flush_queue(&config).await.expect("flush should succeed");
+        assert!(result.is_unchanged());
+        assert_eq!(result.remaining, 2);
+        assert_eq!(load(&config).await.expect("queue should load"), requests);
+    }
+
+    #[tokio::test]
+    async fn flush_queue_keeps_requests_on_a_server_error() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/api/v1/tasks/")
+            .with_status(503)
+            .with_body("service unavailable")
+            .expect(1)
+            .create_async()
+            .await;
+        let config = test::fixtures::config().await.with_mock_url(server.url());
+        let requests = vec![queued_request("milk"), queued_request("bread")];
+        save(&config, &requests).await.expect("queue should save");
+
+        let result = flush_queue(&config).await.expect("flush should succeed");
+        assert!(result.is_unchanged());
+        assert_eq!(result.remaining, 2);
+        assert!(
+            result
+                .summary()
+                .contains("Todoist could not take them right now")
+        );
+        assert_eq!(load(&config).await.expect("queue should load"), requests);
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn flush_queue_keeps_requests_when_the_token_is_rejected() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/api/v1/tasks/")
+            .with_status(401)
+            .expect(1)
+            .create_async()
+            .await;
+        let config = test::fixtures::config().await.with_mock_url(server.url());
+        let requests = vec![queued_request("milk")];
+        save(&config, &requests).await.expect("queue should save");
+
+        let result = flush_queue(&config).await.expect("flush should succeed");
+        assert!(result.failed.is_empty());
+        assert_eq!(result.remaining, 1);
+        assert_eq!(
+            result.stopped_by.map(|error| error.kind),
+            Some(ErrorKind::Auth)
+        );
+        assert_eq!(load(&config).await.expect("queue should load"), requests);
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn flush_queue_drops_requests_todoist_rejects() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/api/v1/tasks/")
+            .with_status(400)
+            .with_body("bad request")
+            .create_async()
+            .await;
+        let config = test::fixtures::config().await.with_mock_url(server.url());
+        save(&config, &[queued_request("milk")])
+            .await
+            .expect("queue should save");
+
+        let result = flush_queue(&config).await.expect("flush should succeed");
+        assert_eq!(result.failed.len(), 1);
+        assert!(
+            result
+                .summary()
+                .contains("Todoist rejected 1 queued request(s)")
+        );
+        assert!(!queue_path(&config).exists());
+        mock.assert();
+    }
+}
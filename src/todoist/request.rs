@@ -1,7 +1,8 @@
 use std::env;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use reqwest::Client;
+use reqwest::RequestBuilder;
 use reqwest::Response;
 use reqwest::header::AUTHORIZATION;
 use reqwest::header::CONTENT_TYPE;
@@ -11,13 +12,14 @@ use spinners::Spinner;
 use spinners::Spinners;
 use uuid::Uuid;
 
-use crate::config::Args;
 use crate::config::Config;
 use crate::config::DEFAULT_TIMEOUT_SECONDS;
 use crate::debug;
-use crate::errors::Error;
+use crate::errors::{Error, ErrorKind};
 use crate::format;
+use crate::oauth;
 use crate::todoist::REMINDERS_URL;
+use crate::todoist::queue::{self, QueuedRequest};
 
 const FAKE_UUID: &str = "42963283-2bab-4b1f-bad2-278ef2b6ba2c";
 const TODOIST_URL: &str = "https://api.todoist.com";
@@ -28,6 +30,8 @@ const SPINNER: Spinners = Spinners::Dots4;
 const MESSAGE: &str = "Querying API";
 const HTTP_UNAUTHORIZED: u16 = 401;
 const HTTP_FORBIDDEN: u16 = 403;
+const HTTP_NOT_FOUND: u16 = 404;
+const HTTP_TOO_MANY_REQUESTS: u16 = 429;
 
 /// Post to Todoist via REST api
 /// We use this when we want more options and don't need natural language processing
@@ -38,29 +42,91 @@ pub async fn post_todoist(
     body: serde_json::Value,
     spinner: bool,
 ) -> Result<String, Error> {
+    post_todoist_with_request_id(config, url, body, spinner, &new_uuid()).await
+}
+
+/// Post to Todoist, queueing the request when Todoist can't be reached so that a later command sends it.
+/// Only use this for requests that are safe to send twice, Todoist ignores a request id it has already seen
+pub async fn post_todoist_or_queue(
+    config: &Config,
+    url: &str,
+    body: serde_json::Value,
+    spinner: bool,
+    description: &str,
+) -> Result<String, Error> {
+    let request_id = new_uuid();
+    match post_todoist_with_request_id(config, url, body.clone(), spinner, &request_id).await {
+        Err(error) if error.kind == ErrorKind::Network => {
+            let request = QueuedRequest {
+                url: url.to_string(),
+                body,
+                request_id,
+                description: description.to_string(),
+            };
+            let count = queue::push(config, request).await?;
+            Err(queue::queued_error(description, count, &error))
+        }
+        result => result,
+    }
+}
+
+/// Post to Todoist with the X-Request-Id given, so that a request sent again is recognised as a repeat
+pub async fn post_todoist_with_request_id(
+    config: &Config,
+    url: &str,
+    body: serde_json::Value,
+    spinner: bool,
+    request_id: &str,
+) -> Result<String, Error> {
+    let spinner = maybe_start_spinner(config, spinner);
+    let response = send_post(config, url, &body, request_id).await?;
+    maybe_stop_spinner(spinner);
+    handle_response(config, response, "POST", url, body).await
+}
+
+/// Post a request from the queue again, with the X-Request-Id it was first sent with.
+/// The error is paired with true when the request should stay queued, because Todoist could not be reached,
+/// rejected the token, or was rate limiting or failing on its side
+pub async fn post_todoist_queued(
+    config: &Config,
+    queued: &QueuedRequest,
+) -> Result<String, (Error, bool)> {
+    let response = send_post(config, &queued.url, &queued.body, &queued.request_id)
+        .await
+        .map_err(|e| (e, true))?;
+    let keep = send_again_later(response.status().as_u16());
+    handle_response(config, response, "POST", &queued.url, queued.body.clone())
+        .await
+        .map_err(|e| (e, keep))
+}
+
+async fn send_post(
+    config: &Config,
+    url: &str,
+    body: &Value,
+    request_id: &str,
+) -> Result<Response, Error> {
     let base_url = get_base_url(config);
-    let token = get_token(config)?;
 
     let request_url = format!("{base_url}{url}");
-    let authorization = format!("Bearer {token}");
-    let spinner = maybe_start_spinner(config, spinner);
 
     debug::maybe_print(config, &format!("POST {request_url}\nbody: {body}"));
 
-    let client = Client::new()
-        .post(request_url.clone())
-        .header(CONTENT_TYPE, "application/json")
-        .header(AUTHORIZATION, authorization)
-        .header("X-Request-Id", new_uuid())
-        .timeout(get_timeout(config));
+    send_with_token(config, "POST", &request_url, |token| {
+        let client = Client::new()
+            .post(request_url.clone())
+            .header(CONTENT_TYPE, "application/json")
+            .header(AUTHORIZATION, format!("Bearer {token}"))
+            .header("X-Request-Id", request_id)
+            .timeout(get_timeout(config));
 
-    let response = match &body {
-        Value::Null => client.send().await?,
+        match body {
+            Value::Null => client,
 
-        body => client.json(&body).send().await?,
-    };
-    maybe_stop_spinner(spinner);
-    handle_response(config, response, "POST", url, body).await
+            body => client.json(body),
+        }
+    })
+    .await
 }
 
 pub async fn post_todoist_no_token(
@@ -81,20 +147,124 @@ pub async fn post_todoist_no_token(
         .header("X-Request-Id", new_uuid())
         .timeout(get_timeout(config));
 
-    let response = match &body {
-        Value::Null => client.send().await?,
+    let request = match &body {
+        Value::Null => client,
 
-        body => client.json(&body).send().await?,
+        body => client.json(&body),
     };
+    let response = send_logged(config, "POST", &request_url, None, request).await?;
     maybe_stop_spinner(spinner);
     handle_response(config, response, "POST", url, body).await
 }
 
-fn get_token(config: &Config) -> Result<String, Error> {
-    config
-        .token
-        .clone()
-        .ok_or_else(|| Error::new("post_todoist", "No token, use auth login to set your token"))
+/// Upload a file to Todoist as multipart form data, under the form field `file`
+pub async fn post_todoist_file(
+    config: &Config,
+    url: &str,
+    file_name: &str,
+    contents: Vec<u8>,
+    spinner: bool,
+) -> Result<String, Error> {
+    let base_url = get_base_url(config);
+
+    let request_url = format!("{base_url}{url}");
+    let spinner = maybe_start_spinner(config, spinner);
+
+    debug::maybe_print(
+        config,
+        &format!(
+            "POST {request_url}\nfile: {file_name} ({} bytes)",
+            contents.len()
+        ),
+    );
+
+    // Built by hand so that uploads don't need reqwest's multipart feature
+    let boundary = format!("tod-{}", new_uuid());
+    let mut body = format!(
+        "--{boundary}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{}\"\r\nContent-Type: application/octet-stream\r\n\r\n",
+        file_name.replace('"', "")
+    )
+    .into_bytes();
+    body.extend_from_slice(&contents);
+    body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+
+    let response = send_with_token(config, "POST", &request_url, |token| {
+        Client::new()
+            .post(request_url.clone())
+            .header(
+                CONTENT_TYPE,
+                format!("multipart/form-data; boundary={boundary}"),
+            )
+            .header(AUTHORIZATION, format!("Bearer {token}"))
+            .header("X-Request-Id", new_uuid())
+            .body(body.clone())
+            .timeout(get_timeout(config))
+    })
+    .await?;
+    maybe_stop_spinner(spinner);
+    handle_response(config, response, "POST", url, json!({"file": file_name})).await
+}
+
+/// Gets the token for a request, refreshing it first if it is a known expired OAuth token
+async fn get_token(config: &Config) -> Result<String, Error> {
+    let refreshed_token = config.internal.refreshed_token.lock().await.clone();
+    if let Some(token) = refreshed_token {
+        return Ok(token);
+    }
+
+    let token = config.token.clone().ok_or_else(|| {
+        Error::new("post_todoist", "No token, use auth login to set your token")
+            .with_kind(ErrorKind::Auth)
+    })?;
+
+    if config.token_expired() && config.refresh_token.is_some() {
+        oauth::refresh(config, &token).await
+    } else {
+        Ok(token)
+    }
+}
+
+/// Sends the request built with the current token.
+/// If Todoist rejects the token and it can be refreshed, refreshes it and retries once.
+async fn send_with_token<F>(
+    config: &Config,
+    method: &str,
+    request_url: &str,
+    build_request: F,
+) -> Result<Response, Error>
+where
+    F: Fn(&str) -> RequestBuilder,
+{
+    let token = get_token(config).await?;
+    let request = build_request(&token);
+    let response = send_logged(config, method, request_url, Some(&token), request).await?;
+
+    if response.status().as_u16() == HTTP_UNAUTHORIZED && config.refresh_token.is_some() {
+        let token = oauth::refresh(config, &token).await?;
+        let request = build_request(&token);
+        send_logged(config, method, request_url, Some(&token), request).await
+    } else {
+        Ok(response)
+    }
+}
+
+/// Sends the request and logs a summary of it with the response status and duration
+async fn send_logged(
+    config: &Config,
+    method: &str,
+    request_url: &str,
+    token: Option<&str>,
+    request: RequestBuilder,
+) -> Result<Response, Error> {
+    let start = Instant::now();
+    let result = request.send().await;
+    let status = result
+        .as_ref()
+        .ok()
+        .map(|response| response.status().as_u16());
+    debug::log_request(config, method, request_url, status, start.elapsed(), token);
+
+    Ok(result?)
 }
 
 pub async fn delete_todoist(
@@ -104,23 +274,22 @@ pub async fn delete_todoist(
     spinner: bool,
 ) -> Result<String, Error> {
     let base_url = get_base_url(config);
-    let token = get_token(config)?;
 
     let request_url = format!("{base_url}{url}");
-    let authorization = format!("Bearer {token}");
     let spinner = maybe_start_spinner(config, spinner);
 
     debug::maybe_print(config, &format!("DELETE {request_url}\nbody: {body}"));
 
-    let response = Client::new()
-        .delete(request_url.clone())
-        .header(CONTENT_TYPE, "application/json")
-        .header(AUTHORIZATION, authorization)
-        .header("X-Request-Id", new_uuid())
-        .json(&body)
-        .timeout(get_timeout(config))
-        .send()
-        .await?;
+    let response = send_with_token(config, "DELETE", &request_url, |token| {
+        Client::new()
+            .delete(request_url.clone())
+            .header(CONTENT_TYPE, "application/json")
+            .header(AUTHORIZATION, format!("Bearer {token}"))
+            .header("X-Request-Id", new_uuid())
+            .json(&body)
+            .timeout(get_timeout(config))
+    })
+    .await?;
 
     maybe_stop_spinner(spinner);
     handle_response(config, response, "DELETE", url, body).await
@@ -130,22 +299,18 @@ pub async fn delete_todoist(
 /// Get Todoist via REST api
 pub async fn get_todoist(config: &Config, url: &str, spinner: bool) -> Result<String, Error> {
     let base_url = get_base_url(config);
-    let token = get_token(config)?;
 
     let request_url = format!("{base_url}{url}");
-    let authorization = format!("Bearer {token}");
     let spinner = maybe_start_spinner(config, spinner);
-    if config.verbose.unwrap_or_default() {
-        println!("GET {request_url}");
-    }
     debug::maybe_print(config, &format!("GET {request_url}"));
-    let response = Client::new()
-        .get(request_url.clone())
-        .header(CONTENT_TYPE, "application/json")
-        .header(AUTHORIZATION, authorization)
-        .timeout(get_timeout(config))
-        .send()
-        .await?;
+    let response = send_with_token(config, "GET", &request_url, |token| {
+        Client::new()
+            .get(request_url.clone())
+            .header(CONTENT_TYPE, "application/json")
+            .header(AUTHORIZATION, format!("Bearer {token}"))
+            .timeout(get_timeout(config))
+    })
+    .await?;
 
     maybe_stop_spinner(spinner);
     handle_response(config, response, "GET", url, json!({})).await
@@ -158,6 +323,11 @@ fn requires_login(status_code: u16) -> bool {
     CODES_REQUIRING_LOGIN.contains(&status_code)
 }
 
+/// Todoist may accept a request that got this status when it is sent again later
+fn send_again_later(status_code: u16) -> bool {
+    requires_login(status_code) || status_code == HTTP_TOO_MANY_REQUESTS || status_code >= 500
+}
+
 /// Returns true if this url can only be accessed by Todoist pro plan users
 fn is_pro_plan_url(url: &str) -> bool {
     PRO_PLAN_URLS.iter().any(|pro_url| url.contains(pro_url))
@@ -182,10 +352,16 @@ async fn handle_response(
             &format!(
                 "Unauthorized or Forbidden response from Todoist\nRun {command} to reauthenticate"
             ),
-        ))
+        )
+        .with_kind(ErrorKind::Auth))
     } else if requires_login(status_code) && is_pro_plan_url(url) {
-        Err(Error::new("reqwest", REMINDERS_PRO_PLAN_MESSAGE))
+        Err(Error::new("reqwest", REMINDERS_PRO_PLAN_MESSAGE).with_kind(ErrorKind::Api))
     } else {
+        let kind = if status_code == HTTP_NOT_FOUND {
+            ErrorKind::NotFound
+        } else {
+            ErrorKind::Api
+        };
         let json_string = response.text().await?;
         Err(Error::new(
             "reqwest",
@@ -196,39 +372,51 @@ async fn handle_response(
             body: {body}
             response: {json_string}",
             ),
+        )
+        .with_kind(kind))
+    }
+}
+
+/// Post JSON to a URL outside of Todoist, such as a webhook, without sending the token
+pub async fn post_json(config: &Config, url: &str, body: &Value) -> Result<(), Error> {
+    debug::maybe_print(config, &format!("POST {url}\nbody: {body}"));
+    let response = Client::new()
+        .post(url)
+        .json(body)
+        .timeout(get_timeout(config))
+        .send()
+        .await?;
+    let status = response.status();
+    if status.is_success() {
+        Ok(())
+    } else {
+        Err(Error::new(
+            "post_json",
+            &format!("POST {url} responded with {status}"),
         ))
     }
 }
 
-fn get_timeout(config: &Config) -> Duration {
-    match config {
-        Config {
-            timeout: None,
-            args: Args { timeout: None, .. },
-            ..
-        } => Duration::from_secs(DEFAULT_TIMEOUT_SECONDS),
-
-        Config {
-            timeout: Some(timeout),
-            args: Args { timeout: None, .. },
-            ..
-        }
-        | Config {
-            timeout: Some(_),
-            args: Args {
-                timeout: Some(timeout),
-                ..
-            },
-            ..
+/// `--timeout` wins, then the entry in `timeouts` for the command being run, then `timeout`, then the default
+pub fn get_timeout(config: &Config) -> Duration {
+    let seconds = config
+        .args
+        .timeout
+        .or_else(|| command_timeout(config))
+        .or(config.timeout)
+        .unwrap_or(DEFAULT_TIMEOUT_SECONDS);
+    Duration::from_secs(seconds)
+}
+
+/// Looks up the command in `timeouts`, falling back to the group it is in so "test" covers "test all"
+fn command_timeout(config: &Config) -> Option<u64> {
+    let timeouts = config.timeouts.as_ref()?;
+    let mut name = config.args.command.as_deref()?;
+    loop {
+        if let Some(seconds) = timeouts.get(name) {
+            return Some(*seconds);
         }
-        | Config {
-            timeout: None,
-            args: Args {
-                timeout: Some(timeout),
-                ..
-            },
-            ..
-        } => Duration::from_secs(timeout.to_owned()),
+        name = name.rsplit_once(' ')?.0;
     }
 }
 
@@ -281,6 +469,154 @@ mod tests {
         assert!(!requires_login(500));
     }
 
+    #[test]
+    fn test_send_again_later() {
+        assert!(send_again_later(HTTP_UNAUTHORIZED));
+        assert!(send_again_later(HTTP_TOO_MANY_REQUESTS));
+        assert!(send_again_later(503));
+        assert!(!send_again_later(400));
+        assert!(!send_again_later(HTTP_NOT_FOUND));
+    }
+
+    #[tokio::test]
+    async fn post_todoist_or_queue_queues_when_todoist_is_unreachable() {
+        // Nothing listens on port 1, so the request fails the same way it does without a connection
+        let config = crate::test::fixtures::config()
+            .await
+            .with_mock_url("http://127.0.0.1:1".to_string());
+
+        let error = post_todoist_or_queue(
+            &config,
+            "/api/v1/tasks/",
+            json!({"content": "milk"}),
+            false,
+            "create task 'milk'",
+        )
+        .await
+        .expect_err("an unreachable Todoist should queue the request");
+        assert_eq!(error.kind, ErrorKind::Network);
+        assert!(error.message.contains("create task 'milk' was queued"));
+
+        let result = queue::flush_queue(&config)
+            .await
+            .expect("flush should succeed");
+        assert_eq!(result.remaining, 1);
+    }
+
+    #[tokio::test]
+    async fn get_todoist_refreshes_token_and_retries_on_unauthorized() {
+        let mut server = mockito::Server::new_async().await;
+        let rejected = server
+            .mock("GET", "/api/v1/user")
+            .match_header("authorization", "Bearer default-token")
+            .with_status(401)
+            .create_async()
+            .await;
+        let refresh = server
+            .mock("POST", "/oauth/access_token")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"access_token": "new-token"}"#)
+            .create_async()
+            .await;
+        let accepted = server
+            .mock("GET", "/api/v1/user")
+            .match_header("authorization", "Bearer new-token")
+            .with_status(200)
+            .with_body("{}")
+            .create_async()
+            .await;
+
+        let temp_dir = tempfile::tempdir().expect("temp dir should be created");
+        let mut config = Config::default_test()
+            .with_path(temp_dir.path().join("request-refresh.cfg"))
+            .with_mock_url(server.url());
+        config.refresh_token = Some("refresh".to_string());
+        let config = config.create().await.expect("config should be created");
+
+        let response = get_todoist(&config, "/api/v1/user", false).await;
+        assert_eq!(response, Ok("{}".to_string()));
+        rejected.assert();
+        refresh.assert();
+        accepted.assert();
+    }
+
+    #[test]
+    fn get_timeout_prefers_flag_then_command_then_config() {
+        let mut config = Config::default_test();
+        assert_eq!(
+            get_timeout(&config),
+            Duration::from_secs(DEFAULT_TIMEOUT_SECONDS)
+        );
+
+        config.timeout = Some(10);
+        config.timeouts = Some(std::collections::HashMap::from([
+            ("test".to_string(), 120),
+            ("task create".to_string(), 5),
+        ]));
+        assert_eq!(get_timeout(&config), Duration::from_secs(10));
+
+        config.args.command = Some("test all".to_string());
+        assert_eq!(get_timeout(&config), Duration::from_secs(120));
+
+        config.args.command = Some("task create".to_string());
+        assert_eq!(get_timeout(&config), Duration::from_secs(5));
+
+        config.args.command = Some("task quick-add".to_string());
+        assert_eq!(get_timeout(&config), Duration::from_secs(10));
+
+        config.args.timeout = Some(60);
+        assert_eq!(get_timeout(&config), Duration::from_secs(60));
+    }
+
+    #[tokio::test]
+    async fn get_todoist_without_refresh_token_asks_to_login() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api/v1/user")
+            .with_status(401)
+            .create_async()
+            .await;
+
+        let config = Config::default_test().with_mock_url(server.url());
+
+        let error = get_todoist(&config, "/api/v1/user", false)
+            .await
+            .expect_err("unauthorized response should fail");
+        assert!(error.message.contains("tod auth login"));
+        assert_eq!(error.kind, ErrorKind::Auth);
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn get_todoist_sets_kind_from_status() {
+        let mut server = mockito::Server::new_async().await;
+        let missing = server
+            .mock("GET", "/api/v1/tasks/missing")
+            .with_status(404)
+            .create_async()
+            .await;
+        let failing = server
+            .mock("GET", "/api/v1/tasks/failing")
+            .with_status(500)
+            .create_async()
+            .await;
+
+        let config = Config::default_test().with_mock_url(server.url());
+
+        let error = get_todoist(&config, "/api/v1/tasks/missing", false)
+            .await
+            .expect_err("not found response should fail");
+        assert_eq!(error.kind, ErrorKind::NotFound);
+
+        let error = get_todoist(&config, "/api/v1/tasks/failing", false)
+            .await
+            .expect_err("server error response should fail");
+        assert_eq!(error.kind, ErrorKind::Api);
+        missing.assert();
+        failing.assert();
+    }
+
     #[test]
     fn test_is_pro_plan_url() {
         assert!(is_pro_plan_url(REMINDERS_URL));
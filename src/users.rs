@@ -1,5 +1,9 @@
+use crate::config::Config;
 use crate::errors::Error;
+use crate::projects::Project;
+use crate::{input, todoist};
 use serde::Deserialize;
+use std::fmt::Display;
 
 #[derive(Deserialize, Debug, PartialEq, Eq)]
 pub struct User {
@@ -20,11 +24,211 @@ pub struct TzInfo {
     pub timezone: String,
 }
 
+/// Someone a shared project has been shared with
+#[derive(Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Collaborator {
+    pub id: String,
+    pub name: String,
+    pub email: String,
+}
+
+impl Display for Collaborator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} <{}>", self.name, self.email)
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CollaboratorResponse {
+    pub results: Vec<Collaborator>,
+    pub next_cursor: Option<String>,
+}
+
+impl CollaboratorResponse {
+    pub fn from_json(json: &str) -> Result<CollaboratorResponse, Error> {
+        let response: CollaboratorResponse = serde_json::from_str(json)?;
+        Ok(response)
+    }
+}
+
+/// Collaborators on a project, always empty for projects that aren't shared
+pub async fn get_collaborators(
+    config: &Config,
+    project: &Project,
+) -> Result<Vec<Collaborator>, Error> {
+    if project.is_shared {
+        todoist::all_collaborators_by_project(config, project, None).await
+    } else {
+        Ok(Vec::new())
+    }
+}
+
+/// Finds the collaborator to assign a task to by name or email, or lets the user pick one when no name is given.
+/// Personal projects have nobody to assign to, so the name is ignored.
+pub async fn select_assignee(
+    config: &Config,
+    project: &Project,
+    name: Option<&str>,
+) -> Result<Option<Collaborator>, Error> {
+    let collaborators = get_collaborators(config, project).await?;
+    if collaborators.is_empty() {
+        if name.is_some() {
+            crate::debug::maybe_print(
+                config,
+                &format!(
+                    "Ignoring assignee, '{}' is not a shared project",
+                    project.name
+                ),
+            );
+        }
+        return Ok(None);
+    }
+
+    match name {
+        Some(name) => find_collaborator(collaborators, name, project).map(Some),
+        None => {
+            let mut options = collaborators
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<String>>();
+            options.insert(0, input::UNASSIGNED.to_string());
+            let selection = input::fuzzy_select(
+                input::ASSIGNEE,
+                options,
+                config.fuzzy_select(),
+                config.mock_select,
+            )?;
+
+            Ok(collaborators
+                .into_iter()
+                .find(|collaborator| collaborator.to_string() == selection))
+        }
+    }
+}
+
+fn find_collaborator(
+    collaborators: Vec<Collaborator>,
+    name: &str,
+    project: &Project,
+) -> Result<Collaborator, Error> {
+    let names = collaborators
+        .iter()
+        .map(|collaborator| collaborator.name.clone())
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    collaborators
+        .into_iter()
+        .find(|collaborator| {
+            collaborator.name.eq_ignore_ascii_case(name)
+                || collaborator.email.eq_ignore_ascii_case(name)
+        })
+        .ok_or_else(|| {
+            Error::new(
+                "assignee",
+                &format!(
+                    "No collaborator named '{name}' in '{}'. Collaborators are: {names}",
+                    project.name
+                ),
+            )
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test::{self, responses::ResponseFromFile};
+    use pretty_assertions::assert_eq;
     use serde_test::{Token, assert_de_tokens};
 
+    fn shared_project() -> Project {
+        Project {
+            is_shared: true,
+            ..test::fixtures::project()
+        }
+    }
+
+    #[tokio::test]
+    async fn select_assignee_should_resolve_name_or_email() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api/v1/projects/123/collaborators?limit=200")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::Collaborators.read().await)
+            .expect(3)
+            .create_async()
+            .await;
+        let config = test::fixtures::config().await.with_mock_url(server.url());
+        let project = shared_project();
+
+        let by_name = select_assignee(&config, &project, Some("alice smith"))
+            .await
+            .expect("assignee should resolve");
+        assert_eq!(by_name.map(|c| c.id), Some("2671355".to_string()));
+
+        let by_email = select_assignee(&config, &project, Some("bob@example.com"))
+            .await
+            .expect("assignee should resolve");
+        assert_eq!(by_email.map(|c| c.id), Some("2671366".to_string()));
+
+        let missing = select_assignee(&config, &project, Some("Carol")).await;
+        assert_eq!(
+            missing,
+            Err(Error::new(
+                "assignee",
+                "No collaborator named 'Carol' in 'myproject'. Collaborators are: Alice Smith, Bob Jones"
+            ))
+        );
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn select_assignee_should_offer_picker_for_shared_projects() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api/v1/projects/123/collaborators?limit=200")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ResponseFromFile::Collaborators.read().await)
+            .expect(2)
+            .create_async()
+            .await;
+        let project = shared_project();
+
+        let config = test::fixtures::config()
+            .await
+            .with_mock_url(server.url())
+            .mock_select(2);
+        let picked = select_assignee(&config, &project, None)
+            .await
+            .expect("assignee should be picked");
+        assert_eq!(picked.map(|c| c.name), Some("Bob Jones".to_string()));
+
+        let config = config.mock_select(0);
+        let unassigned = select_assignee(&config, &project, None)
+            .await
+            .expect("unassigned should be picked");
+        assert_eq!(unassigned, None);
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn select_assignee_should_ignore_personal_projects() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api/v1/projects/123/collaborators?limit=200")
+            .expect(0)
+            .create_async()
+            .await;
+        let config = test::fixtures::config().await.with_mock_url(server.url());
+        let project = test::fixtures::project();
+
+        let result = select_assignee(&config, &project, Some("Alice Smith")).await;
+        assert_eq!(result, Ok(None));
+        mock.assert();
+    }
+
     #[test]
     fn user_deserializes_with_serde_tokens() {
         let expected = User {